@@ -0,0 +1,26 @@
+//! Times parsing the worst-case inputs of `json5::bench` at growing sizes:
+//! the time per byte should stay about the same.
+//!
+//! Run with `cargo bench --features bench`.
+
+extern crate json5;
+
+use std::time::Instant;
+
+fn main() {
+    for size in [10_000, 100_000, 1_000_000, 10_000_000] {
+        for input in json5::bench::inputs(size) {
+            let start = Instant::now();
+            let _ = json5::parse_with_options(&input.json, &input.options);
+            let elapsed = start.elapsed();
+
+            println!(
+                "{:>24} {:>10} bytes {:>12?} {:>8.2} ns/byte",
+                input.name,
+                input.json.len(),
+                elapsed,
+                elapsed.as_nanos() as f64 / input.json.len() as f64
+            );
+        }
+    }
+}
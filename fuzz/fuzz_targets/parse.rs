@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// `json5::parse` must never panic, and whatever it accepts must survive a
+// round trip through `json5::stringify`.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(json) = std::str::from_utf8(data) {
+        if let Ok(value) = json5::parse(json) {
+            json5::parse(&json5::stringify(&value)).unwrap();
+        }
+    }
+});
@@ -0,0 +1,164 @@
+#ifndef JSON5_H
+#define JSON5_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum {
+  JSON5_ERROR_OK,
+  JSON5_ERROR_UNEXPECTED_CHARACTER,
+  JSON5_ERROR_UNEXPECTED_END_OF_JSON,
+  JSON5_ERROR_UNPARSEABLE_NUMBER,
+  JSON5_ERROR_EXCEEDED_MAX_DEPTH,
+  JSON5_ERROR_CUSTOM,
+  JSON5_ERROR_INVALID_UTF8,
+  JSON5_ERROR_NULL_POINTER,
+  JSON5_ERROR_NUMBER_OVERFLOW,
+  JSON5_ERROR_INVALID_ESCAPE,
+  JSON5_ERROR_DUPLICATE_KEY,
+  JSON5_ERROR_CANCELLED,
+  JSON5_ERROR_BUDGET_EXCEEDED,
+  JSON5_ERROR_RESERVED_WORD_KEY,
+} json5_error;
+
+typedef enum {
+  JSON5_TYPE_NULL,
+  JSON5_TYPE_BOOLEAN,
+  JSON5_TYPE_INTEGER,
+  JSON5_TYPE_FLOAT,
+  JSON5_TYPE_STRING,
+  JSON5_TYPE_ARRAY,
+  JSON5_TYPE_OBJECT,
+} json5_type;
+
+typedef struct json5_value json5_value;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses a NUL-terminated UTF-8 string. Returns NULL on failure, in which
+ * case `error` (if not NULL) receives the reason.
+ *
+ * # Safety
+ *
+ * `input` must be NULL or a valid NUL-terminated string, and `error` must be
+ * NULL or valid for writes.
+ */
+json5_value *json5_parse(const char *input, json5_error *error);
+
+/**
+ * Releases a value returned by `json5_parse`.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a pointer returned by `json5_parse` that has not
+ * been freed yet.
+ */
+void json5_free(json5_value *value);
+
+/**
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle.
+ */
+json5_type json5_type_of(const json5_value *value);
+
+/**
+ * Returns false if `value` is not a boolean.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle, and `out` must be valid for
+ * writes.
+ */
+bool json5_get_bool(const json5_value *value, bool *out);
+
+/**
+ * Returns false if `value` is not a number or does not fit in 32 bits.
+ * Floats are truncated toward zero.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle, and `out` must be valid for
+ * writes.
+ */
+bool json5_get_int(const json5_value *value, int32_t *out);
+
+/**
+ * Returns false if `value` is not a number.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle, and `out` must be valid for
+ * writes.
+ */
+bool json5_get_double(const json5_value *value, double *out);
+
+/**
+ * Returns the UTF-8 bytes of a string value, or NULL if `value` is not a
+ * string. The bytes are not NUL-terminated; their length is written to `len`.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle, and `len` must be NULL or
+ * valid for writes.
+ */
+const char *json5_get_string(const json5_value *value, uintptr_t *len);
+
+/**
+ * Returns the number of elements of an array or entries of an object, and 0
+ * for any other value.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle.
+ */
+uintptr_t json5_len(const json5_value *value);
+
+/**
+ * Returns the element at `index`, or NULL if out of range or not an array.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle.
+ */
+const json5_value *json5_array_get(const json5_value *value, uintptr_t index);
+
+/**
+ * Returns the value stored under the NUL-terminated `key`, or NULL if absent
+ * or not an object.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle, and `key` must be NULL or a
+ * valid NUL-terminated string.
+ */
+const json5_value *json5_object_get(const json5_value *value, const char *key);
+
+/**
+ * Returns the value of the `index`-th entry of an object and writes its key
+ * (not NUL-terminated) to `key` and `key_len`. Entry order is unspecified but
+ * stable while the object is alive. Each call walks the entries up to
+ * `index`.
+ *
+ * # Safety
+ *
+ * `value` must be NULL or a live value handle, and `key` and `key_len` must
+ * be NULL or valid for writes.
+ */
+const json5_value *json5_object_entry(const json5_value *value,
+                                      uintptr_t index,
+                                      const char **key,
+                                      uintptr_t *key_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* JSON5_H */
@@ -0,0 +1,134 @@
+//! Compile-time parsing of JSON5 files, a companion to the `json5` crate,
+//! which re-exports it with its `macros` feature.
+
+extern crate proc_macro;
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use json5_core::Value;
+use proc_macro::{Group, Span, TokenStream, TokenTree};
+
+/// Parses a JSON5 file at compile time, expanding to a `json5::StaticValue`
+/// for a constant or static, so that nothing is left to do at run time.
+///
+/// The path is relative to the directory of the `Cargo.toml` of the crate
+/// being compiled. A file that can't be read or parsed fails the build, with
+/// the error's line and column.
+///
+/// ```ignore
+/// static CONFIG: json5::StaticValue = json5::json5_static!("config/default.json5");
+/// ```
+#[proc_macro]
+pub fn json5_static(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(tokens) => tokens,
+        Err((message, span)) => compile_error(&message, span),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, (String, Span)> {
+    let span = match input.clone().into_iter().next() {
+        Some(token) => token.span(),
+        None => return Err(("expected a path".into(), Span::call_site())),
+    };
+    // Literals passed through `macro_rules!` arrive wrapped in a group,
+    // which `syn` sees through.
+    let literal: syn::LitStr =
+        syn::parse(input).map_err(|_| ("expected a string literal".to_string(), span))?;
+
+    let mut path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    path.push(literal.value());
+    let name = path.display().to_string();
+
+    let text =
+        fs::read_to_string(&path).map_err(|e| (format!("couldn't read {}: {}", name, e), span))?;
+    let value = json5_core::parse_named(&text, &name).map_err(|e| (e.to_string(), span))?;
+
+    // `include_str!` makes the crate rebuild when the file changes.
+    let mut expanded = format!("{{ const _: &str = include_str!({:?}); ", name);
+    write_value(&mut expanded, &value);
+    expanded.push_str(" }");
+    expanded
+        .parse()
+        .map_err(|_| ("couldn't generate code for the value".into(), span))
+}
+
+// Writes `value` as a constant expression of type `json5::StaticValue`.
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Boolean(b) => {
+            let _ = write!(out, "::json5::StaticValue::Boolean({})", b);
+        }
+        Value::Integer(i) => {
+            let _ = write!(out, "::json5::StaticValue::Integer({})", i);
+        }
+        Value::Float(f) if f.is_nan() => out.push_str("::json5::StaticValue::Float(f64::NAN)"),
+        Value::Float(f) if f.is_infinite() => {
+            let literal = if *f > 0.0 {
+                "f64::INFINITY"
+            } else {
+                "f64::NEG_INFINITY"
+            };
+            let _ = write!(out, "::json5::StaticValue::Float({})", literal);
+        }
+        // Debug formatting reads back as the same float.
+        Value::Float(f) => {
+            let _ = write!(out, "::json5::StaticValue::Float({:?}f64)", f);
+        }
+        Value::String(s) => {
+            let _ = write!(out, "::json5::StaticValue::String({:?})", s);
+        }
+        Value::Array(v) => {
+            out.push_str("::json5::StaticValue::Array(&[");
+            for item in v {
+                write_value(out, item);
+                out.push_str(", ");
+            }
+            out.push_str("])");
+        }
+        Value::Object(m) => {
+            // Sorted, so that the output doesn't depend on the map's order.
+            let mut entries: Vec<_> = m.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+
+            out.push_str("::json5::StaticValue::Object(&[");
+            for (key, item) in entries {
+                let _ = write!(out, "({:?}, ", key);
+                write_value(out, item);
+                out.push_str("), ");
+            }
+            out.push_str("])");
+        }
+        // Parsing gives none of the others.
+        _ => out.push_str("::json5::StaticValue::Null"),
+    }
+}
+
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let tokens: TokenStream = format!("compile_error!({:?})", message).parse().unwrap();
+    tokens
+        .into_iter()
+        .map(|token| respan(token, span))
+        .collect()
+}
+
+fn respan(token: TokenTree, span: Span) -> TokenTree {
+    match token {
+        TokenTree::Group(group) => {
+            let stream = group
+                .stream()
+                .into_iter()
+                .map(|t| respan(t, span))
+                .collect();
+            let mut group = Group::new(group.delimiter(), stream);
+            group.set_span(span);
+            TokenTree::Group(group)
+        }
+        mut token => {
+            token.set_span(span);
+            token
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Refactorings that rewrite a document's text rather than its value, so
+//! that comments and formatting are kept.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::{Error, ErrorKind};
+use crate::lexer::{tokenize, SyntaxKind};
+use crate::pointer::Pointer;
+use crate::tree::{self, Node, Tree};
+
+/// Sorts the members of the object at `pointer` by key.
+/// Comments on the lines before a member and after it on its line move
+/// with it.
+pub fn sort_keys(json: &str, pointer: &Pointer) -> Result<String, Error> {
+    crate::parse(json)?;
+
+    let tokens = tokenize(json);
+    let mut tree = Tree::build(json, &tokens);
+    let not_found = || Error::new(ErrorKind::Custom(format!("no object at {}", pointer)));
+
+    let mut node = &mut tree.root;
+    for token in pointer.tokens() {
+        node = &mut node.entry_mut(token).ok_or_else(not_found)?.value;
+    }
+    if !node.is_object() {
+        return Err(not_found());
+    }
+
+    if let Some(entries) = node.entries_mut() {
+        let slots: Vec<&str> = entries.iter().map(|e| e.slot).collect();
+        let trailing_comma = entries.last().is_some_and(|e| e.comma);
+        // A stable sort, so duplicate keys keep their order.
+        entries.sort_by_cached_key(|e| e.name());
+        tree::rearrange(entries, &slots, trailing_comma);
+    }
+    Ok(tree.render())
+}
+
+/// Rewrites single-quoted strings, keys included, with double quotes.
+pub fn to_double_quotes(json: &str) -> Result<String, Error> {
+    crate::parse(json)?;
+
+    let mut out = String::with_capacity(json.len());
+    for token in tokenize(json) {
+        let text = token.text(json);
+        if token.kind != SyntaxKind::String || !text.starts_with('\'') {
+            out.push_str(text);
+            continue;
+        }
+
+        out.push('"');
+        let mut chars = text[1..text.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => match chars.next() {
+                    Some('\'') => out.push('\''),
+                    Some(escaped) => {
+                        out.push('\\');
+                        out.push(escaped);
+                    }
+                    None => out.push('\\'),
+                },
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+    Ok(out)
+}
+
+/// Removes every object member whose key appears again later in the same
+/// object, leaving what parsing would have kept.
+pub fn remove_duplicate_keys(json: &str) -> Result<String, Error> {
+    crate::parse(json)?;
+
+    let tokens = tokenize(json);
+    let mut tree = Tree::build(json, &tokens);
+    remove_duplicates(&mut tree.root);
+    Ok(tree.render())
+}
+
+fn remove_duplicates(node: &mut Node) {
+    let is_object = node.is_object();
+    let entries = match node.entries_mut() {
+        Some(entries) => entries,
+        None => return,
+    };
+
+    if is_object {
+        let slots: Vec<&str> = entries.iter().map(|e| e.slot).collect();
+        let trailing_comma = entries.last().is_some_and(|e| e.comma);
+        let names: Vec<_> = entries.iter().map(|e| e.name()).collect();
+
+        let mut i = 0;
+        entries.retain(|_| {
+            i += 1;
+            !names[i..].contains(&names[i - 1])
+        });
+        tree::rearrange(entries, &slots, trailing_comma);
+    }
+    for entry in entries {
+        remove_duplicates(&mut entry.value);
+    }
+}
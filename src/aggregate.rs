@@ -0,0 +1,86 @@
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use crate::value::Value;
+
+/// What the aggregates of `Value`, such as `sum`, do with elements that
+/// aren't numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonNumeric {
+    /// Leave them out.
+    Skip,
+    /// Give no result.
+    Reject,
+}
+
+impl Value {
+    /// The sum of the numbers in this array: an integer if they all are and
+    /// it fits, otherwise a float. Zero if there are none, `None` unless
+    /// this is an array.
+    pub fn sum(&self, non_numeric: NonNumeric) -> Option<Value> {
+        let mut integer: Option<i64> = Some(0);
+        let mut float = 0.0;
+        for number in self.numbers(non_numeric)? {
+            match number {
+                Value::Integer(i) => integer = integer.map(|sum| sum + i64::from(*i)),
+                _ => integer = None,
+            }
+            float += number.to_f64().unwrap_or_default();
+        }
+        match integer.and_then(|sum| i32::try_from(sum).ok()) {
+            Some(sum) => Some(Value::Integer(sum)),
+            None => Some(Value::Float(float)),
+        }
+    }
+
+    /// The smallest number in this array, the first of equal ones. `None`
+    /// if there are none.
+    pub fn min(&self, non_numeric: NonNumeric) -> Option<&Value> {
+        self.extreme(non_numeric, Ordering::Less)
+    }
+
+    /// The largest number in this array, the first of equal ones. `None` if
+    /// there are none.
+    pub fn max(&self, non_numeric: NonNumeric) -> Option<&Value> {
+        self.extreme(non_numeric, Ordering::Greater)
+    }
+
+    /// The arithmetic mean of the numbers in this array. `None` if there are
+    /// none.
+    pub fn mean(&self, non_numeric: NonNumeric) -> Option<f64> {
+        let (count, sum) = self
+            .numbers(non_numeric)?
+            .filter_map(Value::to_f64)
+            .fold((0, 0.0), |(count, sum), f| (count + 1, sum + f));
+        if count == 0 {
+            return None;
+        }
+        Some(sum / f64::from(count))
+    }
+
+    fn extreme(&self, non_numeric: NonNumeric, wanted: Ordering) -> Option<&Value> {
+        self.numbers(non_numeric)?
+            .fold(None, |extreme, number| match extreme {
+                Some(extreme) if compare(number, extreme) != Some(wanted) => Some(extreme),
+                _ => Some(number),
+            })
+    }
+
+    // The numbers in this array, unless it isn't one or `non_numeric` says
+    // to reject it.
+    fn numbers(&self, non_numeric: NonNumeric) -> Option<impl Iterator<Item = &Value>> {
+        let elements = self.to_vec()?;
+        let is_number = |value: &&Value| matches!(value, Value::Integer(_) | Value::Float(_));
+        if non_numeric == NonNumeric::Reject && !elements.iter().all(|value| is_number(&value)) {
+            return None;
+        }
+        Some(elements.iter().filter(is_number))
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+        _ => a.to_f64()?.partial_cmp(&b.to_f64()?),
+    }
+}
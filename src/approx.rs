@@ -0,0 +1,101 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::pointer::Pointer;
+use crate::value::Value;
+
+/// How lenient `Value::approx_eq` is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EqOptions {
+    /// Numbers this close are equal. Integers and floats are compared as
+    /// numbers, so that `1` and `1.0` are equal whatever the tolerance.
+    pub epsilon: f64,
+    /// Floats at most this many representable values apart are equal.
+    pub max_ulps: u64,
+    /// NaN equals NaN.
+    pub nan_equal: bool,
+    /// Arrays are equal if their elements can be paired up, in any order.
+    pub unordered_arrays: bool,
+    /// Values at these pointers aren't compared, and may be missing on
+    /// either side. Pointers into arrays compared in any order use the
+    /// positions of the elements on the left.
+    pub ignored: Vec<Pointer>,
+}
+
+impl Value {
+    /// Whether this value equals `other` within the tolerances of `options`.
+    pub fn approx_eq(&self, other: &Value, options: &EqOptions) -> bool {
+        approx_eq(self, other, options, &mut Vec::new())
+    }
+}
+
+fn approx_eq(a: &Value, b: &Value, options: &EqOptions, path: &mut Vec<String>) -> bool {
+    if options.ignored.iter().any(|p| p.tokens() == &path[..]) {
+        return true;
+    }
+    match (a, b) {
+        (Value::Custom(a), b) => approx_eq(&a.to_value(), b, options, path),
+        (a, Value::Custom(b)) => approx_eq(a, &b.to_value(), options, path),
+        (Value::Integer(a), Value::Integer(b)) if a == b => true,
+        (Value::Integer(_) | Value::Float(_), Value::Integer(_) | Value::Float(_)) => {
+            match (a.to_f64(), b.to_f64()) {
+                (Some(a), Some(b)) => floats_eq(a, b, options),
+                _ => false,
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            if options.unordered_arrays {
+                return unordered_eq(a, b, options, path);
+            }
+            a.iter().zip(b).enumerate().all(|(i, (a, b))| {
+                path.push(i.to_string());
+                let eq = approx_eq(a, b, options, path);
+                path.pop();
+                eq
+            })
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys = a.keys().chain(b.keys().filter(|key| !a.contains_key(*key)));
+            keys.all(|key| {
+                path.push(key.clone());
+                let eq = match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => approx_eq(a, b, options, path),
+                    _ => options.ignored.iter().any(|p| p.tokens() == &path[..]),
+                };
+                path.pop();
+                eq
+            })
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn floats_eq(a: f64, b: f64, options: &EqOptions) -> bool {
+    if a.is_nan() || b.is_nan() {
+        return options.nan_equal && a.is_nan() && b.is_nan();
+    }
+    if a == b || (a - b).abs() <= options.epsilon {
+        return true;
+    }
+    // Floats of the same sign are as many representable values apart as
+    // their bits are.
+    a.is_sign_negative() == b.is_sign_negative()
+        && a.to_bits().abs_diff(b.to_bits()) <= options.max_ulps
+}
+
+// Pairs each element of `a` with the first element of `b` left that it
+// equals.
+fn unordered_eq(a: &[Value], b: &[Value], options: &EqOptions, path: &mut Vec<String>) -> bool {
+    let mut paired = vec![false; b.len()];
+    a.iter().enumerate().all(|(i, a)| {
+        path.push(i.to_string());
+        let j = (0..b.len()).find(|&j| !paired[j] && approx_eq(a, &b[j], options, path));
+        path.pop();
+        match j {
+            Some(j) => paired[j] = true,
+            None => return false,
+        }
+        true
+    })
+}
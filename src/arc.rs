@@ -0,0 +1,144 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use crate::map::Map;
+use crate::value::Value;
+
+/// A `Value` whose strings, arrays and objects are reference counted.
+///
+/// Cloning is O(1) regardless of size, and clones can be shared across
+/// threads. The `make_*_mut` methods copy a container only if it is shared.
+#[derive(Clone, Debug)]
+pub enum ArcValue {
+    Null,
+    Boolean(bool),
+    Integer(i32),
+    Float(f64),
+    String(Arc<String>),
+    Array(Arc<Vec<ArcValue>>),
+    Object(Arc<Map<String, ArcValue>>),
+}
+
+impl PartialEq for ArcValue {
+    fn eq(&self, other: &Self) -> bool {
+        match self {
+            Self::Null => matches!(other, Self::Null),
+            Self::Boolean(b1) => match other {
+                Self::Boolean(b2) => b1 == b2,
+                _ => false,
+            },
+            Self::Integer(i1) => match other {
+                Self::Integer(i2) => i1 == i2,
+                _ => false,
+            },
+            Self::Float(f1) => match other {
+                Self::Float(f2) => f1 == f2,
+                _ => false,
+            },
+            Self::String(s1) => match other {
+                Self::String(s2) => s1 == s2,
+                _ => false,
+            },
+            Self::Array(v1) => match other {
+                Self::Array(v2) => v1 == v2,
+                _ => false,
+            },
+            Self::Object(m1) => match other {
+                Self::Object(m2) => m1 == m2,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl From<Value> for ArcValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Boolean(b) => Self::Boolean(b),
+            Value::Integer(i) => Self::Integer(i),
+            Value::Float(f) => Self::Float(f),
+            Value::String(s) => Self::String(Arc::new(s)),
+            Value::Array(v) => Self::Array(Arc::new(v.into_iter().map(Self::from).collect())),
+            Value::Object(m) => Self::Object(Arc::new(
+                m.into_iter().map(|(k, v)| (k, Self::from(v))).collect(),
+            )),
+            Value::Custom(c) => Self::from(c.to_value()),
+        }
+    }
+}
+
+impl From<&ArcValue> for Value {
+    fn from(value: &ArcValue) -> Self {
+        match value {
+            ArcValue::Null => Self::Null,
+            ArcValue::Boolean(b) => Self::Boolean(*b),
+            ArcValue::Integer(i) => Self::Integer(*i),
+            ArcValue::Float(f) => Self::Float(*f),
+            ArcValue::String(s) => Self::String(String::clone(s)),
+            ArcValue::Array(v) => Self::Array(v.iter().map(Self::from).collect()),
+            ArcValue::Object(m) => {
+                Self::Object(m.iter().map(|(k, v)| (k.clone(), Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl Index<usize> for ArcValue {
+    type Output = ArcValue;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        match self {
+            Self::Array(vec) => vec.get(i).unwrap_or(&Self::Null),
+            _ => &Self::Null,
+        }
+    }
+}
+
+impl Index<&str> for ArcValue {
+    type Output = ArcValue;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            Self::Object(map) => map.get(key).unwrap_or(&Self::Null),
+            _ => &Self::Null,
+        }
+    }
+}
+
+impl ArcValue {
+    pub fn make_string_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::String(s) => Some(Arc::make_mut(s)),
+            _ => None,
+        }
+    }
+
+    pub fn make_array_mut(&mut self) -> Option<&mut Vec<ArcValue>> {
+        match self {
+            Self::Array(v) => Some(Arc::make_mut(v)),
+            _ => None,
+        }
+    }
+
+    pub fn make_object_mut(&mut self) -> Option<&mut Map<String, ArcValue>> {
+        match self {
+            Self::Object(m) => Some(Arc::make_mut(m)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if both values are the same shared container, or equal
+    /// scalars. Useful to tell whether a clone has been modified.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::String(s1), Self::String(s2)) => Arc::ptr_eq(s1, s2),
+            (Self::Array(v1), Self::Array(v2)) => Arc::ptr_eq(v1, v2),
+            (Self::Object(m1), Self::Object(m2)) => Arc::ptr_eq(m1, m2),
+            (Self::String(_), _) | (Self::Array(_), _) | (Self::Object(_), _) => false,
+            _ => self == other,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Lookups and updates of many pointers in one traversal: the pointers are
+//! sorted, so that those sharing a prefix are followed through it once.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::pointer::{self, Pointer};
+use crate::value::Value;
+
+impl Value {
+    /// The values at `pointers`, in the same order, `None` for those that
+    /// don't exist. Like `pointer` for each, but going through every array
+    /// and object once however many pointers lead through it.
+    pub fn get_many(&self, pointers: &[Pointer]) -> Vec<Option<&Value>> {
+        let mut order: Vec<usize> = (0..pointers.len()).collect();
+        order.sort_by(|&a, &b| pointers[a].tokens().cmp(pointers[b].tokens()));
+
+        let mut values = vec![None; pointers.len()];
+        get_sorted(self, pointers, &order, 0, &mut values);
+        values
+    }
+
+    /// Sets the values at the pointers of `entries`, in one traversal, and
+    /// tells for each, in the same order, whether it was set. A missing key
+    /// is added to its object, but arrays only have their elements
+    /// replaced. A value is set before those at pointers within it, and
+    /// when a pointer appears more than once, the last value is the one set.
+    pub fn set_many<I>(&mut self, entries: I) -> Vec<bool>
+    where
+        I: IntoIterator<Item = (Pointer, Value)>,
+    {
+        let mut entries: Vec<Entry> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, (pointer, value))| (i, pointer, Some(value)))
+            .collect();
+        // A stable sort, so that repeated pointers keep their order.
+        entries.sort_by(|a, b| a.1.tokens().cmp(b.1.tokens()));
+
+        let mut set = vec![false; entries.len()];
+        set_sorted(self, &mut entries, 0, &mut set);
+        set
+    }
+}
+
+// The position in the input, the pointer, and the value until it is set.
+type Entry = (usize, Pointer, Option<Value>);
+
+// Resolves the pointers at `order`, which are sorted and share their first
+// `depth` tokens, leading to `value`.
+fn get_sorted<'a>(
+    value: &'a Value,
+    pointers: &[Pointer],
+    order: &[usize],
+    depth: usize,
+    values: &mut [Option<&'a Value>],
+) {
+    let mut i = 0;
+    while let Some(&first) = order.get(i) {
+        let tokens = pointers[first].tokens();
+        let token = match tokens.get(depth) {
+            Some(token) => token,
+            None => {
+                values[first] = Some(value);
+                i += 1;
+                continue;
+            }
+        };
+        let end = i + order[i..]
+            .iter()
+            .take_while(|&&j| pointers[j].tokens()[depth] == *token)
+            .count();
+        let child = match value {
+            Value::Array(v) => pointer::parse_index(token).and_then(|index| v.get(index)),
+            Value::Object(m) => m.get(token),
+            _ => None,
+        };
+        if let Some(child) = child {
+            get_sorted(child, pointers, &order[i..end], depth + 1, values);
+        }
+        i = end;
+    }
+}
+
+// As `get_sorted`, setting values.
+fn set_sorted(value: &mut Value, entries: &mut [Entry], depth: usize, set: &mut [bool]) {
+    let mut i = 0;
+    while i < entries.len() {
+        let tokens = entries[i].1.tokens();
+        let token = match tokens.get(depth) {
+            Some(token) => token,
+            None => {
+                let (position, _, new) = &mut entries[i];
+                *value = new.take().unwrap_or(Value::Null);
+                set[*position] = true;
+                i += 1;
+                continue;
+            }
+        };
+        // The first pointer through this token is the shortest, so it sets
+        // the key itself if any does.
+        let adds_key = tokens.len() == depth + 1;
+        let end = i + entries[i..]
+            .iter()
+            .take_while(|(_, pointer, _)| pointer.tokens()[depth] == *token)
+            .count();
+        let child = match value {
+            Value::Array(v) => pointer::parse_index(token).and_then(move |index| v.get_mut(index)),
+            Value::Object(m) if adds_key => Some(m.entry(token.clone()).or_insert(Value::Null)),
+            Value::Object(m) => m.get_mut(token.as_str()),
+            _ => None,
+        };
+        if let Some(child) = child {
+            set_sorted(child, &mut entries[i..end], depth + 1, set);
+        }
+        i = end;
+    }
+}
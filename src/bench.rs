@@ -0,0 +1,87 @@
+//! Worst-case inputs for the parser, and a measure of the work it does on
+//! them, to show that parsing takes time linear in the size of the input.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::options::ParseOptions;
+use crate::parser::{Parser, MAX_DEPTH};
+
+/// An input meant to be hard to parse.
+pub struct Input {
+    pub name: &'static str,
+    pub json: String,
+    pub options: ParseOptions,
+}
+
+/// Inputs of about `size` bytes each, valid or not, for the shapes that
+/// most often make parsers slow: long tokens, many tiny values, deep
+/// nesting, and what makes the parser backtrack.
+pub fn inputs(size: usize) -> Vec<Input> {
+    let json5 = ParseOptions::default();
+    let hjson = ParseOptions::hjson();
+    let concatenation = ParseOptions {
+        allow_string_concatenation: true,
+        ..ParseOptions::default()
+    };
+    let repeat = |unit: &str, count: usize| unit.repeat(count / unit.len().max(1));
+
+    let mut inputs = Vec::new();
+    let mut add = |name, json: String, options| {
+        inputs.push(Input {
+            name,
+            json,
+            options,
+        })
+    };
+
+    // As deep as allowed, in an array.
+    let depth = MAX_DEPTH / 2 - 1;
+    let nested = format!("{}0{},", "[{a:".repeat(depth), "}]".repeat(depth));
+
+    add("signs", format!("1{}", repeat("+-", size)), json5);
+    add("exponent signs", format!("1e{}", repeat("-", size)), json5);
+    add("digits", repeat("9", size), json5);
+    add("long string", format!("'{}'", repeat("a", size)), json5);
+    add("escapes", format!("'{}'", repeat("\\u0041", size)), json5);
+    add(
+        "tiny strings",
+        format!("[{}'']", repeat("'',", size)),
+        json5,
+    );
+    add("tiny numbers", format!("[{}0]", repeat("0,", size)), json5);
+    add(
+        "duplicate keys",
+        format!("{{{}a:0}}", repeat("a:0,", size)),
+        json5,
+    );
+    add("comments", format!("{}0", repeat("/**/", size)), json5);
+    add(
+        "deep nesting",
+        format!("[{}0]", repeat(&nested, size)),
+        json5,
+    );
+    add("too deep", repeat("[", size), json5);
+    let alternating = repeat("[],{},[{}],{a:[]},", size);
+    add("alternating", format!("[{}0]", alternating), json5);
+    let lines = repeat("1 x\ntrue y\n", size);
+    add("quoteless lines", format!("[\n{}]", lines), hjson);
+    let joined = repeat("'a' + /**/ ", size);
+    add("concatenation", format!("[{}'']", joined), concatenation);
+    let comments = repeat("/**/", size);
+    add(
+        "comments after strings",
+        format!("['a' {} 1]", comments),
+        concatenation,
+    );
+    inputs
+}
+
+/// How many characters the parser reads parsing `json`, whether or not it
+/// parses, counting those read again after backtracking.
+pub fn steps(json: &str, options: &ParseOptions) -> usize {
+    let mut parser = Parser::with_options(json, *options);
+    let _ = crate::parse_document(&mut parser);
+    parser.steps
+}
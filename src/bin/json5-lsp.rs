@@ -0,0 +1,506 @@
+//! A Language Server Protocol server for JSON5, speaking JSON-RPC over
+//! standard input and output.
+//!
+//! Documents are synchronized incrementally and kept as `json5::Document`s,
+//! so an edit only reparses the array or object it falls in. The server publishes
+//! parse errors and warnings as diagnostics, formats documents with
+//! `json5::reformat`, which keeps comments, shows the value under the
+//! cursor on hover, provides semantic tokens for highlighting, and offers
+//! code actions to sort an object's keys, convert to double quotes and
+//! remove duplicate keys.
+//!
+//! A document whose top-level object has a `$schema` member, a path or
+//! `file:` URI relative to the document, gets completion of keys and values
+//! from that JSON Schema.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use json5::{CompletionKind, Document, Pointer, Position, SemanticKind, Span, Value, WarningKind};
+use serde_json::{json, Value as Json};
+
+// Hover shows values up to this many characters long, cut short beyond.
+const MAX_HOVER_LEN: usize = 400;
+
+// The semantic token types we report, indexed by `token_type`.
+const TOKEN_TYPES: [&str; 6] = [
+    "property", "string", "number", "keyword", "comment", "operator",
+];
+
+fn main() -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut input = stdin.lock();
+    let mut server = Server {
+        out: stdout.lock(),
+        documents: HashMap::new(),
+        schemas: HashMap::new(),
+    };
+
+    while let Some(message) = read_message(&mut input)? {
+        if !server.handle(&message)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// Reads a message framed by a `Content-Length` header, or `None` at the end
+// of the input.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Json>> {
+    let mut length = None;
+
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+struct Server<W> {
+    out: W,
+    documents: HashMap<String, Document>,
+    // The schema of each document, by where `$schema` says it is, kept while
+    // the document is being edited and doesn't parse.
+    schemas: HashMap<String, (String, Value)>,
+}
+
+impl<W: Write> Server<W> {
+    fn send(&mut self, message: Json) -> io::Result<()> {
+        let body = message.to_string();
+        write!(self.out, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        self.out.flush()
+    }
+
+    // Returns whether to keep going.
+    fn handle(&mut self, message: &Json) -> io::Result<bool> {
+        let method = match message["method"].as_str() {
+            Some(method) => method,
+            // A response to a request of ours; we make none.
+            None => return Ok(true),
+        };
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        let id = match message.get("id") {
+            Some(id) => id,
+            None => {
+                match method {
+                    "textDocument/didOpen" => {
+                        let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                        self.documents
+                            .insert(uri.into(), Document::new(text.into()));
+                        self.publish_diagnostics(uri)?;
+                    }
+                    "textDocument/didChange" => {
+                        if let Some(document) = self.documents.get_mut(uri) {
+                            let changes = params["contentChanges"].as_array();
+                            for change in changes.into_iter().flatten() {
+                                apply_change(document, change);
+                            }
+                            self.publish_diagnostics(uri)?;
+                        }
+                    }
+                    "textDocument/didClose" => {
+                        self.documents.remove(uri);
+                        self.schemas.remove(uri);
+                        self.send(json!({
+                            "jsonrpc": "2.0",
+                            "method": "textDocument/publishDiagnostics",
+                            "params": { "uri": uri, "diagnostics": [] },
+                        }))?;
+                    }
+                    "exit" => return Ok(false),
+                    _ => (),
+                }
+                return Ok(true);
+            }
+        };
+
+        let document = self.documents.get(uri);
+        let result = match (method, document) {
+            ("initialize", _) => json!({
+                "capabilities": {
+                    "textDocumentSync": 2,
+                    "hoverProvider": true,
+                    "documentFormattingProvider": true,
+                    "completionProvider": { "triggerCharacters": ["\"", "'"] },
+                    "codeActionProvider": true,
+                    "semanticTokensProvider": {
+                        "legend": { "tokenTypes": TOKEN_TYPES, "tokenModifiers": [] },
+                        "full": true,
+                    },
+                },
+                "serverInfo": { "name": "json5-lsp" },
+            }),
+            ("shutdown", _) => Json::Null,
+            ("textDocument/hover", Some(document)) => hover(document, &params["position"]),
+            ("textDocument/formatting", Some(document)) => format(document.text()),
+            ("textDocument/semanticTokens/full", Some(document)) => {
+                semantic_tokens(document.text())
+            }
+            ("textDocument/codeAction", Some(document)) => {
+                code_actions(uri, document, &params["range"]["start"])
+            }
+            ("textDocument/completion", Some(document)) => match self.schemas.get(uri) {
+                Some((_, schema)) => complete(document, &params["position"], schema),
+                None => json!([]),
+            },
+            ("textDocument/hover", None)
+            | ("textDocument/formatting", None)
+            | ("textDocument/semanticTokens/full", None)
+            | ("textDocument/completion", None)
+            | ("textDocument/codeAction", None) => Json::Null,
+            _ => {
+                self.send(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32601, "message": format!("unsupported method {}", method) },
+                }))?;
+                return Ok(true);
+            }
+        };
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))?;
+        Ok(true)
+    }
+
+    // Loads the schema the document names, if it changed.
+    fn update_schema(&mut self, uri: &str) {
+        let location = match self.documents[uri].value() {
+            Some(value) => match value["$schema"].to_string() {
+                Some(location) => location.clone(),
+                None => {
+                    self.schemas.remove(uri);
+                    return;
+                }
+            },
+            None => return,
+        };
+        if self.schemas.get(uri).is_some_and(|(l, _)| *l == location) {
+            return;
+        }
+
+        let path = location.strip_prefix("file://").unwrap_or(&location);
+        let base = uri
+            .strip_prefix("file://")
+            .map(Path::new)
+            .and_then(Path::parent);
+        let path = match base {
+            Some(base) => base.join(path),
+            None => Path::new(path).to_path_buf(),
+        };
+        let schema = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| json5::parse(&text).ok());
+        match schema {
+            Some(schema) => self.schemas.insert(uri.into(), (location, schema)),
+            None => self.schemas.remove(uri),
+        };
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> io::Result<()> {
+        self.update_schema(uri);
+        let document = &self.documents[uri];
+        let text = document.text();
+        let diagnostics: Vec<Json> = match document.error() {
+            Some(error) => vec![diagnostic(text, error.span(), 1, &error.kind().to_string())],
+            None => document
+                .warnings()
+                .iter()
+                .map(|warning| {
+                    // The range already says where.
+                    let message = warning.to_string();
+                    let message = message.rsplit_once(", line ").map_or(&*message, |m| m.0);
+                    diagnostic(text, Some(warning.span), 2, message)
+                })
+                .collect(),
+        };
+
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        });
+        self.send(message)
+    }
+}
+
+fn diagnostic(text: &str, span: Option<Span>, severity: u8, message: &str) -> Json {
+    let range = match span {
+        Some(span) => range(text, span),
+        None => json!({
+            "start": { "line": 0, "character": 0 },
+            "end": { "line": 0, "character": 0 },
+        }),
+    };
+    json!({
+        "range": range,
+        "severity": severity,
+        "source": "json5",
+        "message": message,
+    })
+}
+
+// Replaces the range a change gives, or the whole document without one.
+fn apply_change(document: &mut Document, change: &Json) {
+    let text = change["text"].as_str().unwrap_or_default();
+    let range = &change["range"];
+
+    if range.is_null() {
+        *document = Document::new(text.into());
+        return;
+    }
+    let start = offset(document.text(), &range["start"]);
+    let end = offset(document.text(), &range["end"]);
+    if let (Some(start), Some(end)) = (start, end) {
+        if start <= end {
+            let _ = document.apply_edit(start..end, text);
+        }
+    }
+}
+
+fn complete(document: &Document, position: &Json, schema: &Value) -> Json {
+    let text = document.text();
+    let offset = match offset(text, position) {
+        Some(offset) => offset,
+        None => return json!([]),
+    };
+
+    let items: Vec<Json> = json5::complete(text, offset, schema)
+        .into_iter()
+        .map(|completion| {
+            let kind = match completion.kind {
+                CompletionKind::Key => 10,
+                CompletionKind::Value => 12,
+            };
+            let mut item = json!({
+                "label": completion.label,
+                "kind": kind,
+                "insertText": completion.insert_text,
+            });
+            if let Some(documentation) = completion.documentation {
+                item["documentation"] = documentation.into();
+            }
+            if let Some(span) = completion.replace {
+                item["textEdit"] = json!({
+                    "range": range(text, span),
+                    "newText": completion.insert_text,
+                });
+            }
+            item
+        })
+        .collect();
+    json!(items)
+}
+
+fn hover(document: &Document, position: &Json) -> Json {
+    let text = document.text();
+    let found = offset(text, position).and_then(|offset| document.value_at(offset));
+    let (pointer, span) = match found {
+        Some(found) => found,
+        None => return Json::Null,
+    };
+    let item = match document.value() {
+        Some(value) => value.at(pointer.tokens()),
+        None => return Json::Null,
+    };
+
+    let mut shown = json5::stringify(item);
+    if let Some((cut, _)) = shown.char_indices().nth(MAX_HOVER_LEN) {
+        shown.truncate(cut);
+        shown.push('…');
+    }
+    let title = if pointer.is_root() {
+        "/".into()
+    } else {
+        pointer.to_string()
+    };
+    json!({
+        "contents": {
+            "kind": "markdown",
+            "value": format!("`{}` ({})\n\n```json5\n{}\n```", title, item.type_name(), shown),
+        },
+        "range": range(text, span),
+    })
+}
+
+fn format(text: &str) -> Json {
+    match json5::reformat(text) {
+        Ok(formatted) if formatted != text => json!([replace_all(text, formatted)]),
+        _ => json!([]),
+    }
+}
+
+fn code_actions(uri: &str, document: &Document, position: &Json) -> Json {
+    let text = document.text();
+    let mut actions = Vec::new();
+    let mut action = |title: &str, kind: &str, new_text: String| {
+        if new_text != text {
+            actions.push(json!({
+                "title": title,
+                "kind": kind,
+                "edit": { "changes": { uri: [replace_all(text, new_text)] } },
+            }));
+        }
+    };
+
+    // The innermost object at the cursor.
+    let found = offset(text, position).and_then(|offset| document.value_at(offset));
+    if let (Some((pointer, _)), Some(value)) = (found, document.value()) {
+        let mut tokens = pointer.tokens().to_vec();
+        while value.at(&tokens).to_map().is_none() && tokens.pop().is_some() {}
+        if value.at(&tokens).to_map().is_some() {
+            let pointer: Pointer = tokens.iter().collect();
+            if let Ok(sorted) = json5::sort_keys(text, &pointer) {
+                action("Sort keys", "refactor.rewrite", sorted);
+            }
+        }
+    }
+    if let Ok(converted) = json5::to_double_quotes(text) {
+        action("Convert to double quotes", "refactor.rewrite", converted);
+    }
+    let duplicates = document
+        .warnings()
+        .iter()
+        .any(|w| matches!(w.kind, WarningKind::DuplicateKey(_)));
+    if duplicates {
+        if let Ok(deduplicated) = json5::remove_duplicate_keys(text) {
+            action("Remove duplicate keys", "quickfix", deduplicated);
+        }
+    }
+    json!(actions)
+}
+
+// An edit replacing the whole of `text`.
+fn replace_all(text: &str, new_text: String) -> Json {
+    let line_start = line_starts(text).last().unwrap_or(0);
+    let end = Position {
+        offset: text.len(),
+        line: line_starts(text).count() + 1,
+        column: text.len() - line_start + 1,
+    };
+    let whole = Span {
+        start: Position::default(),
+        end,
+    };
+    json!({ "range": range(text, whole), "newText": new_text })
+}
+
+// Tokens are encoded as runs of five integers: the line and start character,
+// each relative to the previous token, the length, the type and modifiers.
+// Tokens spanning lines are split, as clients needn't support them.
+fn semantic_tokens(text: &str) -> Json {
+    let mut data = Vec::new();
+    let (mut last_line, mut last_start) = (0, 0);
+
+    for token in json5::semantic_tokens(text) {
+        let token_type = match token.kind {
+            SemanticKind::Key => 0,
+            SemanticKind::String => 1,
+            SemanticKind::Number => 2,
+            SemanticKind::Keyword => 3,
+            SemanticKind::Comment => 4,
+            SemanticKind::Punctuation => 5,
+            // Errors are reported as diagnostics instead.
+            SemanticKind::Error => continue,
+        };
+        let start = lsp_position(text, token.span.start);
+        let (mut line, mut start) = (
+            start["line"].as_u64().unwrap_or_default(),
+            start["character"].as_u64().unwrap_or_default(),
+        );
+
+        let pieces = text[token.span.start.offset..token.span.end.offset]
+            .split('\n')
+            .flat_map(|piece| piece.strip_suffix('\r').unwrap_or(piece).split('\r'));
+        for piece in pieces {
+            let length: usize = piece.chars().map(char::len_utf16).sum();
+            if length > 0 {
+                let delta_start = if line == last_line {
+                    start - last_start
+                } else {
+                    start
+                };
+                data.extend_from_slice(&[
+                    line - last_line,
+                    delta_start,
+                    length as u64,
+                    token_type,
+                    0,
+                ]);
+                last_line = line;
+                last_start = start;
+            }
+            line += 1;
+            start = 0;
+        }
+    }
+    json!({ "data": data })
+}
+
+// LSP positions count lines from 0 and characters in UTF-16 code units.
+fn range(text: &str, span: Span) -> Json {
+    json!({
+        "start": lsp_position(text, span.start),
+        "end": lsp_position(text, span.end),
+    })
+}
+
+fn lsp_position(text: &str, position: Position) -> Json {
+    let line_start = text[..position.offset]
+        .rfind(['\n', '\r'])
+        .map_or(0, |i| i + 1);
+    let character: usize = text[line_start..position.offset]
+        .chars()
+        .map(char::len_utf16)
+        .sum();
+    json!({ "line": position.line - 1, "character": character })
+}
+
+// The offsets at which the lines after the first start. Lines end with
+// "\n", "\r\n" or "\r".
+fn line_starts(text: &str) -> impl Iterator<Item = usize> + '_ {
+    text.match_indices(['\n', '\r'])
+        .filter(move |&(i, s)| s == "\n" || !text[i + 1..].starts_with('\n'))
+        .map(|(i, _)| i + 1)
+}
+
+// The byte offset of an LSP position, if it's in the document.
+fn offset(text: &str, position: &Json) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+
+    let line_start = if line == 0 {
+        0
+    } else {
+        line_starts(text).nth(line - 1)?
+    };
+    let mut units = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= character || c == '\n' || c == '\r' {
+            return Some(line_start + i);
+        }
+        units += c.len_utf16();
+    }
+    Some(text.len())
+}
@@ -0,0 +1,120 @@
+//! A command line tool that checks JSON5 documents and applies the
+//! structural rewrites of the library to them, keeping their comments.
+//!
+//! ```text
+//! json5 [--sort-keys[=POINTER]] [--double-quotes] [--dedupe] [--in-place] [FILE...]
+//! ```
+//!
+//! Each file, or standard input if none are given, is parsed, rewritten as
+//! the flags say and written to standard output, or back to the file with
+//! `--in-place`. Without flags, documents are only checked. Errors are
+//! reported on standard error, and the tool exits with status 1 if any
+//! document had one, 2 for bad usage.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use json5::{Error, Pointer};
+
+const USAGE: &str =
+    "usage: json5 [--sort-keys[=POINTER]] [--double-quotes] [--dedupe] [--in-place] [FILE...]";
+
+#[derive(Default)]
+struct Flags {
+    // The object whose keys to sort, the whole document by default.
+    sort_keys: Option<Pointer>,
+    double_quotes: bool,
+    dedupe: bool,
+    in_place: bool,
+}
+
+fn main() {
+    let mut flags = Flags::default();
+    let mut files = Vec::new();
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--sort-keys" => flags.sort_keys = Some(Pointer::root()),
+            "--double-quotes" => flags.double_quotes = true,
+            "--dedupe" => flags.dedupe = true,
+            "--in-place" => flags.in_place = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => match arg.strip_prefix("--sort-keys=") {
+                Some(pointer) => match pointer.parse() {
+                    Ok(pointer) => flags.sort_keys = Some(pointer),
+                    Err(error) => usage_error(&error.to_string()),
+                },
+                None if arg.starts_with('-') && arg != "-" => {
+                    usage_error(&format!("unknown flag `{}`", arg))
+                }
+                None => files.push(arg),
+            },
+        }
+    }
+    if flags.in_place && files.is_empty() {
+        usage_error("--in-place needs files");
+    }
+    if files.is_empty() {
+        files.push("-".into());
+    }
+
+    let mut failed = false;
+    for file in &files {
+        if let Err(message) = run(file, &flags) {
+            eprintln!("{}: {}", file, message);
+            failed = true;
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("json5: {}\n{}", message, USAGE);
+    process::exit(2);
+}
+
+// Reads `file`, `-` for standard input, and writes it out rewritten.
+fn run(file: &str, flags: &Flags) -> Result<(), String> {
+    let text = if file == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| e.to_string())?;
+        text
+    } else {
+        fs::read_to_string(file).map_err(|e| e.to_string())?
+    };
+
+    let rewritten = rewrite(text, flags).map_err(|e| e.to_string())?;
+    if flags.in_place {
+        fs::write(file, rewritten).map_err(|e| e.to_string())
+    } else if flags.sort_keys.is_some() || flags.double_quotes || flags.dedupe {
+        io::stdout()
+            .write_all(rewritten.as_bytes())
+            .map_err(|e| e.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+// Duplicates go first, so that sorting doesn't move members about to go.
+fn rewrite(mut text: String, flags: &Flags) -> Result<String, Error> {
+    json5::parse(&text)?;
+
+    if flags.dedupe {
+        text = json5::remove_duplicate_keys(&text)?;
+    }
+    if let Some(pointer) = &flags.sort_keys {
+        text = json5::sort_keys(&text, pointer)?;
+    }
+    if flags.double_quotes {
+        text = json5::to_double_quotes(&text)?;
+    }
+    Ok(text)
+}
@@ -0,0 +1,51 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::options::ParseOptions;
+use crate::parser::Parser;
+use crate::value::Value;
+use crate::warning::Warning;
+
+/// The parser's scratch space, kept from one document to the next, for
+/// parsing many small documents without allocating it again for each.
+///
+/// Only the values parsed are allocated anew: their strings, arrays and
+/// objects are their own.
+#[derive(Clone, Debug, Default)]
+pub struct ParseBuffer {
+    options: ParseOptions,
+    number: String,
+    warnings: Vec<Warning>,
+}
+
+impl ParseBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: ParseOptions) -> Self {
+        ParseBuffer {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Parses a JSON5 document, as `parse_with_options` does.
+    pub fn parse(&mut self, json: &str) -> Result<Value, Error> {
+        let mut parser = Parser::with_options(json, self.options);
+        self.warnings.clear();
+        parser.number = core::mem::take(&mut self.number);
+        parser.warnings = core::mem::take(&mut self.warnings);
+
+        let result = crate::parse_document(&mut parser);
+        self.number = parser.number;
+        self.warnings = parser.warnings;
+        result
+    }
+
+    /// The warnings from the last document parsed.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::format;
+use alloc::string::String;
+use core::convert::TryFrom;
+
+use crate::error::{Error, ErrorKind};
+use crate::parser::MAX_DEPTH;
+use crate::value::Value;
+
+// A cursor over the input of the binary formats' readers.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Reader {
+            bytes,
+            offset: 0,
+            depth: 0,
+        }
+    }
+
+    pub fn error(&self, message: &str) -> Error {
+        Error::new(ErrorKind::Custom(format!(
+            "{} at byte {}",
+            message, self.offset
+        )))
+    }
+
+    pub fn take(&mut self, n: u64) -> Result<&'a [u8], Error> {
+        let rest = &self.bytes[self.offset..];
+        match usize::try_from(n) {
+            Ok(n) if n <= rest.len() => {
+                self.offset += n;
+                Ok(&rest[..n])
+            }
+            _ => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    pub fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    // A big-endian unsigned integer `n` bytes long.
+    pub fn uint(&mut self, n: u64) -> Result<u64, Error> {
+        Ok(self
+            .take(n)?
+            .iter()
+            .fold(0, |acc, &b| (acc << 8) | u64::from(b)))
+    }
+
+    pub fn string(&mut self, len: u64) -> Result<String, Error> {
+        let start = self.offset;
+        let bytes = self.take(len)?;
+        match core::str::from_utf8(bytes) {
+            Ok(s) => Ok(s.into()),
+            Err(_) => {
+                self.offset = start;
+                Err(self.error("invalid UTF-8"))
+            }
+        }
+    }
+
+    // Caps a length read from the input, which may be bogus, by what the
+    // rest of the input could hold.
+    pub fn capacity(&self, len: u64) -> usize {
+        let rest = self.bytes.len() - self.offset;
+        usize::try_from(len).map_or(rest, |len| len.min(rest))
+    }
+
+    pub fn enter(&mut self) -> Result<(), Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::new(ErrorKind::ExceededMaxDepth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    pub fn finish(&self, value: Value) -> Result<Value, Error> {
+        if self.offset < self.bytes.len() {
+            return Err(self.error("trailing bytes"));
+        }
+        Ok(value)
+    }
+}
+
+// Integers beyond the range of `Value::Integer` become floats.
+pub fn integer(i: i128) -> Value {
+    match i32::try_from(i) {
+        Ok(i) => Value::Integer(i),
+        Err(_) => Value::Float(i as f64),
+    }
+}
@@ -0,0 +1,93 @@
+//! A process-wide cache of parsed documents, for programs that parse the
+//! same snippets again and again, such as templates.
+//!
+//! Documents are looked up by a hash of their text, and compared in full
+//! on a match. The least recently used are evicted once the cache holds
+//! `capacity` of them, 64 unless set otherwise.
+
+use std::hash::Hasher;
+use std::string::String;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::vec::Vec;
+
+use crate::error::Error;
+use crate::fnv::Fnv;
+use crate::value::Value;
+
+struct Cache {
+    // Least recently used first.
+    entries: Vec<(u64, String, Arc<Value>)>,
+    capacity: usize,
+}
+
+static CACHE: Mutex<Cache> = Mutex::new(Cache {
+    entries: Vec::new(),
+    capacity: 64,
+});
+
+fn cache() -> MutexGuard<'static, Cache> {
+    // The cache is consistent between statements, even after a panic.
+    CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn hash(json: &str) -> u64 {
+    let mut h = Fnv::new();
+    h.write(json.as_bytes());
+    h.finish()
+}
+
+impl Cache {
+    fn get(&mut self, hash: u64, json: &str) -> Option<Arc<Value>> {
+        let i = self
+            .entries
+            .iter()
+            .position(|(h, text, _)| *h == hash && text == json)?;
+        let entry = self.entries.remove(i);
+        let value = entry.2.clone();
+        self.entries.push(entry);
+        Some(value)
+    }
+
+    fn evict(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.capacity);
+        self.entries.drain(..excess);
+    }
+}
+
+/// Parses a document as `json5::parse` does, or returns the value parsed
+/// before from the same text. Errors aren't cached.
+pub fn parse(json: &str) -> Result<Arc<Value>, Error> {
+    let hash = hash(json);
+    if let Some(value) = cache().get(hash, json) {
+        return Ok(value);
+    }
+
+    // Parsed without holding the lock; another thread may parse the same
+    // text meanwhile, and the first to finish wins.
+    let value = Arc::new(crate::parse(json)?);
+    let mut cache = cache();
+    if let Some(value) = cache.get(hash, json) {
+        return Ok(value);
+    }
+    if cache.capacity > 0 {
+        cache.entries.push((hash, json.into(), value.clone()));
+        cache.evict();
+    }
+    Ok(value)
+}
+
+/// Sets how many documents the cache holds, evicting the least recently
+/// used if it holds more. Zero disables caching.
+pub fn set_capacity(capacity: usize) {
+    let mut cache = cache();
+    cache.capacity = capacity;
+    cache.evict();
+}
+
+pub fn len() -> usize {
+    cache().entries.len()
+}
+
+pub fn clear() {
+    cache().entries.clear();
+}
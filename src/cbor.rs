@@ -0,0 +1,156 @@
+//! Conversion to and from CBOR.
+
+use alloc::vec::Vec;
+
+use crate::bytes::{self, Reader};
+use crate::error::Error;
+use crate::map::Map;
+use crate::value::Value;
+
+impl Value {
+    /// Encodes the value as CBOR, with floats always 64 bits wide so that
+    /// every value, NaN and the infinities included, reads back unchanged.
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_value(&mut out, self);
+        out
+    }
+
+    /// Decodes a CBOR item. Tags are skipped over; byte strings,
+    /// indefinite-length items, simple values other than booleans, null and
+    /// undefined (read as null), and map keys that aren't text are rejected.
+    pub fn from_cbor(bytes: &[u8]) -> Result<Value, Error> {
+        let mut reader = Reader::new(bytes);
+        let value = read_value(&mut reader)?;
+        reader.finish(value)
+    }
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+
+    if n < 24 {
+        out.push(major | n as u8);
+    } else if n <= 0xff {
+        out.extend_from_slice(&[major | 24, n as u8]);
+    } else if n <= 0xffff {
+        out.push(major | 25);
+        out.extend_from_slice(&(n as u16).to_be_bytes());
+    } else if n <= 0xffff_ffff {
+        out.push(major | 26);
+        out.extend_from_slice(&(n as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.push(0xf6),
+        Value::Boolean(b) => out.push(if *b { 0xf5 } else { 0xf4 }),
+        Value::Integer(i) if *i >= 0 => write_head(out, 0, *i as u64),
+        Value::Integer(i) => write_head(out, 1, (-1 - i64::from(*i)) as u64),
+        Value::Float(f) => {
+            out.push(0xfb);
+            out.extend_from_slice(&f.to_bits().to_be_bytes());
+        }
+        Value::String(s) => write_text(out, s),
+        Value::Array(v) => {
+            write_head(out, 4, v.len() as u64);
+            for item in v {
+                write_value(out, item);
+            }
+        }
+        Value::Object(m) => {
+            write_head(out, 5, m.len() as u64);
+            for (key, item) in m {
+                write_text(out, key);
+                write_value(out, item);
+            }
+        }
+        Value::Custom(c) => write_value(out, &c.to_value()),
+    }
+}
+
+fn write_text(out: &mut Vec<u8>, s: &str) {
+    write_head(out, 3, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn read_value(reader: &mut Reader) -> Result<Value, Error> {
+    let initial = reader.byte()?;
+    let (major, info) = (initial >> 5, initial & 0x1f);
+
+    // Floats and simple values are told apart by the width of their argument.
+    if major == 7 {
+        return match info {
+            20 => Ok(Value::Boolean(false)),
+            21 => Ok(Value::Boolean(true)),
+            22 | 23 => Ok(Value::Null),
+            25 => Ok(Value::Float(f16_to_f64(reader.uint(2)? as u16))),
+            26 => Ok(Value::Float(f32::from_bits(reader.uint(4)? as u32).into())),
+            27 => Ok(Value::Float(f64::from_bits(reader.uint(8)?))),
+            _ => Err(reader.error("unsupported CBOR simple value")),
+        };
+    }
+
+    let n = match info {
+        0..=23 => u64::from(info),
+        24..=27 => reader.uint(1 << (info - 24))?,
+        31 => return Err(reader.error("unsupported indefinite-length CBOR item")),
+        _ => return Err(reader.error("invalid CBOR item")),
+    };
+
+    match major {
+        0 => Ok(bytes::integer(n.into())),
+        1 => Ok(bytes::integer(-1 - i128::from(n))),
+        3 => Ok(Value::String(reader.string(n)?)),
+        4 => {
+            let mut items = Vec::with_capacity(reader.capacity(n));
+            reader.enter()?;
+            for _ in 0..n {
+                items.push(read_value(reader)?);
+            }
+            reader.leave();
+            Ok(Value::Array(items))
+        }
+        5 => {
+            let mut map = Map::new();
+            reader.enter()?;
+            for _ in 0..n {
+                let key = match read_value(reader)? {
+                    Value::String(key) => key,
+                    _ => return Err(reader.error("map key is not text")),
+                };
+                map.insert(key, read_value(reader)?);
+            }
+            reader.leave();
+            Ok(Value::Object(map))
+        }
+        6 => {
+            reader.enter()?;
+            let value = read_value(reader)?;
+            reader.leave();
+            Ok(value)
+        }
+        _ => Err(reader.error("unsupported CBOR byte string")),
+    }
+}
+
+fn f16_to_f64(half: u16) -> f64 {
+    let exponent = (half >> 10) & 0x1f;
+    let mantissa = f64::from(half & 0x3ff);
+
+    let magnitude = match exponent {
+        0 => mantissa / (1 << 24) as f64,
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * (1u64 << exponent) as f64 / (1 << 25) as f64,
+    };
+    if half & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
@@ -0,0 +1,84 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::value::Value;
+
+impl Value {
+    /// Rust source for an expression that constructs this value, for build
+    /// scripts and macros that embed configuration in a program, e.g. with
+    /// `include!`. It refers to this crate as `::json5`.
+    ///
+    /// The output depends only on the value: object keys come out sorted,
+    /// unless `preserve_order` is enabled, in which case their order is kept.
+    pub fn to_rust_tokens(&self) -> String {
+        let mut out = String::new();
+        write_value(&mut out, self);
+        out
+    }
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("::json5::Value::Null"),
+        Value::Boolean(b) => {
+            let _ = write!(out, "::json5::Value::Boolean({})", b);
+        }
+        Value::Integer(i) => {
+            let _ = write!(out, "::json5::Value::Integer({})", i);
+        }
+        Value::Float(f) => {
+            out.push_str("::json5::Value::Float(");
+            if f.is_nan() {
+                out.push_str("f64::NAN");
+            } else if f.is_infinite() {
+                out.push_str(if *f > 0.0 {
+                    "f64::INFINITY"
+                } else {
+                    "f64::NEG_INFINITY"
+                });
+            } else {
+                // Debug formatting reads back as the same float.
+                let _ = write!(out, "{:?}f64", f);
+            }
+            out.push(')');
+        }
+        Value::String(s) => {
+            let _ = write!(out, "::json5::Value::String({:?}.into())", s);
+        }
+        Value::Array(v) => {
+            out.push_str("::json5::Value::Array([");
+            for (i, item) in v.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_value(out, item);
+            }
+            out.push_str("].into())");
+        }
+        Value::Object(m) if m.is_empty() => {
+            out.push_str("::json5::Value::Object(::json5::Map::new())")
+        }
+        Value::Object(m) => {
+            #[allow(unused_mut)]
+            let mut entries: Vec<_> = m.iter().collect();
+            #[cfg(not(feature = "preserve_order"))]
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+
+            // Arrays are iterated by value whatever the edition of the crate
+            // the code ends up in.
+            out.push_str("::json5::Value::Object(::core::iter::IntoIterator::into_iter([");
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                let _ = write!(out, "({:?}.into(), ", key);
+                write_value(out, item);
+                out.push(')');
+            }
+            out.push_str("]).collect())");
+        }
+        // The type isn't known to generated code, only what it stands for.
+        Value::Custom(c) => write_value(out, &c.to_value()),
+    }
+}
@@ -0,0 +1,48 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::lexer::{tokenize, SyntaxKind};
+use crate::pointer::Pointer;
+use crate::span::Span;
+use crate::value::Value;
+
+/// A comment in a document, from `parse_with_comments`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comment {
+    /// The text between `//` and the end of the line, or between `/*` and
+    /// `*/`.
+    pub text: String,
+    /// The span of the whole comment.
+    pub span: Span,
+    /// The JSON Pointer of the first value after the comment, the one it
+    /// usually documents, unless nothing follows it.
+    pub pointer: Option<Pointer>,
+}
+
+/// Like `parse`, but also returns the comments in the document, in order,
+/// each with the value that follows it.
+pub fn parse_with_comments(json: &str) -> Result<(Value, Vec<Comment>), Error> {
+    let (value, mut spans) = crate::parse_with_spans(json)?;
+    spans.sort_by_key(|(_, span)| span.start.offset);
+
+    let comments = tokenize(json)
+        .into_iter()
+        .filter_map(|token| {
+            let text = token.text(json);
+            let text = match token.kind {
+                SyntaxKind::LineComment => &text[2..],
+                SyntaxKind::BlockComment => &text[2..text.len() - 2],
+                _ => return None,
+            };
+            let end = token.span.end.offset;
+            let next = spans.partition_point(|(_, span)| span.start.offset < end);
+            Some(Comment {
+                text: text.into(),
+                span: token.span,
+                pointer: spans.get(next).map(|(pointer, _)| pointer.clone()),
+            })
+        })
+        .collect();
+    Ok((value, comments))
+}
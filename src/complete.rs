@@ -0,0 +1,367 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::lexer::{tokenize, SyntaxKind, Token};
+use crate::pointer::Pointer;
+use crate::span::Span;
+use crate::value::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionKind {
+    Key,
+    Value,
+}
+
+/// A suggestion from `complete`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Completion {
+    pub kind: CompletionKind,
+    pub label: String,
+    /// The text to insert, in place of `replace` when that's set: the key
+    /// or value being typed at the cursor.
+    pub insert_text: String,
+    pub replace: Option<Span>,
+    /// The schema's `description` of the key or value, or its `title`.
+    pub documentation: Option<String>,
+}
+
+/// Suggests the keys or values that `schema`, a JSON Schema, allows at byte
+/// `offset` of `json`, a document that may be incomplete, as it is while
+/// being typed.
+///
+/// Keys come from `properties`, leaving out those the object already has,
+/// and values from `enum`, `const`, `default` and `type`. Subschemas are
+/// followed through `properties`, `additionalProperties`, `items`,
+/// `prefixItems`, `allOf`, `anyOf`, `oneOf` and `$ref`s within the schema.
+pub fn complete(json: &str, offset: usize, schema: &Value) -> Vec<Completion> {
+    let tokens = tokenize(json);
+    let context = match Context::at(json, &tokens, offset) {
+        Some(context) => context,
+        None => return Vec::new(),
+    };
+
+    let resolver = Resolver { root: schema };
+    let schemas = resolver.at(schema, &context.path);
+    let mut completions = Vec::new();
+
+    match &context.key {
+        Some(present) => {
+            for subschema in &schemas {
+                let properties = match resolver.resolve(subschema)["properties"].to_map() {
+                    Some(properties) => properties,
+                    None => continue,
+                };
+                for (key, property) in properties {
+                    let duplicate = completions.iter().any(|c: &Completion| c.label == *key);
+                    if present.contains(key) || duplicate {
+                        continue;
+                    }
+                    completions.push(Completion {
+                        kind: CompletionKind::Key,
+                        label: key.clone(),
+                        insert_text: crate::stringify(&Value::String(key.clone())) + ": ",
+                        replace: context.replace,
+                        documentation: documentation(resolver.resolve(property)),
+                    });
+                }
+            }
+        }
+        None => {
+            for subschema in &schemas {
+                resolver.values(subschema, &context, &mut completions);
+            }
+        }
+    }
+    completions
+}
+
+// Where the cursor is: in the array or object at `path`, or at the top, and
+// at a key, with those the object has, or at a value.
+struct Context {
+    path: Vec<String>,
+    key: Option<Vec<String>>,
+    replace: Option<Span>,
+}
+
+enum Frame {
+    Object {
+        key: Option<String>,
+        at_value: bool,
+        keys: Vec<String>,
+    },
+    Array {
+        index: usize,
+    },
+}
+
+impl Context {
+    fn at(json: &str, tokens: &[Token], offset: usize) -> Option<Self> {
+        let mut frames: Vec<Frame> = Vec::new();
+        let mut path = Vec::new();
+        let mut replace = None;
+        let mut rest = tokens.len();
+
+        for (i, token) in tokens.iter().enumerate() {
+            if token.span.end.offset > offset || token.span.end.offset == offset && is_word(token) {
+                // A key or value being typed is replaced by the completion.
+                if token.span.start.offset < offset && is_word(token) {
+                    replace = Some(token.span);
+                    rest = i + 1;
+                } else if token.span.start.offset < offset && token.kind != SyntaxKind::Whitespace {
+                    // Inside a comment or some other token.
+                    return None;
+                } else {
+                    rest = i;
+                }
+                break;
+            }
+            step(json, token, &mut frames, &mut path);
+        }
+
+        let key = match frames.last() {
+            Some(Frame::Object {
+                key,
+                at_value: true,
+                ..
+            }) => {
+                path.push(key.clone().unwrap_or_default());
+                None
+            }
+            Some(Frame::Object { keys, .. }) => {
+                let mut keys = keys.clone();
+                keys.extend(keys_after(json, &tokens[rest..]));
+                Some(keys)
+            }
+            Some(Frame::Array { index }) => {
+                path.push(index.to_string());
+                None
+            }
+            None => None,
+        };
+        Some(Context { path, key, replace })
+    }
+}
+
+// The keys of the object from its entry after the cursor on.
+fn keys_after(json: &str, tokens: &[Token]) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut depth = 0;
+    let mut significant = tokens.iter().filter(|t| !t.is_trivia()).peekable();
+
+    while let Some(token) = significant.next() {
+        match token.kind {
+            SyntaxKind::LeftBrace | SyntaxKind::LeftBracket => depth += 1,
+            SyntaxKind::RightBrace | SyntaxKind::RightBracket if depth == 0 => break,
+            SyntaxKind::RightBrace | SyntaxKind::RightBracket => depth -= 1,
+            SyntaxKind::String | SyntaxKind::Identifier
+                if depth == 0
+                    && significant
+                        .peek()
+                        .is_some_and(|t| t.kind == SyntaxKind::Colon) =>
+            {
+                keys.push(key_name(json, token));
+            }
+            _ => (),
+        }
+    }
+    keys
+}
+
+fn key_name(json: &str, token: &Token) -> String {
+    crate::parser::unquote_key(token.text(json))
+}
+
+fn is_word(token: &Token) -> bool {
+    matches!(
+        token.kind,
+        SyntaxKind::String | SyntaxKind::Identifier | SyntaxKind::Number
+    )
+}
+
+// Follows `token` through the structure of the document, tracking the path
+// to the innermost array or object.
+fn step(json: &str, token: &Token, frames: &mut Vec<Frame>, path: &mut Vec<String>) {
+    let entered = match frames.last() {
+        Some(Frame::Object { key, at_value, .. }) if *at_value => key.clone(),
+        Some(Frame::Array { index }) => Some(index.to_string()),
+        _ => None,
+    };
+
+    match token.kind {
+        SyntaxKind::LeftBrace | SyntaxKind::LeftBracket => {
+            if !frames.is_empty() {
+                path.push(entered.unwrap_or_default());
+            }
+            frames.push(if token.kind == SyntaxKind::LeftBrace {
+                Frame::Object {
+                    key: None,
+                    at_value: false,
+                    keys: Vec::new(),
+                }
+            } else {
+                Frame::Array { index: 0 }
+            });
+        }
+        SyntaxKind::RightBrace | SyntaxKind::RightBracket => {
+            frames.pop();
+            if !frames.is_empty() {
+                path.pop();
+            }
+        }
+        SyntaxKind::Colon => {
+            if let Some(Frame::Object { at_value, .. }) = frames.last_mut() {
+                *at_value = true;
+            }
+        }
+        SyntaxKind::Comma => match frames.last_mut() {
+            Some(Frame::Object { key, at_value, .. }) => {
+                *key = None;
+                *at_value = false;
+            }
+            Some(Frame::Array { index }) => *index += 1,
+            None => (),
+        },
+        SyntaxKind::String | SyntaxKind::Identifier => {
+            if let Some(Frame::Object {
+                key,
+                at_value: false,
+                keys,
+            }) = frames.last_mut()
+            {
+                let name = key_name(json, token);
+                keys.push(name.clone());
+                *key = Some(name);
+            }
+        }
+        _ => (),
+    }
+}
+
+struct Resolver<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Resolver<'a> {
+    // Follows `$ref`s to definitions elsewhere in the root schema.
+    fn resolve(&self, schema: &'a Value) -> &'a Value {
+        let mut schema = schema;
+        // Bounded, in case of a cycle.
+        for _ in 0..32 {
+            let reference = match schema["$ref"].to_string() {
+                Some(reference) => reference,
+                None => break,
+            };
+            let pointer = match reference.strip_prefix('#').and_then(Pointer::parse) {
+                Some(pointer) => pointer,
+                None => break,
+            };
+            schema = self.root.at(pointer.tokens());
+        }
+        schema
+    }
+
+    // The schema and those it combines with `allOf`, `anyOf` and `oneOf`.
+    fn branches(&self, schema: &'a Value, out: &mut Vec<&'a Value>) {
+        let schema = self.resolve(schema);
+        if out.iter().any(|s| core::ptr::eq(*s, schema)) || out.len() > 64 {
+            return;
+        }
+        out.push(schema);
+        for combinator in ["allOf", "anyOf", "oneOf"] {
+            if let Some(branches) = schema[combinator].to_vec() {
+                for branch in branches {
+                    self.branches(branch, out);
+                }
+            }
+        }
+    }
+
+    // The schemas for the value at `path`.
+    fn at(&self, schema: &'a Value, path: &[String]) -> Vec<&'a Value> {
+        let mut schemas = Vec::new();
+        self.branches(schema, &mut schemas);
+
+        for token in path {
+            let mut next = Vec::new();
+            for schema in schemas {
+                let property = &schema["properties"][token.as_str()];
+                let index = token.parse::<usize>().ok();
+                let prefix_item = index.map_or(&Value::Null, |i| match &schema["prefixItems"] {
+                    Value::Array(items) => items.get(i).unwrap_or(&Value::Null),
+                    _ => &Value::Null,
+                });
+
+                let found = if !property.is_null() {
+                    property
+                } else if !prefix_item.is_null() {
+                    prefix_item
+                } else if index.is_some() && schema["items"].to_map().is_some() {
+                    &schema["items"]
+                } else if schema["additionalProperties"].to_map().is_some() {
+                    &schema["additionalProperties"]
+                } else {
+                    continue;
+                };
+                self.branches(found, &mut next);
+            }
+            schemas = next;
+        }
+        schemas
+    }
+
+    fn values(&self, schema: &'a Value, context: &Context, out: &mut Vec<Completion>) {
+        let documentation = documentation(schema);
+        let mut push = |value: &Value| {
+            let label = crate::stringify(value);
+            if out.iter().any(|c| c.label == label) {
+                return;
+            }
+            out.push(Completion {
+                kind: CompletionKind::Value,
+                insert_text: label.clone(),
+                label,
+                replace: context.replace,
+                documentation: documentation.clone(),
+            });
+        };
+
+        if let Some(values) = schema["enum"].to_vec() {
+            values.iter().for_each(&mut push);
+        }
+        if let Some(object) = schema.to_map() {
+            for name in ["const", "default"] {
+                if let Some(value) = object.get(name) {
+                    push(value);
+                }
+            }
+        }
+
+        let types = match &schema["type"] {
+            Value::String(t) => alloc::vec![t.as_str()],
+            Value::Array(types) => types
+                .iter()
+                .filter_map(|t| Some(t.to_string()?.as_str()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        for t in types {
+            match t {
+                "boolean" => {
+                    push(&Value::Boolean(true));
+                    push(&Value::Boolean(false));
+                }
+                "null" => push(&Value::Null),
+                "object" => push(&Value::Object(Default::default())),
+                "array" => push(&Value::Array(Vec::new())),
+                _ => (),
+            }
+        }
+    }
+}
+
+fn documentation(schema: &Value) -> Option<String> {
+    schema["description"]
+        .to_string()
+        .or_else(|| schema["title"].to_string())
+        .cloned()
+}
@@ -0,0 +1,124 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::{Error, ErrorKind};
+use crate::pointer::Pointer;
+use crate::value::Value;
+
+impl Value {
+    /// Resolves conditional sections: object entries with keys like
+    /// `"@if(env=prod)"`, whose values are objects merged into the object
+    /// containing them when `predicate` holds for the condition, here
+    /// `env=prod`, and dropped otherwise.
+    ///
+    /// Sections are merged over the other entries, objects key by key and
+    /// anything else replacing what was there, in the order of their keys
+    /// sorted as strings, whatever the order of the map: where both
+    /// `@if(a)` and `@if(b)` hold and set the same key, `@if(b)` wins. They
+    /// may be nested.
+    ///
+    /// A section that isn't an object, or a key that starts with `@if(` but
+    /// doesn't end with `)`, is an error, even in a section that would be
+    /// dropped. On error, the value is left as it was.
+    pub fn resolve_conditions<F>(&mut self, mut predicate: F) -> Result<(), Error>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        check(self, &mut Vec::new())?;
+        resolve(self, &mut predicate);
+        Ok(())
+    }
+}
+
+// The condition of a section key, unless it is another key.
+fn condition(key: &str) -> Option<&str> {
+    let rest = key.strip_prefix("@if(")?;
+    Some(rest.strip_suffix(')').unwrap_or(rest))
+}
+
+// Finds the errors before anything is changed.
+fn check(value: &Value, path: &mut Vec<String>) -> Result<(), Error> {
+    match value {
+        Value::Array(v) => {
+            for (i, value) in v.iter().enumerate() {
+                path.push(i.to_string());
+                check(value, path)?;
+                path.pop();
+            }
+        }
+        Value::Object(m) => {
+            for (key, value) in m {
+                path.push(key.clone());
+                if key.starts_with("@if(") {
+                    if !key.ends_with(')') {
+                        return Err(error("unterminated condition", path));
+                    }
+                    if !matches!(value, Value::Object(_)) {
+                        return Err(error("a conditional section must be an object", path));
+                    }
+                }
+                check(value, path)?;
+                path.pop();
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn resolve<F>(value: &mut Value, predicate: &mut F)
+where
+    F: FnMut(&str) -> bool,
+{
+    match value {
+        Value::Array(v) => {
+            for value in v {
+                resolve(value, predicate);
+            }
+        }
+        Value::Object(m) => {
+            let mut sections = Vec::new();
+            for (key, mut value) in core::mem::take(m) {
+                if condition(&key).is_some() {
+                    sections.push((key, value));
+                } else {
+                    resolve(&mut value, predicate);
+                    m.insert(key, value);
+                }
+            }
+            sections.sort_by(|a, b| a.0.cmp(&b.0));
+
+            for (key, mut section) in sections {
+                match condition(&key) {
+                    Some(condition) if predicate(condition) => {
+                        resolve(&mut section, predicate);
+                        merge(value, section);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+fn merge(target: &mut Value, value: Value) {
+    match (target, value) {
+        (Value::Object(existing), Value::Object(m)) => {
+            for (key, item) in m {
+                match existing.get_mut(&key) {
+                    Some(slot) => merge(slot, item),
+                    None => {
+                        existing.insert(key, item);
+                    }
+                }
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
+
+fn error(message: &str, path: &[String]) -> Error {
+    let pointer: Pointer = path.iter().collect();
+    Error::new(ErrorKind::Custom(message.into())).with_pointer(pointer)
+}
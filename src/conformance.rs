@@ -0,0 +1,101 @@
+//! Runs a directory of test documents laid out as the official JSON5 test
+//! suite is, to check how a configuration of the parser behaves on it.
+//!
+//! Documents are found in the directory and every directory within it, and
+//! what is expected of each is told by its extension: `.json` and `.json5`
+//! files must parse, while `.js` files, valid JavaScript but not JSON5, and
+//! `.txt` files must not. Files with other extensions are ignored.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::load::LoadError;
+use crate::options::ParseOptions;
+
+/// Whether a test document should parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expectation {
+    Valid,
+    Invalid,
+}
+
+impl Expectation {
+    fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" | "json5" => Some(Self::Valid),
+            "js" | "txt" => Some(Self::Invalid),
+            _ => None,
+        }
+    }
+}
+
+/// A test document that didn't parse as expected, with the error if it
+/// should have parsed.
+#[derive(Debug)]
+pub struct ConformanceFailure {
+    pub path: PathBuf,
+    pub expected: Expectation,
+    pub error: Option<Error>,
+}
+
+/// The results of `run_conformance`.
+#[derive(Debug, Default)]
+pub struct ConformanceReport {
+    pub passed: usize,
+    /// In the order of their paths.
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl ConformanceReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Parses every test document in `dir` with `options`, and reports those
+/// that didn't parse as expected. Fails only if a file or directory can't be
+/// read.
+pub fn run_conformance<P>(dir: P, options: &ParseOptions) -> Result<ConformanceReport, LoadError>
+where
+    P: AsRef<Path>,
+{
+    let mut paths = Vec::new();
+    find_documents(dir.as_ref(), &mut paths)?;
+    paths.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut report = ConformanceReport::default();
+    for (path, expected) in paths {
+        let json = fs::read_to_string(&path).map_err(|error| LoadError::Io {
+            path: path.clone(),
+            error,
+        })?;
+        match (crate::parse_with_options(&json, options), expected) {
+            (Ok(_), Expectation::Valid) | (Err(_), Expectation::Invalid) => report.passed += 1,
+            (result, expected) => report.failures.push(ConformanceFailure {
+                path,
+                expected,
+                error: result.err(),
+            }),
+        }
+    }
+    Ok(report)
+}
+
+fn find_documents(dir: &Path, paths: &mut Vec<(PathBuf, Expectation)>) -> Result<(), LoadError> {
+    let io_error = |error| LoadError::Io {
+        path: dir.to_path_buf(),
+        error,
+    };
+
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        let path = entry.path();
+        if entry.file_type().map_err(io_error)?.is_dir() {
+            find_documents(&path, paths)?;
+        } else if let Some(expected) = Expectation::of(&path) {
+            paths.push((path, expected));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::map::Map;
+use crate::pointer::Pointer;
+use crate::value::Value;
+
+/// A naming convention for object keys, for `Value::normalize_keys`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Convention {
+    /// `max_retries`
+    SnakeCase,
+    /// `max-retries`
+    KebabCase,
+    /// `maxRetries`
+    CamelCase,
+    /// `MaxRetries`
+    PascalCase,
+    /// `MAX_RETRIES`
+    ScreamingSnakeCase,
+}
+
+impl Convention {
+    /// `key` in this convention. Words are split at `-`, `_` and spaces,
+    /// and where the case changes, so that `HTTPServer` is `http_server` in
+    /// snake case.
+    pub fn apply(&self, key: &str) -> String {
+        let mut out = String::with_capacity(key.len());
+        for (i, word) in words(key).iter().enumerate() {
+            let separator = match self {
+                Convention::SnakeCase | Convention::ScreamingSnakeCase => "_",
+                Convention::KebabCase => "-",
+                Convention::CamelCase | Convention::PascalCase => "",
+            };
+            if i > 0 {
+                out.push_str(separator);
+            }
+            match self {
+                Convention::ScreamingSnakeCase => {
+                    out.extend(word.chars().flat_map(char::to_uppercase))
+                }
+                Convention::PascalCase => capitalize(&mut out, word),
+                Convention::CamelCase if i > 0 => capitalize(&mut out, word),
+                _ => out.push_str(word),
+            }
+        }
+        out
+    }
+}
+
+/// Object keys that became the same key in `Value::normalize_keys`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyCollision {
+    /// The JSON Pointer of the normalized key.
+    pub pointer: Pointer,
+    /// The keys as they were, the one whose value was kept first.
+    pub keys: Vec<String>,
+}
+
+impl Value {
+    /// Rewrites the keys of this and all nested objects to `convention`,
+    /// e.g. before deserializing a configuration that its users may write
+    /// in any style, and returns the keys that collided.
+    ///
+    /// Of colliding keys, the value of the one already in the convention is
+    /// kept, or else that of the one that sorts first.
+    pub fn normalize_keys(&mut self, convention: Convention) -> Vec<KeyCollision> {
+        let mut collisions = Vec::new();
+        normalize(self, convention, &mut Vec::new(), &mut collisions);
+        collisions
+    }
+}
+
+fn normalize(
+    value: &mut Value,
+    convention: Convention,
+    path: &mut Vec<String>,
+    collisions: &mut Vec<KeyCollision>,
+) {
+    match value {
+        Value::Array(values) => {
+            for (i, value) in values.iter_mut().enumerate() {
+                path.push(i.to_string());
+                normalize(value, convention, path, collisions);
+                path.pop();
+            }
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<Option<(String, Value)>> =
+                core::mem::take(map).into_iter().map(Some).collect();
+
+            // The entries for each new key, in the order of the first.
+            let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+            let mut index: Map<String, usize> = Map::new();
+            for (i, entry) in entries.iter().enumerate() {
+                let key = entry.as_ref().map_or("", |(key, _)| key);
+                let new_key = convention.apply(key);
+                match index.get(&new_key) {
+                    Some(&group) => groups[group].1.push(i),
+                    None => {
+                        index.insert(new_key.clone(), groups.len());
+                        groups.push((new_key, alloc::vec![i]));
+                    }
+                }
+            }
+
+            for (new_key, mut members) in groups {
+                let name = |i: &usize| entries[*i].as_ref().map_or("", |(key, _)| key.as_str());
+                members.sort_by_key(|i| (name(i) != new_key, name(i)));
+                let mut kept = None;
+                let mut keys = Vec::new();
+                for i in members {
+                    if let Some((key, value)) = entries[i].take() {
+                        kept.get_or_insert(value);
+                        keys.push(key);
+                    }
+                }
+
+                path.push(new_key.clone());
+                if let Some(mut value) = kept {
+                    normalize(&mut value, convention, path, collisions);
+                    map.insert(new_key, value);
+                }
+                if keys.len() > 1 {
+                    collisions.push(KeyCollision {
+                        pointer: path.iter().collect(),
+                        keys,
+                    });
+                }
+                path.pop();
+            }
+        }
+        _ => (),
+    }
+}
+
+// The words of `key`, in lowercase.
+fn words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' || c == '_' || c.is_whitespace() {
+            if !word.is_empty() {
+                words.push(core::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let previous = chars[i - 1];
+            let next_lowercase = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            // `aB` and `1B`, or the `S` of `HTTPServer`.
+            if !previous.is_uppercase() || next_lowercase {
+                words.push(core::mem::take(&mut word));
+            }
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(out: &mut String, word: &str) {
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        out.extend(first.to_uppercase());
+        out.push_str(chars.as_str());
+    }
+}
@@ -0,0 +1,305 @@
+//! Conversion between arrays of objects and CSV or TSV.
+//!
+//! Each object is a row, and the header lists every key of every object, in
+//! the order first seen. Strings are written as they are, other scalars as
+//! `stringify` writes them, and arrays and objects as compact JSON5. Fields
+//! are quoted as RFC 4180 says, when they contain the delimiter, a quote or
+//! a line break, and so are strings that would read as null.
+//!
+//! Going the other way, quoted fields are strings, and unquoted fields are
+//! null, booleans or numbers if they read as such in JSON, and otherwise
+//! strings, so that `007` stays a string. Blank lines are skipped.
+
+use std::io::Read;
+
+use crate::error::{Error, ErrorKind, TokenKind};
+use crate::map::Map;
+use crate::pointer::Pointer;
+use crate::span::{Position, Span};
+use crate::value::Value;
+
+/// How `to_csv` writes a table, and `from_csv` reads one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Between fields, `,` by default.
+    pub delimiter: char,
+    /// For null and missing values, empty by default.
+    pub null: String,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            null: String::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Tab-separated values.
+    pub fn tsv() -> Self {
+        CsvOptions {
+            delimiter: '\t',
+            ..CsvOptions::default()
+        }
+    }
+}
+
+/// Reads a table with a header line into an array of objects, one for each
+/// line after the header. Every line must have as many fields as the header.
+pub fn from_csv<R>(mut reader: R, options: &CsvOptions) -> Result<Value, Error>
+where
+    R: Read,
+{
+    let mut csv = String::new();
+    reader
+        .read_to_string(&mut csv)
+        .map_err(|e| Error::new(ErrorKind::Custom(e.to_string())))?;
+
+    let mut reader = CsvReader {
+        chars: csv.chars().peekable(),
+        position: Position::default(),
+        delimiter: options.delimiter,
+    };
+    let header = match reader.row()? {
+        Some((fields, _)) => fields,
+        None => return Ok(Value::Array(Vec::new())),
+    };
+    let mut keys = Vec::with_capacity(header.len());
+    for (key, _, span) in header {
+        if keys.contains(&key) {
+            return Err(Error::new(ErrorKind::DuplicateKey(key)).with_span(span));
+        }
+        keys.push(key);
+    }
+
+    let mut rows = Vec::new();
+    while let Some((fields, span)) = reader.row()? {
+        if fields.len() != keys.len() {
+            let message = format!("expected {} fields, found {}", keys.len(), fields.len());
+            return Err(Error::new(ErrorKind::Custom(message)).with_span(span));
+        }
+        let row: Map<String, Value> = keys
+            .iter()
+            .zip(fields)
+            .map(|(key, (field, quoted, _))| (key.clone(), infer(field, quoted, options)))
+            .collect();
+        rows.push(Value::Object(row));
+    }
+    Ok(Value::Array(rows))
+}
+
+fn infer(field: String, quoted: bool, options: &CsvOptions) -> Value {
+    if quoted {
+        return Value::String(field);
+    }
+    match field.as_str() {
+        _ if field == options.null => Value::Null,
+        "true" => Value::Boolean(true),
+        "false" => Value::Boolean(false),
+        s if is_number(s) => match s.parse() {
+            Ok(i) => Value::Integer(i),
+            Err(_) => s.parse().map_or(Value::String(field), Value::Float),
+        },
+        _ => Value::String(field),
+    }
+}
+
+// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`, a number in JSON.
+fn is_number(s: &str) -> bool {
+    fn digits(s: &str) -> &str {
+        s.trim_start_matches(|c: char| c.is_ascii_digit())
+    }
+
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let rest = match s.strip_prefix('0') {
+        Some(rest) => rest,
+        None if s.starts_with(|c: char| c.is_ascii_digit()) => digits(s),
+        None => return false,
+    };
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) if fraction.starts_with(|c: char| c.is_ascii_digit()) => digits(fraction),
+        Some(_) => return false,
+        None => rest,
+    };
+    let rest = match rest.strip_prefix(['e', 'E']) {
+        Some(exponent) => {
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            if !exponent.starts_with(|c: char| c.is_ascii_digit()) {
+                return false;
+            }
+            digits(exponent)
+        }
+        None => rest,
+    };
+    rest.is_empty()
+}
+
+struct CsvReader<'a> {
+    chars: core::iter::Peekable<core::str::Chars<'a>>,
+    position: Position,
+    delimiter: char,
+}
+
+// A field, whether it was quoted, and its span.
+type Field = (String, bool, Span);
+
+impl CsvReader<'_> {
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        self.position.advance(c, self.chars.peek().copied());
+        Some(c)
+    }
+
+    // The fields of the next line that isn't blank, and the span of the line.
+    fn row(&mut self) -> Result<Option<(Vec<Field>, Span)>, Error> {
+        while let Some('\r' | '\n') = self.chars.peek() {
+            self.next();
+        }
+        if self.chars.peek().is_none() {
+            return Ok(None);
+        }
+
+        let start = self.position;
+        let mut fields = Vec::new();
+        loop {
+            fields.push(self.field()?);
+            match self.next() {
+                Some(c) if c == self.delimiter => continue,
+                Some('\r') if self.chars.peek() == Some(&'\n') => {
+                    self.next();
+                }
+                _ => (),
+            }
+            let span = Span {
+                start,
+                end: self.position,
+            };
+            return Ok(Some((fields, span)));
+        }
+    }
+
+    fn field(&mut self) -> Result<Field, Error> {
+        let start = self.position;
+        let mut field = String::new();
+        if self.chars.peek() != Some(&'"') {
+            while let Some(&c) = self.chars.peek() {
+                if c == self.delimiter || c == '\r' || c == '\n' {
+                    break;
+                }
+                field.push(c);
+                self.next();
+            }
+            let span = Span {
+                start,
+                end: self.position,
+            };
+            return Ok((field, false, span));
+        }
+
+        self.next();
+        loop {
+            match self.next() {
+                Some('"') if self.chars.peek() == Some(&'"') => {
+                    self.next();
+                    field.push('"');
+                }
+                Some('"') => break,
+                Some(c) => field.push(c),
+                None => {
+                    let kind = ErrorKind::UnexpectedEof {
+                        expected: vec![TokenKind::Char('"')],
+                    };
+                    return Err(Error::new(kind).with_span(Span {
+                        start,
+                        end: self.position,
+                    }));
+                }
+            }
+        }
+        match self.chars.peek() {
+            Some(&c) if c != self.delimiter && c != '\r' && c != '\n' => {
+                let kind = ErrorKind::UnexpectedCharacter {
+                    found: c,
+                    expected: vec![TokenKind::Char(self.delimiter)],
+                };
+                let mut end = self.position;
+                end.advance(c, None);
+                Err(Error::new(kind).with_span(Span {
+                    start: self.position,
+                    end,
+                }))
+            }
+            _ => {
+                let span = Span {
+                    start,
+                    end: self.position,
+                };
+                Ok((field, true, span))
+            }
+        }
+    }
+}
+
+/// Formats `value`, an array of objects, as a table, one line per object
+/// after the header.
+pub fn to_csv(value: &Value, options: &CsvOptions) -> Result<String, Error> {
+    let rows = match value {
+        Value::Array(rows) => rows,
+        _ => {
+            return Err(Error::new(ErrorKind::Custom(
+                "a table must be an array".into(),
+            )))
+        }
+    };
+
+    let mut header: Vec<&str> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let map = match row {
+            Value::Object(map) => map,
+            _ => {
+                return Err(
+                    Error::new(ErrorKind::Custom("a row must be an object".into()))
+                        .with_pointer(Pointer::root().index(i)),
+                )
+            }
+        };
+        for key in map.keys() {
+            if !header.contains(&key.as_str()) {
+                header.push(key);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for (i, key) in header.iter().enumerate() {
+        write_field(&mut out, i, key, false, options);
+    }
+    out.push('\n');
+    for row in rows {
+        for (i, key) in header.iter().enumerate() {
+            match &row[*key] {
+                Value::Null => write_field(&mut out, i, &options.null, false, options),
+                Value::String(s) => write_field(&mut out, i, s, *s == options.null, options),
+                value => write_field(&mut out, i, &crate::stringify(value), false, options),
+            }
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn write_field(out: &mut String, i: usize, field: &str, quote: bool, options: &CsvOptions) {
+    if i > 0 {
+        out.push(options.delimiter);
+    }
+    let special = |c| c == options.delimiter || c == '"' || c == '\n' || c == '\r';
+    if quote || field.contains(special) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
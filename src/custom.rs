@@ -0,0 +1,28 @@
+use alloc::sync::Arc;
+use core::any::Any;
+use core::fmt;
+
+use crate::value::Value;
+
+/// A type carried through a tree of values as `Value::Custom`, such as a
+/// decimal amount that a float would round, or an identifier with its own
+/// validation.
+pub trait Scalar: Any + fmt::Debug + Send + Sync {
+    /// The standard value that stands for this one where the tree is
+    /// written out or converted: a string for the decimal amount, say.
+    fn to_value(&self) -> Value;
+}
+
+impl Value {
+    pub fn custom<T: Scalar>(scalar: T) -> Self {
+        Self::Custom(Arc::new(scalar))
+    }
+
+    /// The scalar, if this is a custom one of type `T`.
+    pub fn to_custom<T: Scalar>(&self) -> Option<&T> {
+        match self {
+            Self::Custom(scalar) => (&**scalar as &dyn Any).downcast_ref(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,628 @@
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::value::{BorrowedStrDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::error::{Error, ErrorKind, TokenKind};
+use crate::options::{KeyMatching, ParseOptions};
+use crate::parser::Parser;
+use crate::pointer::Pointer;
+use crate::span::{Position, Span};
+use crate::value::Value;
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::new(ErrorKind::Custom(msg.to_string()))
+    }
+}
+
+enum Segment<'de> {
+    Key(Cow<'de, str>),
+    Index(usize),
+}
+
+pub struct Deserializer<'de> {
+    json: &'de str,
+    parser: Parser<'de>,
+    path: Vec<Segment<'de>>,
+    key_span: Option<Span>,
+    unused: Vec<UnusedKey>,
+    ignoring: bool,
+    key_matching: KeyMatching,
+    // The fields of the struct about to be deserialized, when its keys are
+    // matched loosely.
+    fields: &'static [&'static str],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnusedKey {
+    pub pointer: Pointer,
+    pub span: Span,
+}
+
+pub fn from_str<'a, T>(json: &'a str) -> Result<T, Error>
+where
+    T: de::Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_str(json);
+    let value = deserializer.value(PhantomData::<T>)?;
+
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Like `from_str`, but also returns the object keys that the target type
+/// ignored, e.g. misspelled fields that silently fell back to defaults.
+pub fn from_str_with_unused<'a, T>(json: &'a str) -> Result<(T, Vec<UnusedKey>), Error>
+where
+    T: de::Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_str(json);
+    let value = deserializer.value(PhantomData::<T>)?;
+
+    deserializer.end()?;
+    Ok((value, deserializer.unused))
+}
+
+impl<'de> Deserializer<'de> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &'de str) -> Self {
+        let mut parser = Parser::with_options(json, ParseOptions::default());
+
+        parser.next();
+        Deserializer {
+            json,
+            parser,
+            path: Vec::new(),
+            key_span: None,
+            unused: Vec::new(),
+            ignoring: false,
+            key_matching: KeyMatching::default(),
+            fields: &[],
+        }
+    }
+
+    /// Matches object keys to struct fields as `matching` says, rather than
+    /// exactly. A field is preferred to others that match loosely when the
+    /// key is its name.
+    pub fn with_key_matching(mut self, matching: KeyMatching) -> Self {
+        self.key_matching = matching;
+        self
+    }
+
+    pub fn unused_keys(&self) -> &[UnusedKey] {
+        &self.unused
+    }
+
+    pub fn end(&mut self) -> Result<(), Error> {
+        self.parser.skip_comments()?;
+
+        match self.parser.ch {
+            None => Ok(()),
+            Some(_) => Err(self.parser.unexpected(&[TokenKind::EndOfInput])),
+        }
+    }
+
+    /// An iterator over the elements of the top-level array, deserialized
+    /// one at a time, so that only one is held in memory at once. It ends
+    /// after the first error.
+    #[allow(clippy::should_implement_trait)]
+    pub fn into_iter<T>(self) -> ArrayIter<'de, T>
+    where
+        T: de::Deserialize<'de>,
+    {
+        ArrayIter {
+            de: self,
+            index: 0,
+            started: false,
+            done: false,
+            marker: PhantomData,
+        }
+    }
+
+    fn pointer(&self) -> Pointer {
+        self.path
+            .iter()
+            .fold(Pointer::root(), |pointer, segment| match segment {
+                Segment::Key(key) => pointer.key(key),
+                Segment::Index(i) => pointer.index(*i),
+            })
+    }
+
+    // Attaches the current pointer to errors, and the span of the value
+    // starting at `start` to errors raised by `Deserialize` impls. Errors are
+    // located by the innermost value they occur in.
+    fn locate<T>(&self, start: Position, result: Result<T, Error>) -> Result<T, Error> {
+        match result {
+            Err(mut error) if error.pointer().is_none() => {
+                if error.span().is_none() {
+                    error = error.with_span(Span {
+                        start,
+                        end: self.parser.position,
+                    });
+                }
+                Err(error.with_pointer(self.pointer()))
+            }
+            _ => result,
+        }
+    }
+
+    // Deserializes the next value, locating errors raised once it has been
+    // read, as by enums that buffer their contents to find their tag.
+    fn value<T>(&mut self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.parser.skip_comments()?;
+        let start = self.parser.position;
+        let result = seed.deserialize(&mut *self);
+        self.locate(start, result)
+    }
+
+    // The string at the parser, unquoted, if it has no escapes and can be
+    // borrowed from the input as it is, moving past it then.
+    fn borrowed_str(&mut self) -> Option<&'de str> {
+        let mark = match self.parser.ch {
+            Some(c @ '"') | Some(c @ '\'') => c,
+            _ => return None,
+        };
+        let rest = &self.json[self.parser.position.offset + 1..];
+        let end = rest.find(|c: char| c == mark || c == '\\' || c < ' ')?;
+        if !rest[end..].starts_with(mark) {
+            return None;
+        }
+
+        let s = &rest[..end];
+        for _ in 0..s.chars().count() + 2 {
+            self.parser.next();
+        }
+        Some(s)
+    }
+
+    // A key or variant name, borrowed unless it has escapes.
+    fn parse_key(&mut self) -> Result<Cow<'de, str>, Error> {
+        if let Some(s) = self.borrowed_str() {
+            return Ok(Cow::Borrowed(s));
+        }
+        let start = self.parser.position.offset;
+        let key = self.parser.parse_key()?;
+        let text = &self.json[start..self.parser.position.offset];
+        Ok(if text == key {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(key)
+        })
+    }
+
+    fn parse_any<V>(&mut self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let fields = core::mem::take(&mut self.fields);
+        match self.parser.ch {
+            Some('[') => {
+                self.parser.enter()?;
+                self.parser.next();
+                let depth = self.path.len();
+                let result = visitor.visit_seq(Seq {
+                    de: &mut *self,
+                    index: 0,
+                });
+                self.path.truncate(depth);
+                let result = result.and_then(|value| self.end_container(']').map(|_| value));
+                self.parser.leave();
+                result
+            }
+            Some('{') => {
+                self.parser.enter()?;
+                self.parser.next();
+                let depth = self.path.len();
+                let result = visitor.visit_map(Map {
+                    de: &mut *self,
+                    first: true,
+                    fields,
+                });
+                self.path.truncate(depth);
+                let result = result.and_then(|value| self.end_container('}').map(|_| value));
+                self.parser.leave();
+                result
+            }
+            _ => {
+                if let Some(s) = self.borrowed_str() {
+                    return visitor.visit_borrowed_str(s);
+                }
+                match self.parser.parse_value()? {
+                    Value::Null => visitor.visit_unit(),
+                    Value::Boolean(b) => visitor.visit_bool(b),
+                    Value::Integer(i) => visitor.visit_i32(i),
+                    Value::Float(f) => visitor.visit_f64(f),
+                    Value::String(s) => visitor.visit_string(s),
+                    Value::Array(_) | Value::Object(_) | Value::Custom(_) => {
+                        Err(self.parser.unexpected(&[TokenKind::Value]))
+                    }
+                }
+            }
+        }
+    }
+
+    fn end_container(&mut self, close: char) -> Result<(), Error> {
+        self.parser.skip_comments()?;
+        if let Some(',') = self.parser.ch {
+            self.parser.next();
+            self.parser.skip_comments()?;
+        }
+        self.parser.consume(close)
+    }
+
+    // Moves past the separator preceding an element or entry. Returns false
+    // at the end of the container.
+    fn next_entry(&mut self, first: bool, close: char) -> Result<bool, Error> {
+        self.parser.skip_comments()?;
+
+        if !first {
+            match self.parser.ch {
+                Some(',') => {
+                    self.parser.next();
+                    self.parser.skip_comments()?;
+                }
+                Some(c) if c == close => return Ok(false),
+                _ => {
+                    let expected = [TokenKind::Char(','), TokenKind::Char(close)];
+                    return Err(self.parser.unexpected(&expected));
+                }
+            }
+        }
+
+        match self.parser.ch {
+            Some(c) => Ok(c != close),
+            None => {
+                let entry = if close == ']' {
+                    TokenKind::Value
+                } else {
+                    TokenKind::Key
+                };
+                Err(self.parser.unexpected(&[entry, TokenKind::Char(close)]))
+            }
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.parser.skip_comments()?;
+        let start = self.parser.position;
+        let result = self.parse_any(visitor);
+        self.locate(start, result)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.parser.skip_comments()?;
+        let start = self.parser.position;
+        let result = match self.parser.ch {
+            Some('n') => {
+                self.parser.parse_null()?;
+                visitor.visit_none()
+            }
+            _ => visitor.visit_some(&mut *self),
+        };
+        self.locate(start, result)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.parser.skip_comments()?;
+        let start = self.parser.position;
+        let result = match self.parser.ch {
+            Some('"') | Some('\'') => match self.parse_key()? {
+                Cow::Borrowed(s) => visitor.visit_enum(BorrowedStrDeserializer::new(s)),
+                Cow::Owned(s) => visitor.visit_enum(StringDeserializer::new(s)),
+            },
+            Some('{') => {
+                self.parser.enter()?;
+                self.parser.next();
+                let depth = self.path.len();
+                let result = visitor.visit_enum(Enum { de: &mut *self });
+                let result = self.locate(start, result);
+                self.path.truncate(depth);
+                let result = result.and_then(|value| self.end_container('}').map(|_| value));
+                self.parser.leave();
+                result
+            }
+            _ => self.parse_any(visitor),
+        };
+        self.locate(start, result)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.key_matching != KeyMatching::default() {
+            self.fields = fields;
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.parser.skip_comments()?;
+        let start = self.parser.position;
+        // Strings are taken as their UTF-8 bytes, arrays as numbers.
+        let result = match self.parser.ch {
+            Some('"') | Some('\'') => match self.parse_key()? {
+                Cow::Borrowed(s) => visitor.visit_borrowed_bytes(s.as_bytes()),
+                Cow::Owned(s) => visitor.visit_byte_buf(s.into_bytes()),
+            },
+            _ => self.parse_any(visitor),
+        };
+        self.locate(start, result)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.ignoring {
+            return self.deserialize_any(visitor);
+        }
+        if let (Some(Segment::Key(_)), Some(span)) = (self.path.last(), self.key_span) {
+            self.unused.push(UnusedKey {
+                pointer: self.pointer(),
+                span,
+            });
+        }
+
+        // Keys nested inside an ignored value are not reported separately.
+        self.ignoring = true;
+        let result = self.deserialize_any(visitor);
+        self.ignoring = false;
+        result
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map
+        identifier
+    }
+}
+
+/// The elements of a top-level array, from `Deserializer::into_iter`.
+pub struct ArrayIter<'de, T> {
+    de: Deserializer<'de>,
+    index: usize,
+    started: bool,
+    done: bool,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T> ArrayIter<'de, T>
+where
+    T: de::Deserialize<'de>,
+{
+    fn next_element(&mut self) -> Result<Option<T>, Error> {
+        if !self.started {
+            self.started = true;
+            self.de.parser.skip_comments()?;
+            if self.de.parser.ch != Some('[') {
+                return Err(self.de.parser.unexpected(&[TokenKind::Char('[')]));
+            }
+            self.de.parser.enter()?;
+            self.de.parser.next();
+        }
+
+        if !self.de.next_entry(self.index == 0, ']')? {
+            self.de.end_container(']')?;
+            self.de.parser.leave();
+            self.de.end()?;
+            return Ok(None);
+        }
+
+        self.de.path.push(Segment::Index(self.index));
+        let value = self.de.value(PhantomData::<T>)?;
+        self.de.path.pop();
+        self.index += 1;
+        Ok(Some(value))
+    }
+}
+
+impl<'de, T> Iterator for ArrayIter<'de, T>
+where
+    T: de::Deserialize<'de>,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_element();
+        if !matches!(result, Ok(Some(_))) {
+            self.done = true;
+        }
+        result.transpose()
+    }
+}
+
+struct Seq<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    index: usize,
+}
+
+impl<'de, 'a> SeqAccess<'de> for Seq<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if !self.de.next_entry(self.index == 0, ']')? {
+            return Ok(None);
+        }
+
+        self.de.path.push(Segment::Index(self.index));
+        let value = self.de.value(seed)?;
+        self.de.path.pop();
+        self.index += 1;
+        Ok(Some(value))
+    }
+}
+
+struct Map<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+    fields: &'static [&'static str],
+}
+
+impl<'de, 'a> MapAccess<'de> for Map<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if !self.de.next_entry(self.first, '}')? {
+            return Ok(None);
+        }
+        self.first = false;
+
+        let start = self.de.parser.position;
+        let key = self.de.parse_key()?;
+        self.de.key_span = Some(Span {
+            start,
+            end: self.de.parser.position,
+        });
+
+        self.de.path.push(Segment::Key(key.clone()));
+        let fields = self.fields.iter().map(|field| (*field, ()));
+        let key = match self.de.key_matching.best(fields, &key) {
+            Some((field, ())) => Cow::Borrowed(field),
+            None => key,
+        };
+        let result = deserialize_str(seed, key).map(Some);
+        self.de.locate(start, result)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.de.parser.skip_comments()?;
+        self.de.parser.consume(':')?;
+
+        let value = self.de.value(seed)?;
+        self.de.path.pop();
+        Ok(value)
+    }
+}
+
+struct Enum<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.de.parser.skip_comments()?;
+        let variant = self.de.parse_key()?;
+
+        self.de.path.push(Segment::Key(variant.clone()));
+        let value = deserialize_str(seed, variant)?;
+
+        self.de.parser.skip_comments()?;
+        self.de.parser.consume(':')?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.de, "", fields, visitor)
+    }
+}
+
+fn deserialize_str<'de, T>(seed: T, s: Cow<'de, str>) -> Result<T::Value, Error>
+where
+    T: DeserializeSeed<'de>,
+{
+    match s {
+        Cow::Borrowed(s) => seed.deserialize(BorrowedStrDeserializer::new(s)),
+        Cow::Owned(s) => seed.deserialize(StringDeserializer::new(s)),
+    }
+}
@@ -0,0 +1,112 @@
+use core::fmt;
+
+use crate::value::Value;
+
+/// A value in `Debug` output as it reads in JSON, with the structure that
+/// `{:#?}` spreads over lines, from `Value::debug`. Deep or long arrays and
+/// objects can be cut short.
+#[derive(Clone, Copy)]
+pub struct DebugValue<'a> {
+    value: &'a Value,
+    max_depth: Option<usize>,
+    max_elements: Option<usize>,
+    ellipsis: &'a str,
+}
+
+impl Value {
+    /// Formats the value with `{:?}` or `{:#?}` more concisely than its own
+    /// `Debug`, e.g. `{"a": [1, null]}` for `Object({"a": Array([Integer(1),
+    /// Null])})`.
+    pub fn debug(&self) -> DebugValue<'_> {
+        DebugValue {
+            value: self,
+            max_depth: None,
+            max_elements: None,
+            ellipsis: "...",
+        }
+    }
+}
+
+impl<'a> DebugValue<'a> {
+    /// Writes arrays and objects nested deeper than `depth` as an ellipsis.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Writes only the first `count` elements of arrays and objects, then an
+    /// ellipsis with how many were left out.
+    pub fn max_elements(mut self, count: usize) -> Self {
+        self.max_elements = Some(count);
+        self
+    }
+
+    /// What stands for what was left out, `...` by default.
+    pub fn ellipsis(mut self, ellipsis: &'a str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    fn at(&self, value: &'a Value) -> Self {
+        DebugValue {
+            value,
+            max_depth: self.max_depth.map(|depth| depth.saturating_sub(1)),
+            ..*self
+        }
+    }
+}
+
+impl fmt::Debug for DebugValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let limit = self.max_elements.unwrap_or(usize::MAX);
+        let rest = Rest(self.value.len().saturating_sub(limit), self.ellipsis);
+
+        match self.value {
+            Value::Null => f.write_str("null"),
+            Value::Boolean(b) => fmt::Debug::fmt(b, f),
+            Value::Integer(i) => fmt::Debug::fmt(i, f),
+            Value::Float(n) => fmt::Debug::fmt(n, f),
+            Value::String(s) => fmt::Debug::fmt(s, f),
+            Value::Custom(c) => fmt::Debug::fmt(c, f),
+            Value::Array(v) if v.is_empty() => f.write_str("[]"),
+            Value::Object(m) if m.is_empty() => f.write_str("{}"),
+            Value::Array(_) if self.max_depth == Some(0) => write!(f, "[{}]", self.ellipsis),
+            Value::Object(_) if self.max_depth == Some(0) => write!(f, "{{{}}}", self.ellipsis),
+            Value::Array(v) => {
+                let mut list = f.debug_list();
+                list.entries(v.iter().take(limit).map(|value| self.at(value)));
+                if rest.0 > 0 {
+                    list.entry(&rest);
+                }
+                list.finish()
+            }
+            // A set of entries, rather than a map, to end with the rest.
+            Value::Object(m) => {
+                let mut set = f.debug_set();
+                set.entries(m.iter().take(limit).map(|(k, v)| Entry(k, self.at(v))));
+                if rest.0 > 0 {
+                    set.entry(&rest);
+                }
+                set.finish()
+            }
+        }
+    }
+}
+
+struct Entry<'a>(&'a str, DebugValue<'a>);
+
+impl fmt::Debug for Entry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: ", self.0)?;
+        fmt::Debug::fmt(&self.1, f)
+    }
+}
+
+// How many elements were left out.
+struct Rest<'a>(usize, &'a str);
+
+impl fmt::Debug for Rest<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} more", self.1, self.0)
+    }
+}
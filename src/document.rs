@@ -0,0 +1,273 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::error::Error;
+use crate::lexer::tokenize;
+use crate::options::ParseOptions;
+use crate::parser::Parser;
+use crate::pointer::Pointer;
+use crate::span::{Position, Span};
+use crate::tree::Tree;
+use crate::value::Value;
+use crate::warning::Warning;
+
+/// A JSON5 document being edited, as in an editor: its text, and what
+/// parsing it gave, kept up to date edit by edit.
+#[derive(Clone, Debug)]
+pub struct Document {
+    text: String,
+    parsed: Result<Parsed, Error>,
+}
+
+#[derive(Clone, Debug)]
+struct Parsed {
+    value: Value,
+    // Values before the arrays and objects containing them, as from
+    // `parse_with_spans`.
+    spans: Vec<(Pointer, Span)>,
+    // Keys in the order they are written, as from `parse_with_key_spans`.
+    key_spans: Vec<(Pointer, Span)>,
+    warnings: Vec<Warning>,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let parsed = parse(&text);
+        Document { text, parsed }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The parsed document, unless it has an error.
+    pub fn value(&self) -> Option<&Value> {
+        self.parsed.as_ref().ok().map(|p| &p.value)
+    }
+
+    pub fn error(&self) -> Option<&Error> {
+        self.parsed.as_ref().err()
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        match &self.parsed {
+            Ok(parsed) => &parsed.warnings,
+            Err(_) => &[],
+        }
+    }
+
+    /// The span of the value at `pointer`.
+    pub fn span(&self, pointer: &Pointer) -> Option<Span> {
+        let parsed = self.parsed.as_ref().ok()?;
+        // With duplicate keys, the last value is the one kept.
+        let found = parsed.spans.iter().rev().find(|(p, _)| p == pointer);
+        found.map(|(_, span)| *span)
+    }
+
+    /// The span of the key of the value at `pointer`, as written, quotes
+    /// included.
+    pub fn key_span(&self, pointer: &Pointer) -> Option<Span> {
+        let parsed = self.parsed.as_ref().ok()?;
+        let found = parsed.key_spans.iter().rev().find(|(p, _)| p == pointer);
+        found.map(|(_, span)| *span)
+    }
+
+    /// The JSON Pointer and span of the innermost value at byte `offset`.
+    pub fn value_at(&self, offset: usize) -> Option<(&Pointer, Span)> {
+        let parsed = self.parsed.as_ref().ok()?;
+        let found = parsed
+            .spans
+            .iter()
+            .find(|(_, span)| span.start.offset <= offset && offset < span.end.offset);
+        found.map(|(pointer, span)| (pointer, *span))
+    }
+
+    /// The text of the value at `pointer`, exactly as it is in the document,
+    /// with its key and the comments that go with it: those on the lines
+    /// before it and after it on its line. Its comma is left out. The whole
+    /// document is returned for the root pointer.
+    pub fn extract(&self, pointer: &Pointer) -> Option<String> {
+        self.parsed.as_ref().ok()?;
+        let tokens = tokenize(&self.text);
+        let tree = Tree::build(&self.text, &tokens);
+
+        let (last, parents) = match pointer.tokens().split_last() {
+            Some(split) => split,
+            None => return Some(self.text.clone()),
+        };
+        let mut node = &tree.root;
+        for token in parents {
+            node = &node.entry(token)?.value;
+        }
+        let entry = node.entry(last)?;
+
+        let mut out = String::from(entry.comments);
+        if let Some((key, separator)) = entry.key {
+            out.push_str(key);
+            out.push_str(separator);
+        }
+        entry.value.write(&mut out, tree.line_ending);
+        if !entry.trailing.is_empty() {
+            out.push_str(entry.after_value);
+            out.push_str(entry.trailing);
+        }
+        Some(out)
+    }
+
+    /// Replaces the text in the byte `range` with `new_text` and returns the
+    /// updated warnings, or the error the document now has.
+    ///
+    /// When the document parsed before the edit and the edit falls within an
+    /// array or object, only the innermost such is parsed again. Otherwise,
+    /// or if that fails, the whole document is.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds or not on character boundaries, as
+    /// `String::replace_range` does.
+    pub fn apply_edit(
+        &mut self,
+        range: Range<usize>,
+        new_text: &str,
+    ) -> Result<&[Warning], &Error> {
+        let container = match &self.parsed {
+            Ok(parsed) => parsed.spans.iter().position(|(_, span)| {
+                let text = &self.text[span.start.offset..];
+                (text.starts_with('[') || text.starts_with('{'))
+                    && span.start.offset < range.start
+                    && range.end < span.end.offset
+            }),
+            Err(_) => None,
+        };
+        let removed = range.end - range.start;
+        self.text.replace_range(range, new_text);
+
+        let reparsed = match container {
+            Some(i) => self.reparse(i, new_text.len() as isize - removed as isize),
+            None => false,
+        };
+        if !reparsed {
+            self.parsed = parse(&self.text);
+        }
+
+        match &self.parsed {
+            Ok(parsed) => Ok(&parsed.warnings),
+            Err(error) => Err(error),
+        }
+    }
+
+    // Parses the container at `spans[i]` again, its length having changed by
+    // `delta`. Returns whether that worked, leaving the document as it was
+    // otherwise.
+    fn reparse(&mut self, i: usize, delta: isize) -> bool {
+        let parsed = match &mut self.parsed {
+            Ok(parsed) => parsed,
+            Err(_) => return false,
+        };
+        let (pointer, old) = parsed.spans[i].clone();
+        // Which of several values for a duplicate key is kept depends on
+        // the others.
+        if parsed.spans.iter().filter(|(p, _)| *p == pointer).count() > 1 {
+            return false;
+        }
+        let path = pointer.tokens().to_vec();
+
+        let mut parser = Parser::resume(
+            &self.text[old.start.offset..],
+            ParseOptions::default(),
+            old.start,
+            path.clone(),
+        );
+        parser.spans = Some(Vec::new());
+        parser.key_spans = Some(Vec::new());
+        parser.next();
+
+        let value = match parser.parse_value() {
+            Ok(value) => value,
+            Err(_) => return false,
+        };
+        let end = parser.position;
+        // The container must end where its old self, moved by the edit, did;
+        // otherwise the edit changed more than its contents.
+        if end.offset as isize != old.end.offset as isize + delta {
+            return false;
+        }
+        match value_mut(&mut parsed.value, &path) {
+            Some(slot) => *slot = value,
+            None => return false,
+        }
+
+        let shift = |p: Position| {
+            if p.offset < old.end.offset {
+                return p;
+            }
+            Position {
+                offset: p.offset - old.end.offset + end.offset,
+                line: p.line - old.end.line + end.line,
+                column: if p.line == old.end.line {
+                    p.column - old.end.column + end.column
+                } else {
+                    p.column
+                },
+            }
+        };
+        let shift_span = |s: Span| Span {
+            start: shift(s.start),
+            end: shift(s.end),
+        };
+
+        // What was inside the container comes right before it.
+        let first = parsed.spans[..i]
+            .iter()
+            .rposition(|(p, _)| p == &pointer || !p.tokens().starts_with(pointer.tokens()))
+            .map_or(0, |j| j + 1);
+        let after: Vec<(Pointer, Span)> = parsed.spans.drain(i + 1..).collect();
+        parsed.spans.truncate(first);
+        parsed.spans.extend(parser.spans.take().unwrap_or_default());
+        parsed
+            .spans
+            .extend(after.into_iter().map(|(p, s)| (p, shift_span(s))));
+
+        let inside =
+            |s: &Span| old.start.offset <= s.start.offset && s.end.offset <= old.end.offset;
+        parsed.warnings.retain(|w| !inside(&w.span));
+        for warning in &mut parsed.warnings {
+            warning.span = shift_span(warning.span);
+        }
+        parsed.warnings.extend(parser.warnings);
+        parsed.warnings.sort_by_key(|w| w.span.start.offset);
+
+        parsed.key_spans.retain(|(_, s)| !inside(s));
+        for (_, span) in &mut parsed.key_spans {
+            *span = shift_span(*span);
+        }
+        parsed
+            .key_spans
+            .extend(parser.key_spans.unwrap_or_default());
+        parsed.key_spans.sort_by_key(|(_, s)| s.start.offset);
+        true
+    }
+}
+
+fn parse(text: &str) -> Result<Parsed, Error> {
+    let mut parser = Parser::with_options(text, ParseOptions::default());
+    parser.spans = Some(Vec::new());
+    parser.key_spans = Some(Vec::new());
+
+    let value = crate::parse_document(&mut parser)?;
+    Ok(Parsed {
+        value,
+        spans: parser.spans.unwrap_or_default(),
+        key_spans: parser.key_spans.unwrap_or_default(),
+        warnings: parser.warnings,
+    })
+}
+
+fn value_mut<'a>(value: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |value, token| match value {
+        Value::Array(v) => v.get_mut(crate::pointer::parse_index(token)?),
+        Value::Object(m) => m.get_mut(token),
+        _ => None,
+    })
+}
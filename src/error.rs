@@ -1,25 +1,278 @@
-#[derive(Debug)]
-pub enum Error {
-    UnexpectedCharacter,
-    UnexpectedEndOfJson,
-    UnparseableNumber,
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
+use crate::map::Map;
+use crate::parser::{is_identifier_part, is_identifier_start};
+use crate::pointer::{parse_index, Pointer};
+use crate::span::Span;
+use crate::value::Value;
+
+/// What the parser was looking for when it failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Value,
+    Key,
+    Number,
+    String,
+    HexDigit,
+    Char(char),
+    Literal(&'static str),
+    EndOfInput,
 }
 
-impl PartialEq for Error {
-    fn eq(&self, other: &Self) -> bool {
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::UnexpectedCharacter => match other {
-                Self::UnexpectedCharacter => true,
-                _ => false,
-            },
-            Self::UnexpectedEndOfJson => match other {
-                Self::UnexpectedEndOfJson => true,
-                _ => false,
-            },
-            Self::UnparseableNumber => match other {
-                Self::UnparseableNumber => true,
-                _ => false,
-            },
+            Self::Value => f.write_str("a value"),
+            Self::Key => f.write_str("an object key"),
+            Self::Number => f.write_str("a number"),
+            Self::String => f.write_str("a string"),
+            Self::HexDigit => f.write_str("a hexadecimal digit"),
+            Self::Char(c) => write!(f, "`{}`", c),
+            Self::Literal(s) => write!(f, "`{}`", s),
+            Self::EndOfInput => f.write_str("end of input"),
         }
     }
 }
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    UnexpectedCharacter {
+        found: char,
+        expected: Vec<TokenKind>,
+    },
+    UnexpectedEof {
+        expected: Vec<TokenKind>,
+    },
+    InvalidNumber,
+    NumberOverflow,
+    InvalidEscape,
+    ExceededMaxDepth,
+    DuplicateKey(String),
+    /// A reserved word such as `null` as an unquoted key, where
+    /// `ParseOptions::allow_reserved_word_keys` doesn't allow it.
+    ReservedWordKey(String),
+    /// An error with only a message: raised by a `Deserialize` impl, or by
+    /// the extensions of the parser, such as expressions, units and
+    /// literals, and by conversions and other operations on values.
+    Custom(String),
+    /// Parsing was cancelled through `Limits::cancel`.
+    Cancelled,
+    /// The document is longer, or has more values, than `Limits` allow.
+    BudgetExceeded,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedCharacter { found, expected } => {
+                write!(f, "unexpected character `{}`", found.escape_debug())?;
+                write_expected(f, expected)
+            }
+            Self::UnexpectedEof { expected } => {
+                f.write_str("unexpected end of JSON")?;
+                write_expected(f, expected)
+            }
+            Self::InvalidNumber => f.write_str("invalid number"),
+            Self::NumberOverflow => f.write_str("number out of range"),
+            Self::InvalidEscape => f.write_str("invalid escape sequence"),
+            Self::ExceededMaxDepth => f.write_str("exceeded maximum nesting depth"),
+            Self::DuplicateKey(key) => write!(f, "duplicate key `{}`", key),
+            Self::ReservedWordKey(key) => {
+                write!(f, "reserved word `{}` as a key must be quoted", key)
+            }
+            Self::Custom(message) => f.write_str(message),
+            Self::Cancelled => f.write_str("parsing cancelled"),
+            Self::BudgetExceeded => f.write_str("exceeded parsing budget"),
+        }
+    }
+}
+
+impl ErrorKind {
+    /// A name for the kind of error that doesn't change with its details,
+    /// e.g. `unexpected_character`, for programs to match on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedCharacter { .. } => "unexpected_character",
+            Self::UnexpectedEof { .. } => "unexpected_eof",
+            Self::InvalidNumber => "invalid_number",
+            Self::NumberOverflow => "number_overflow",
+            Self::InvalidEscape => "invalid_escape",
+            Self::ExceededMaxDepth => "exceeded_max_depth",
+            Self::DuplicateKey(_) => "duplicate_key",
+            Self::ReservedWordKey(_) => "reserved_word_key",
+            Self::Custom(_) => "custom",
+            Self::Cancelled => "cancelled",
+            Self::BudgetExceeded => "budget_exceeded",
+        }
+    }
+}
+
+fn write_expected(f: &mut fmt::Formatter, expected: &[TokenKind]) -> fmt::Result {
+    for (i, token) in expected.iter().enumerate() {
+        let separator = match i {
+            0 => ", expected ",
+            _ if i == expected.len() - 1 => " or ",
+            _ => ", ",
+        };
+        write!(f, "{}{}", separator, token)?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    inner: Box<ErrorImpl>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ErrorImpl {
+    kind: ErrorKind,
+    span: Option<Span>,
+    pointer: Option<Pointer>,
+    name: Option<String>,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind) -> Self {
+        Error {
+            inner: Box::new(ErrorImpl {
+                kind,
+                span: None,
+                pointer: None,
+                name: None,
+            }),
+        }
+    }
+
+    pub(crate) fn with_span(mut self, span: Span) -> Self {
+        self.inner.span = Some(span);
+        self
+    }
+
+    pub(crate) fn with_pointer(mut self, pointer: Pointer) -> Self {
+        self.inner.pointer = Some(pointer);
+        self
+    }
+
+    pub(crate) fn with_name(mut self, name: String) -> Self {
+        self.inner.name = Some(name);
+        self
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.inner.kind
+    }
+
+    /// Where in the document the error occurred.
+    pub fn span(&self) -> Option<Span> {
+        self.inner.span
+    }
+
+    /// The JSON Pointer to the value being deserialized when the error
+    /// occurred.
+    pub fn pointer(&self) -> Option<&Pointer> {
+        self.inner.pointer.as_ref()
+    }
+
+    /// The name given to the document with `parse_named`, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    /// The `pointer` written as a path of accessors, e.g. `services[2].backoff`
+    /// for `/services/2/backoff`. Keys that aren't identifiers are quoted, as
+    /// in `labels["app/name"]`, and numbers are taken to be indices.
+    pub fn path(&self) -> Option<String> {
+        let pointer = self.pointer()?;
+        let mut path = String::new();
+
+        for token in pointer.tokens() {
+            let mut chars = token.chars();
+            let identifier =
+                chars.next().is_some_and(is_identifier_start) && chars.all(is_identifier_part);
+            if parse_index(token).is_some() {
+                let _ = write!(path, "[{}]", token);
+            } else if identifier {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(token);
+            } else {
+                let _ = write!(path, "[{:?}]", token);
+            }
+        }
+        Some(path)
+    }
+
+    /// The error as an object, e.g. for a web service to respond with:
+    /// `kind`, the `code` of its kind, `message`, describing the kind,
+    /// `line` and `column`, `pointer`, `path` and `name`, each null when
+    /// unknown.
+    pub fn to_value(&self) -> Value {
+        let position = self.span().map(|span| span.start);
+        let string = |s: Option<&str>| s.map_or(Value::Null, |s| Value::String(s.into()));
+        let number = |n: Option<usize>| {
+            n.map_or(Value::Null, |n| match i32::try_from(n) {
+                Ok(n) => Value::Integer(n),
+                Err(_) => Value::Float(n as f64),
+            })
+        };
+
+        let mut m = Map::new();
+        m.insert("kind".into(), Value::String(self.kind().code().into()));
+        m.insert("message".into(), Value::String(self.kind().to_string()));
+        m.insert("line".into(), number(position.map(|p| p.line)));
+        m.insert("column".into(), number(position.map(|p| p.column)));
+        let pointer = self.pointer().map(Pointer::to_string);
+        m.insert("pointer".into(), string(pointer.as_deref()));
+        m.insert("path".into(), string(self.path().as_deref()));
+        m.insert("name".into(), string(self.name()));
+        Value::Object(m)
+    }
+}
+
+/// As `Error::to_value`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let position = self.span().map(|span| span.start);
+        let mut s = serializer.serialize_struct("Error", 7)?;
+        s.serialize_field("kind", self.kind().code())?;
+        s.serialize_field("message", &self.kind().to_string())?;
+        s.serialize_field("line", &position.map(|p| p.line))?;
+        s.serialize_field("column", &position.map(|p| p.column))?;
+        s.serialize_field("pointer", &self.pointer().map(Pointer::to_string))?;
+        s.serialize_field("path", &self.path())?;
+        s.serialize_field("name", &self.name())?;
+        s.end()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = &self.inner.name {
+            write!(f, "{}: ", name)?;
+        }
+        write!(f, "{}", self.inner.kind)?;
+        match &self.inner.pointer {
+            Some(p) if !p.is_root() => write!(f, " at {}", p)?,
+            _ => (),
+        }
+        match &self.inner.span {
+            Some(s) => write!(f, ", line {} column {}", s.start.line, s.start.column),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,103 @@
+//! Arithmetic and string interpolation for `parse_with_variables`.
+
+use alloc::format;
+use alloc::string::String;
+
+use crate::map::Map;
+use crate::value::Value;
+
+/// `a op b`, for `+`, `-`, `*`, `/` and `%`. Integers stay integers while
+/// the result is one and fits; `+` joins strings with scalars.
+pub(crate) fn apply(op: char, a: Value, b: Value) -> Result<Value, String> {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => {
+            let exact = match op {
+                '+' => a.checked_add(b),
+                '-' => a.checked_sub(b),
+                '*' => a.checked_mul(b),
+                '/' | '%' if b == 0 => return Err("division by zero".into()),
+                '/' if a.checked_rem(b) == Some(0) => a.checked_div(b),
+                '/' => None,
+                // Only `i32::MIN % -1` wraps, and its remainder is 0.
+                _ => Some(a.wrapping_rem(b)),
+            };
+            match exact {
+                Some(i) => Ok(Value::Integer(i)),
+                None => Ok(Value::Float(float(op, f64::from(a), f64::from(b)))),
+            }
+        }
+        (a @ Value::String(_), b) | (a, b @ Value::String(_)) if op == '+' => {
+            Ok(Value::String(format!("{}{}", text(&a)?, text(&b)?)))
+        }
+        (a, b) => match (a.to_f64(), b.to_f64()) {
+            (Some(a), Some(b)) => Ok(Value::Float(float(op, a, b))),
+            _ if op == '+' => Err("`+` needs numbers or strings".into()),
+            _ => Err(format!("`{}` needs numbers", op)),
+        },
+    }
+}
+
+pub(crate) fn negate(value: Value) -> Result<Value, String> {
+    match value {
+        Value::Integer(i) => Ok(i
+            .checked_neg()
+            .map_or(Value::Float(-f64::from(i)), Value::Integer)),
+        Value::Float(f) => Ok(Value::Float(-f)),
+        _ => Err("`-` needs numbers".into()),
+    }
+}
+
+fn float(op: char, a: f64, b: f64) -> f64 {
+    match op {
+        '+' => a + b,
+        '-' => a - b,
+        '*' => a * b,
+        '/' => a / b,
+        _ => a % b,
+    }
+}
+
+// A scalar as it reads in a string.
+pub(crate) fn text(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            Err("arrays and objects can't be joined with strings".into())
+        }
+        value => Ok(crate::stringify(value)),
+    }
+}
+
+/// `s` with every `${name}` replaced by the variable `name`, and `$${` by
+/// `${`.
+pub(crate) fn interpolate(s: &str, variables: &Map<String, Value>) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(i) = rest.find("${") {
+        if rest[..i].ends_with('$') {
+            out.push_str(&rest[..i]);
+            rest = &rest[i + 2..];
+            out.push('{');
+            continue;
+        }
+        out.push_str(&rest[..i]);
+        let end = match rest[i..].find('}') {
+            Some(end) => i + end,
+            None => return Err("unterminated `${`".into()),
+        };
+        out.push_str(&text(variable(Some(variables), &rest[i + 2..end])?)?);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+pub(crate) fn variable<'a>(
+    variables: Option<&'a Map<String, Value>>,
+    name: &str,
+) -> Result<&'a Value, String> {
+    variables
+        .and_then(|variables| variables.get(name))
+        .ok_or_else(|| format!("unknown variable `{}`", name))
+}
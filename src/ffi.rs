@@ -0,0 +1,298 @@
+//! C API for embedding the parser in non-Rust hosts.
+//!
+//! Values returned by `json5_parse` are owned by the caller and must be
+//! released with `json5_free`. Pointers obtained from the accessors borrow
+//! from their root value and become invalid once it is freed.
+//!
+//! Build a shared library with
+//! `cargo rustc --release --features ffi --lib --crate-type cdylib` and include
+//! `include/json5.h`, which is regenerated with
+//! `cbindgen --config cbindgen.toml --output include/json5.h`.
+
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::error::{Error, ErrorKind};
+use crate::value::Value;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Json5Error {
+    Ok,
+    UnexpectedCharacter,
+    UnexpectedEndOfJson,
+    UnparseableNumber,
+    ExceededMaxDepth,
+    Custom,
+    InvalidUtf8,
+    NullPointer,
+    NumberOverflow,
+    InvalidEscape,
+    DuplicateKey,
+    Cancelled,
+    BudgetExceeded,
+    ReservedWordKey,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Json5Type {
+    Null,
+    Boolean,
+    Integer,
+    Float,
+    String,
+    Array,
+    Object,
+}
+
+impl From<&Error> for Json5Error {
+    fn from(error: &Error) -> Self {
+        match error.kind() {
+            ErrorKind::UnexpectedCharacter { .. } => Self::UnexpectedCharacter,
+            ErrorKind::UnexpectedEof { .. } => Self::UnexpectedEndOfJson,
+            ErrorKind::InvalidNumber => Self::UnparseableNumber,
+            ErrorKind::NumberOverflow => Self::NumberOverflow,
+            ErrorKind::InvalidEscape => Self::InvalidEscape,
+            ErrorKind::ExceededMaxDepth => Self::ExceededMaxDepth,
+            ErrorKind::DuplicateKey(_) => Self::DuplicateKey,
+            ErrorKind::Custom(_) => Self::Custom,
+            ErrorKind::Cancelled => Self::Cancelled,
+            ErrorKind::BudgetExceeded => Self::BudgetExceeded,
+            ErrorKind::ReservedWordKey(_) => Self::ReservedWordKey,
+        }
+    }
+}
+
+unsafe fn set_error(error: *mut Json5Error, value: Json5Error) {
+    if !error.is_null() {
+        *error = value;
+    }
+}
+
+/// Parses a NUL-terminated UTF-8 string. Returns NULL on failure, in which
+/// case `error` (if not NULL) receives the reason.
+///
+/// # Safety
+///
+/// `input` must be NULL or a valid NUL-terminated string, and `error` must be
+/// NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn json5_parse(input: *const c_char, error: *mut Json5Error) -> *mut Value {
+    if input.is_null() {
+        set_error(error, Json5Error::NullPointer);
+        return ptr::null_mut();
+    }
+
+    let json = match CStr::from_ptr(input).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_error(error, Json5Error::InvalidUtf8);
+            return ptr::null_mut();
+        }
+    };
+
+    match crate::parse(json) {
+        Ok(value) => {
+            set_error(error, Json5Error::Ok);
+            Box::into_raw(Box::new(value))
+        }
+        Err(e) => {
+            set_error(error, Json5Error::from(&e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a value returned by `json5_parse`.
+///
+/// # Safety
+///
+/// `value` must be NULL or a pointer returned by `json5_parse` that has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn json5_free(value: *mut Value) {
+    if !value.is_null() {
+        drop(Box::from_raw(value));
+    }
+}
+
+/// # Safety
+///
+/// `value` must be NULL or a live value handle.
+#[no_mangle]
+pub unsafe extern "C" fn json5_type_of(value: *const Value) -> Json5Type {
+    match value.as_ref() {
+        None | Some(Value::Null) => Json5Type::Null,
+        Some(Value::Boolean(_)) => Json5Type::Boolean,
+        Some(Value::Integer(_)) => Json5Type::Integer,
+        Some(Value::Float(_)) => Json5Type::Float,
+        Some(Value::String(_)) => Json5Type::String,
+        Some(Value::Array(_)) => Json5Type::Array,
+        Some(Value::Object(_)) => Json5Type::Object,
+        Some(Value::Custom(c)) => json5_type_of(&c.to_value()),
+    }
+}
+
+/// Returns false if `value` is not a boolean.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle, and `out` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn json5_get_bool(value: *const Value, out: *mut bool) -> bool {
+    match value.as_ref().and_then(Value::to_bool) {
+        Some(b) if !out.is_null() => {
+            *out = b;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns false if `value` is not a number or does not fit in 32 bits.
+/// Floats are truncated toward zero.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle, and `out` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn json5_get_int(value: *const Value, out: *mut i32) -> bool {
+    match value
+        .as_ref()
+        .and_then(Value::to_i64_lossy)
+        .and_then(|i| i32::try_from(i).ok())
+    {
+        Some(i) if !out.is_null() => {
+            *out = i;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns false if `value` is not a number.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle, and `out` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn json5_get_double(value: *const Value, out: *mut f64) -> bool {
+    match value.as_ref().and_then(Value::to_f64) {
+        Some(f) if !out.is_null() => {
+            *out = f;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns the UTF-8 bytes of a string value, or NULL if `value` is not a
+/// string. The bytes are not NUL-terminated; their length is written to `len`.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle, and `len` must be NULL or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn json5_get_string(value: *const Value, len: *mut usize) -> *const c_char {
+    match value.as_ref().and_then(Value::to_string) {
+        Some(s) => {
+            if !len.is_null() {
+                *len = s.len();
+            }
+            s.as_ptr() as *const c_char
+        }
+        None => ptr::null(),
+    }
+}
+
+/// Returns the number of elements of an array or entries of an object, and 0
+/// for any other value.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle.
+#[no_mangle]
+pub unsafe extern "C" fn json5_len(value: *const Value) -> usize {
+    match value.as_ref() {
+        Some(Value::Array(v)) => v.len(),
+        Some(Value::Object(m)) => m.len(),
+        _ => 0,
+    }
+}
+
+/// Returns the element at `index`, or NULL if out of range or not an array.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle.
+#[no_mangle]
+pub unsafe extern "C" fn json5_array_get(value: *const Value, index: usize) -> *const Value {
+    match value.as_ref() {
+        Some(Value::Array(v)) => v.get(index).map_or(ptr::null(), |e| e as *const Value),
+        _ => ptr::null(),
+    }
+}
+
+/// Returns the value stored under the NUL-terminated `key`, or NULL if absent
+/// or not an object.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle, and `key` must be NULL or a
+/// valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn json5_object_get(value: *const Value, key: *const c_char) -> *const Value {
+    if key.is_null() {
+        return ptr::null();
+    }
+
+    let key = match CStr::from_ptr(key).to_str() {
+        Ok(k) => k,
+        Err(_) => return ptr::null(),
+    };
+
+    match value.as_ref() {
+        Some(Value::Object(m)) => m.get(key).map_or(ptr::null(), |e| e as *const Value),
+        _ => ptr::null(),
+    }
+}
+
+/// Returns the value of the `index`-th entry of an object and writes its key
+/// (not NUL-terminated) to `key` and `key_len`. Entry order is unspecified but
+/// stable while the object is alive. Each call walks the entries up to
+/// `index`.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live value handle, and `key` and `key_len` must
+/// be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn json5_object_entry(
+    value: *const Value,
+    index: usize,
+    key: *mut *const c_char,
+    key_len: *mut usize,
+) -> *const Value {
+    let (k, v) = match value.as_ref() {
+        Some(Value::Object(m)) => match m.iter().nth(index) {
+            Some(entry) => entry,
+            None => return ptr::null(),
+        },
+        _ => return ptr::null(),
+    };
+
+    if !key.is_null() {
+        *key = k.as_ptr() as *const c_char;
+    }
+    if !key_len.is_null() {
+        *key_len = k.len();
+    }
+    v
+}
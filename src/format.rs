@@ -0,0 +1,436 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::lexer::{line_ending, tokenize, SyntaxKind, Token};
+use crate::options::{LineEnding, TrailingCommas};
+
+/// One level of indentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tab,
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent::Spaces(2)
+    }
+}
+
+/// How `reformat_with_options` lays out a document. The indentation and
+/// line ending, unless given, are taken from the document, as
+/// `detect_style` finds them; comments are kept as they are unless asked
+/// otherwise.
+///
+/// Banner comments, those opening with a row of the same punctuation, such
+/// as `//////` or `/* ==== */`, are always kept verbatim.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub indent: Option<Indent>,
+    pub line_ending: Option<LineEnding>,
+    /// Rewrites `/* */` comments on lines of their own as `//` comments,
+    /// one per line, without the `*` that may start each line.
+    pub block_to_line_comments: bool,
+    /// Puts a space after the `//` of line comments that lack one.
+    pub space_after_line_comment: bool,
+    /// Starts comments on the line of what comes before them at this
+    /// column, counting from 1, or a space after it if the line is longer.
+    pub trailing_comment_column: Option<usize>,
+    /// Adds or removes commas after the last entries of arrays and objects,
+    /// rather than keeping them as written. Arrays and objects that aren't
+    /// empty are always reformatted over several lines, so `Multiline`
+    /// adds them as `Always` does.
+    pub trailing_commas: Option<TrailingCommas>,
+    /// Writes arrays of more numbers than this, without comments among
+    /// them, with this many to a line, and lined up in columns.
+    pub numbers_per_line: Option<usize>,
+}
+
+/// The indentation and line ending a document uses, so that changes to it
+/// can use them too.
+///
+/// The indentation is tabs if most indented lines start with one, and
+/// otherwise the number of spaces by which lines are most often indented
+/// more or less than the line before. The line ending is that of the first
+/// line. Lines within comments and strings aren't counted, and documents
+/// with no indented lines, or no lines, get the defaults.
+pub fn detect_style(json: &str) -> FormatOptions {
+    let mut tabs = 0;
+    let mut spaces = 0;
+    // How many times lines are indented by each number of spaces more or
+    // less than the line before.
+    let mut steps: Vec<(usize, usize)> = Vec::new();
+    let mut last = 0;
+
+    let tokens = tokenize(json);
+    for (i, token) in tokens.iter().enumerate() {
+        let text = token.text(json);
+        if token.kind != SyntaxKind::Whitespace {
+            continue;
+        }
+        // The indentation of the next line, if a token starts it.
+        let line = match text.rfind(['\n', '\r']) {
+            Some(end) if i + 1 < tokens.len() => &text[end + 1..],
+            _ => continue,
+        };
+
+        if line.starts_with('\t') {
+            tabs += 1;
+            continue;
+        }
+        let width = line.len() - line.trim_start_matches(' ').len();
+        if width > 0 {
+            spaces += 1;
+        }
+        let step = width.abs_diff(last);
+        last = width;
+        if step == 0 {
+            continue;
+        }
+        match steps.iter_mut().find(|(s, _)| *s == step) {
+            Some((_, count)) => *count += 1,
+            None => steps.push((step, 1)),
+        }
+    }
+
+    let indent = if tabs > spaces {
+        Indent::Tab
+    } else {
+        // The most common step, the smallest of those as common.
+        steps.sort_unstable();
+        let best = steps.iter().rev().max_by_key(|(_, count)| *count);
+        match best {
+            Some(&(step, _)) => Indent::Spaces(step),
+            None => Indent::default(),
+        }
+    };
+    FormatOptions {
+        indent: Some(indent),
+        line_ending: Some(line_ending(json, &tokens)),
+        ..FormatOptions::default()
+    }
+}
+
+/// Reformats a JSON5 document: one array element or object entry per line,
+/// indented one level per level of nesting, with a space after every colon.
+/// The document's own indentation and line ending are kept, as
+/// `detect_style` finds them.
+///
+/// Unlike parsing and stringifying it again, this works on the document's
+/// tokens, so comments, the spelling of strings and numbers, trailing commas
+/// and single blank lines between entries are all kept. The document must
+/// parse without errors with the default options.
+pub fn reformat(json: &str) -> Result<String, Error> {
+    reformat_with_options(json, &FormatOptions::default())
+}
+
+/// Like `reformat`, laid out as `options` says.
+pub fn reformat_with_options(json: &str, options: &FormatOptions) -> Result<String, Error> {
+    crate::parse(json)?;
+
+    let detected = detect_style(json);
+    let tokens = tokenize(json);
+    let mut formatter = Formatter {
+        out: String::with_capacity(json.len()),
+        indent_unit: options.indent.or(detected.indent).unwrap_or_default(),
+        line_ending: options
+            .line_ending
+            .or(detected.line_ending)
+            .unwrap_or_default(),
+        indent: 0,
+        line_break: false,
+        blank_line: false,
+    };
+    let mut breaks = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i];
+        let text = token.text(json);
+        i += 1;
+
+        if token.kind == SyntaxKind::Whitespace {
+            breaks += line_breaks(text);
+            continue;
+        }
+        let own_line = breaks > 0 || formatter.out.is_empty();
+        formatter.blank_line |= breaks > 1;
+        breaks = 0;
+
+        match token.kind {
+            SyntaxKind::LineComment | SyntaxKind::BlockComment if !own_line => {
+                // A comment on the line of what came before stays there.
+                formatter.pad_to(options.trailing_comment_column);
+                if token.kind == SyntaxKind::LineComment {
+                    formatter.out.push_str(&line_comment(text, options));
+                    formatter.line_break = true;
+                } else {
+                    formatter.out.push_str(text);
+                }
+            }
+            SyntaxKind::LineComment => {
+                formatter.write(&line_comment(text, options));
+                formatter.line_break = true;
+            }
+            SyntaxKind::BlockComment if options.block_to_line_comments && !is_banner(text) => {
+                for line in block_comment_lines(text) {
+                    match line {
+                        "" => formatter.write("//"),
+                        line => formatter.write(&format!("// {}", line)),
+                    }
+                    formatter.line_break = true;
+                }
+            }
+            SyntaxKind::LeftBrace | SyntaxKind::LeftBracket => {
+                formatter.write(text);
+                let numbers = match options.numbers_per_line {
+                    Some(n) if token.kind == SyntaxKind::LeftBracket => {
+                        numbers(&tokens, json, i).filter(|numbers| numbers.values.len() > n)
+                    }
+                    _ => None,
+                };
+                if let Some(numbers) = numbers {
+                    let comma = match options.trailing_commas {
+                        None => numbers.trailing_comma,
+                        Some(policy) => policy != TrailingCommas::Never,
+                    };
+                    let per_line = options.numbers_per_line.unwrap_or_default();
+                    formatter.write_numbers(&numbers.values, per_line, comma);
+                    i = numbers.end;
+                    formatter.end_value(&tokens, i, options.trailing_commas);
+                    continue;
+                }
+                match next_significant(&tokens, i) {
+                    Some(j) if is_close(tokens[j].kind) => {
+                        formatter.out.push_str(tokens[j].text(json));
+                        i = j + 1;
+                        formatter.end_value(&tokens, i, options.trailing_commas);
+                    }
+                    _ => {
+                        formatter.indent += 1;
+                        formatter.line_break = true;
+                        formatter.blank_line = false;
+                    }
+                }
+            }
+            SyntaxKind::RightBrace | SyntaxKind::RightBracket => {
+                formatter.indent -= 1;
+                formatter.line_break = true;
+                formatter.blank_line = false;
+                formatter.write(text);
+                formatter.end_value(&tokens, i, options.trailing_commas);
+            }
+            SyntaxKind::Comma => {
+                let last = next_significant(&tokens, i).is_some_and(|j| is_close(tokens[j].kind));
+                if !last || options.trailing_commas != Some(TrailingCommas::Never) {
+                    formatter.out.push(',');
+                }
+                formatter.line_break = true;
+            }
+            SyntaxKind::Colon => formatter.out.push_str(": "),
+            SyntaxKind::BlockComment => {
+                formatter.write(text);
+                // A block comment on its own line keeps its line to itself.
+                formatter.line_break = true;
+            }
+            _ => {
+                formatter.write(text);
+                formatter.end_value(&tokens, i, options.trailing_commas);
+            }
+        }
+    }
+    formatter.out.push_str(formatter.line_ending.as_str());
+    Ok(formatter.out)
+}
+
+struct Formatter {
+    out: String,
+    indent_unit: Indent,
+    line_ending: LineEnding,
+    indent: usize,
+    // Whether the next token goes on a new line, and whether a blank line
+    // from the original document goes before it.
+    line_break: bool,
+    blank_line: bool,
+}
+
+impl Formatter {
+    fn write(&mut self, text: &str) {
+        if self.line_break {
+            self.out.push_str(self.line_ending.as_str());
+            if self.blank_line {
+                self.out.push_str(self.line_ending.as_str());
+            }
+            match self.indent_unit {
+                Indent::Spaces(width) => self
+                    .out
+                    .extend(core::iter::repeat_n(' ', width * self.indent)),
+                Indent::Tab => self.out.extend(core::iter::repeat_n('\t', self.indent)),
+            }
+        }
+        self.line_break = false;
+        self.blank_line = false;
+        self.out.push_str(text);
+    }
+
+    // The numbers of an array, after its opening bracket, `per_line` to a
+    // line, each right-aligned in its column.
+    fn write_numbers(&mut self, numbers: &[&str], per_line: usize, trailing_comma: bool) {
+        let per_line = per_line.max(1);
+        let mut widths = vec![0; per_line];
+        for (i, number) in numbers.iter().enumerate() {
+            let width = &mut widths[i % per_line];
+            *width = (*width).max(number.chars().count());
+        }
+
+        self.indent += 1;
+        for (r, row) in numbers.chunks(per_line).enumerate() {
+            let mut line = String::new();
+            for (c, number) in row.iter().enumerate() {
+                if c > 0 {
+                    line.push(' ');
+                }
+                let padding = widths[c] - number.chars().count();
+                line.extend(core::iter::repeat_n(' ', padding));
+                line.push_str(number);
+                if r * per_line + c + 1 < numbers.len() || trailing_comma {
+                    line.push(',');
+                }
+            }
+            self.line_break = true;
+            self.write(&line);
+        }
+        self.indent -= 1;
+        self.line_break = true;
+        self.write("]");
+    }
+
+    // After a value, whose entry is the last if `tokens[next..]` closes
+    // the array or object, adds the comma that `policy` may call for.
+    fn end_value(&mut self, tokens: &[Token], next: usize, policy: Option<TrailingCommas>) {
+        let last = next_significant(tokens, next).is_some_and(|j| is_close(tokens[j].kind));
+        let add = matches!(
+            policy,
+            Some(TrailingCommas::Always) | Some(TrailingCommas::Multiline)
+        );
+        if last && add {
+            self.out.push(',');
+        }
+    }
+
+    // Pads the current line with spaces to end before `column`, or with a
+    // space if there's no column or the line is already that long.
+    fn pad_to(&mut self, column: Option<usize>) {
+        let line = match self.out.rfind(['\n', '\r']) {
+            Some(i) => &self.out[i + 1..],
+            None => &self.out[..],
+        };
+        let width = line.chars().count();
+        let padding = column.map_or(1, |c| c.saturating_sub(width + 1).max(1));
+        self.out.extend(core::iter::repeat_n(' ', padding));
+    }
+}
+
+// Comments such as `//////` or `/* ==== */`, drawn rather than written.
+fn is_banner(comment: &str) -> bool {
+    let body = comment[2..].trim_start();
+    match body.chars().next() {
+        Some(c) if c.is_ascii_punctuation() => {
+            body.chars().take(3).filter(|&d| d == c).count() == 3
+        }
+        _ => false,
+    }
+}
+
+fn line_comment(comment: &str, options: &FormatOptions) -> String {
+    let body = comment.trim_start_matches('/');
+    if !options.space_after_line_comment
+        || is_banner(comment)
+        || body.is_empty()
+        || body.starts_with(char::is_whitespace)
+    {
+        return comment.into();
+    }
+    let slashes = &comment[..comment.len() - body.len()];
+    format!("{} {}", slashes, body)
+}
+
+// The lines of a block comment without its delimiters, trimmed, and without
+// a `*` starting a line after the first. Blank first and last lines are
+// dropped.
+fn block_comment_lines(comment: &str) -> Vec<&str> {
+    let body = &comment[2..comment.len() - 2];
+    let mut lines: Vec<&str> = body
+        .split('\n')
+        .flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'))
+        .enumerate()
+        .map(|(i, line)| {
+            let line = line.trim();
+            match line.strip_prefix('*') {
+                Some(rest) if i > 0 => rest.trim_start(),
+                _ => line,
+            }
+        })
+        .collect();
+    if lines.len() > 1 && lines[lines.len() - 1].is_empty() {
+        lines.pop();
+    }
+    if lines.len() > 1 && lines[0].is_empty() {
+        lines.remove(0);
+    }
+    lines
+}
+
+// "\n", "\r\n" and "\r" each end a line.
+fn line_breaks(text: &str) -> usize {
+    text.matches('\n').count() + text.matches('\r').count() - text.matches("\r\n").count()
+}
+
+// An array of numbers alone, without comments.
+struct Numbers<'a> {
+    values: Vec<&'a str>,
+    trailing_comma: bool,
+    // The index of the token after the closing bracket.
+    end: usize,
+}
+
+// The array of numbers whose elements start at `tokens[from]`, if it is
+// one.
+fn numbers<'a>(tokens: &[Token], json: &'a str, from: usize) -> Option<Numbers<'a>> {
+    let mut values = Vec::new();
+    let mut trailing_comma = false;
+    let significant = tokens[from..]
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.kind != SyntaxKind::Whitespace);
+
+    for (i, token) in significant {
+        match token.kind {
+            SyntaxKind::Number if values.is_empty() || trailing_comma => {
+                values.push(token.text(json));
+                trailing_comma = false;
+            }
+            SyntaxKind::Comma if !values.is_empty() && !trailing_comma => trailing_comma = true,
+            SyntaxKind::RightBracket => {
+                return Some(Numbers {
+                    values,
+                    trailing_comma,
+                    end: from + i + 1,
+                })
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn next_significant(tokens: &[Token], from: usize) -> Option<usize> {
+    (from..tokens.len()).find(|&i| !tokens[i].is_trivia())
+}
+
+fn is_close(kind: SyntaxKind) -> bool {
+    kind == SyntaxKind::RightBrace || kind == SyntaxKind::RightBracket
+}
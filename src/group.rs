@@ -0,0 +1,77 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::map::Map;
+use crate::value::Value;
+
+impl Value {
+    /// Groups the elements of this array by the values at `path` in them,
+    /// into an object of arrays keyed by those values: strings as they are,
+    /// and other values as `stringify` writes them, so `null` for elements
+    /// without one. Empty unless this is an array.
+    pub fn group_by<S>(&self, path: &[S]) -> Value
+    where
+        S: AsRef<str>,
+    {
+        let mut groups: Map<String, Vec<Value>> = Map::new();
+        for element in self.to_vec().map_or(&[][..], |v| v) {
+            let key = match element.at(path) {
+                Value::String(s) => s.clone(),
+                value => crate::stringify(value),
+            };
+            groups.entry(key).or_default().push(element.clone());
+        }
+        Value::Object(
+            groups
+                .into_iter()
+                .map(|(key, group)| (key, Value::Array(group)))
+                .collect(),
+        )
+    }
+
+    /// This object with only the members at `paths`, paths of keys through
+    /// nested objects, which keep only those members in turn. Members that
+    /// don't exist are left out. For an array, each of its elements so
+    /// trimmed; for anything else, an empty object.
+    pub fn project<S>(&self, paths: &[&[S]]) -> Value
+    where
+        S: AsRef<str>,
+    {
+        if let Value::Array(v) = self {
+            return Value::Array(v.iter().map(|element| element.project(paths)).collect());
+        }
+        let mut projection = Value::Object(Map::new());
+        for path in paths {
+            if let Some(value) = path
+                .iter()
+                .try_fold(self, |value, key| value.get(key.as_ref()))
+            {
+                insert(&mut projection, path, value);
+            }
+        }
+        projection
+    }
+}
+
+// Sets `value` at `path` in `projection`, adding objects along it.
+fn insert<S>(projection: &mut Value, path: &[S], value: &Value)
+where
+    S: AsRef<str>,
+{
+    let (last, parents) = match path.split_last() {
+        Some(split) => split,
+        None => return *projection = value.clone(),
+    };
+    let mut object = projection;
+    for key in parents {
+        object = match object {
+            Value::Object(m) => m
+                .entry(key.as_ref().into())
+                .or_insert_with(|| Value::Object(Map::new())),
+            _ => return,
+        };
+    }
+    if let Value::Object(m) = object {
+        m.insert(last.as_ref().into(), value.clone());
+    }
+}
@@ -0,0 +1,131 @@
+use alloc::vec::Vec;
+use core::hash::Hasher;
+
+use crate::fnv::Fnv;
+use crate::value::Value;
+
+fn write_value(h: &mut Fnv, value: &Value) {
+    match value {
+        Value::Null => h.write_u8(0),
+        Value::Boolean(b) => {
+            h.write_u8(1);
+            h.write_u8(*b as u8);
+        }
+        Value::Integer(i) => {
+            h.write_u8(2);
+            h.write(&i.to_le_bytes());
+        }
+        Value::Float(f) => {
+            h.write_u8(3);
+            // 0.0 and -0.0 compare equal, so they must hash equally
+            let f = if *f == 0.0 { 0.0 } else { *f };
+            h.write(&f.to_bits().to_le_bytes());
+        }
+        Value::String(s) => {
+            h.write_u8(4);
+            write_str(h, s);
+        }
+        Value::Array(v) => {
+            h.write_u8(5);
+            h.write(&(v.len() as u64).to_le_bytes());
+            for value in v {
+                write_value(h, value);
+            }
+        }
+        Value::Object(m) => {
+            // Entries are hashed separately and summed, which doesn't depend
+            // on iteration order.
+            let sum = m.iter().fold(0u64, |sum, (key, value)| {
+                let mut entry = Fnv::new();
+                write_str(&mut entry, key);
+                write_value(&mut entry, value);
+                sum.wrapping_add(entry.finish())
+            });
+            h.write_u8(6);
+            h.write(&(m.len() as u64).to_le_bytes());
+            h.write(&sum.to_le_bytes());
+        }
+        Value::Custom(c) => write_value(h, &c.to_value()),
+    }
+}
+
+fn write_str(h: &mut Fnv, s: &str) {
+    h.write(&(s.len() as u64).to_le_bytes());
+    h.write(s.as_bytes());
+}
+
+impl Value {
+    /// A hash of the value's content that ignores the order of object keys
+    /// and is stable across runs, so it can be persisted and compared later.
+    pub fn structural_hash(&self) -> u64 {
+        let mut h = Fnv::new();
+        write_value(&mut h, self);
+        h.finish()
+    }
+}
+
+/// Caches results derived from documents by their structural hash, so that
+/// reformatting or reordering a config doesn't trigger the work again.
+pub struct ContentCache<T> {
+    entries: Vec<(u64, Value, T)>,
+    capacity: usize,
+}
+
+impl<T> ContentCache<T> {
+    /// Keeps at most `capacity` entries, evicting the least recently used.
+    pub fn new(capacity: usize) -> Self {
+        ContentCache {
+            entries: Vec::new(),
+            capacity,
+        }
+    }
+
+    pub fn get(&mut self, value: &Value) -> Option<&T> {
+        let i = self.position(value)?;
+        Some(self.touch(i))
+    }
+
+    pub fn get_or_insert_with<F>(&mut self, value: &Value, f: F) -> &T
+    where
+        F: FnOnce(&Value) -> T,
+    {
+        if let Some(i) = self.position(value) {
+            return self.touch(i);
+        }
+        if self.entries.len() >= self.capacity.max(1) {
+            self.entries.remove(0);
+        }
+
+        let t = f(value);
+        self.entries
+            .push((value.structural_hash(), value.clone(), t));
+        self.touch(self.entries.len() - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Moves entry `i` to the back, where the most recently used entry lives.
+    fn touch(&mut self, i: usize) -> &T {
+        let entry = self.entries.remove(i);
+        self.entries.push(entry);
+        &self.entries[self.entries.len() - 1].2
+    }
+
+    // Hashes narrow the search; equality rules out collisions.
+    fn position(&self, value: &Value) -> Option<usize> {
+        let hash = value.structural_hash();
+        self.entries
+            .iter()
+            .position(|(h, v, _)| *h == hash && v == value)
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+
+use crate::lexer::{tokenize, SyntaxKind};
+use crate::span::Span;
+
+/// How `semantic_tokens` classifies a token for highlighting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SemanticKind {
+    /// An object key, quoted or not.
+    Key,
+    String,
+    Number,
+    /// `true`, `false` or `null`.
+    Keyword,
+    Comment,
+    Punctuation,
+    /// Anything that can't be part of a valid document, such as an
+    /// unterminated string or an unquoted word in place of a value.
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub kind: SemanticKind,
+    pub span: Span,
+}
+
+/// Classifies the tokens of a JSON5 document for syntax highlighting, in
+/// order and leaving out whitespace.
+///
+/// This works on the tokens alone, without parsing, so a document being
+/// edited is highlighted as far as possible: a string or identifier is a
+/// key when a colon follows it.
+pub fn semantic_tokens(json: &str) -> Vec<SemanticToken> {
+    let tokens = tokenize(json);
+    let mut result = Vec::with_capacity(tokens.len());
+
+    for (i, token) in tokens.iter().enumerate() {
+        let is_key = || {
+            tokens[i + 1..]
+                .iter()
+                .find(|t| !t.is_trivia())
+                .is_some_and(|t| t.kind == SyntaxKind::Colon)
+        };
+
+        let kind = match token.kind {
+            SyntaxKind::Whitespace => continue,
+            SyntaxKind::LineComment | SyntaxKind::BlockComment => SemanticKind::Comment,
+            SyntaxKind::LeftBrace
+            | SyntaxKind::RightBrace
+            | SyntaxKind::LeftBracket
+            | SyntaxKind::RightBracket
+            | SyntaxKind::Colon
+            | SyntaxKind::Comma => SemanticKind::Punctuation,
+            SyntaxKind::String | SyntaxKind::Identifier if is_key() => SemanticKind::Key,
+            SyntaxKind::String => SemanticKind::String,
+            SyntaxKind::Number => SemanticKind::Number,
+            SyntaxKind::Identifier => match token.text(json) {
+                "true" | "false" | "null" => SemanticKind::Keyword,
+                _ => SemanticKind::Error,
+            },
+            SyntaxKind::Error => SemanticKind::Error,
+        };
+        result.push(SemanticToken {
+            kind,
+            span: token.span,
+        });
+    }
+    result
+}
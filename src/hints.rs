@@ -0,0 +1,81 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::options::StringifyOptions;
+use crate::pointer::Pointer;
+use crate::ser::Serializer;
+use crate::value::Value;
+
+/// How a scalar was written, where that isn't how `stringify` would write
+/// it, from `parse_with_hints`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScalarHint {
+    /// A string in single quotes.
+    SingleQuoted,
+    /// An integer in hexadecimal.
+    Hex { uppercase: bool },
+    /// A number with an exponent, as in `1.5e3`.
+    Exponent,
+    /// A float in fixed-point notation with this many decimals, as in
+    /// `2.50`. Never found by `parse_with_hints`, but given to
+    /// `stringify_with_hints` for the floats to write so. It takes the
+    /// place of `StringifyOptions::significant_digits` for those floats.
+    Fixed { decimals: u8 },
+}
+
+/// Like `parse`, but also returns how scalars written in other than the
+/// default style were written, by JSON Pointer, for `stringify_with_hints`
+/// to write them back the same way after the value has been modified.
+pub fn parse_with_hints(json: &str) -> Result<(Value, Vec<(Pointer, ScalarHint)>), Error> {
+    let (value, spans) = crate::parse_with_spans(json)?;
+
+    let hints = spans
+        .into_iter()
+        .filter_map(|(pointer, span)| {
+            let text = &json[span.start.offset..span.end.offset];
+            let number = text.trim_start_matches(['+', '-']);
+            let hint = if text.starts_with('\'') {
+                ScalarHint::SingleQuoted
+            } else if number.starts_with("0x") || number.starts_with("0X") {
+                ScalarHint::Hex {
+                    uppercase: number[2..].contains(|c: char| c.is_ascii_uppercase()),
+                }
+            } else if number.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+                && number.contains(['e', 'E'])
+            {
+                ScalarHint::Exponent
+            } else {
+                return None;
+            };
+            Some((pointer, hint))
+        })
+        .collect();
+    Ok((value, hints))
+}
+
+/// Like `stringify`, but writing the scalars at the pointers of `hints` as
+/// they say, as far as the values there allow: a hint for a string is
+/// ignored if the value is now a number.
+pub fn stringify_with_hints(value: &Value, hints: &[(Pointer, ScalarHint)]) -> String {
+    stringify_with_hints_and_options(value, hints, &StringifyOptions::default())
+}
+
+/// Like `stringify_with_hints`, with `options` for everything else.
+///
+/// Where a hint and an option both apply to a scalar, the hint wins, except
+/// that `significant_digits` still rounds floats written with an exponent,
+/// and strings long enough to be split by `wrap_strings_at` are split in
+/// double quotes whatever their hint. `Fixed` floats are written with their
+/// decimals alone, unrounded by `significant_digits`.
+pub fn stringify_with_hints_and_options(
+    value: &Value,
+    hints: &[(Pointer, ScalarHint)],
+    options: &StringifyOptions,
+) -> String {
+    let mut serializer = Serializer::new(*options);
+    serializer.hints = hints.iter().cloned().collect();
+
+    serializer.write_value(value);
+    serializer.out
+}
@@ -0,0 +1,217 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::error::Error;
+use crate::map::Map;
+use crate::pointer::Pointer;
+use crate::span::Span;
+use crate::value::Value;
+
+/// The layer a setting came from, and where in it, for layers parsed from
+/// text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Provenance {
+    pub source: String,
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.source)?;
+        match &self.span {
+            Some(span) => write!(f, ", line {}", span.start.line),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A configuration assembled from layers such as defaults, files and the
+/// environment, that remembers which layer each setting came from.
+///
+/// Each layer is merged over the ones added before it: objects are merged
+/// key by key, and any other value replaces what was there, arrays included.
+#[derive(Clone, Debug)]
+pub struct Layered {
+    value: Value,
+    provenance: Map<Pointer, Provenance>,
+}
+
+impl Default for Layered {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Layered {
+    pub fn new() -> Self {
+        Layered {
+            value: Value::Object(Map::new()),
+            provenance: Map::new(),
+        }
+    }
+
+    /// The merged configuration.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Which layer set the value at `pointer`. An object is attributed to the
+    /// layer that created it, even if later layers added to it.
+    pub fn provenance(&self, pointer: &Pointer) -> Option<&Provenance> {
+        self.provenance.get(pointer)
+    }
+
+    /// Parses `json` and merges it as the layer named `source`, usually the
+    /// path of the file it was read from.
+    pub fn add_str(&mut self, source: &str, json: &str) -> Result<(), Error> {
+        let (value, spans) =
+            crate::parse_with_spans(json).map_err(|e| e.with_name(source.into()))?;
+        let spans: Map<Pointer, Span> = spans.into_iter().collect();
+
+        self.merge(source, value, &spans);
+        Ok(())
+    }
+
+    /// Merges `value` as the layer named `source`, e.g. built-in defaults or
+    /// settings gathered from the environment.
+    pub fn add_value(&mut self, source: &str, value: Value) {
+        self.merge(source, value, &Map::new());
+    }
+
+    fn merge(&mut self, source: &str, value: Value, spans: &Map<Pointer, Span>) {
+        let mut layer = Layer {
+            source,
+            spans,
+            provenance: &mut self.provenance,
+        };
+        merge(&mut self.value, value, &mut Vec::new(), &mut layer);
+    }
+
+    /// Formats the merged configuration as JSON5, with the provenance of
+    /// every setting in a comment beside it.
+    pub fn to_annotated_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, &self.value, &mut Vec::new(), 0);
+        out.push('\n');
+        out
+    }
+
+    fn write(&self, out: &mut String, value: &Value, path: &mut Vec<String>, indent: usize) {
+        let entries: Vec<(String, &Value)> = match value {
+            Value::Array(v) if !v.is_empty() => v
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), v))
+                .collect(),
+            Value::Object(m) if !m.is_empty() => m.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            _ => {
+                out.push_str(&crate::stringify(value));
+                return;
+            }
+        };
+        let is_array = matches!(value, Value::Array(_));
+
+        out.push(if is_array { '[' } else { '{' });
+        out.push('\n');
+        for (token, item) in entries {
+            push_indent(out, indent + 2);
+            if !is_array {
+                out.push_str(&crate::stringify(&Value::String(token.clone())));
+                out.push_str(": ");
+            }
+            path.push(token);
+            self.write(out, item, path, indent + 2);
+            out.push(',');
+
+            let pointer: Pointer = path.iter().collect();
+            match self.provenance(&pointer) {
+                Some(provenance) if !is_nested(item) => {
+                    out.push_str(" // ");
+                    out.push_str(&provenance.to_string());
+                }
+                _ => (),
+            }
+            path.pop();
+            out.push('\n');
+        }
+        push_indent(out, indent);
+        out.push(if is_array { ']' } else { '}' });
+    }
+}
+
+struct Layer<'a> {
+    source: &'a str,
+    spans: &'a Map<Pointer, Span>,
+    provenance: &'a mut Map<Pointer, Provenance>,
+}
+
+impl<'a> Layer<'a> {
+    // Attributes `value` and everything in it to this layer.
+    fn record(&mut self, path: &mut Vec<String>, value: &Value) {
+        let pointer: Pointer = path.iter().collect();
+        let provenance = Provenance {
+            source: self.source.into(),
+            span: self.spans.get(&pointer).copied(),
+        };
+        self.provenance.insert(pointer, provenance);
+
+        match value {
+            Value::Array(v) => {
+                for (i, item) in v.iter().enumerate() {
+                    path.push(i.to_string());
+                    self.record(path, item);
+                    path.pop();
+                }
+            }
+            Value::Object(m) => {
+                for (key, item) in m {
+                    path.push(key.clone());
+                    self.record(path, item);
+                    path.pop();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Drops what was recorded for a value about to be replaced.
+    fn forget(&mut self, path: &[String]) {
+        self.provenance.retain(|p, _| !p.tokens().starts_with(path));
+    }
+}
+
+fn merge(target: &mut Value, value: Value, path: &mut Vec<String>, layer: &mut Layer) {
+    match (target, value) {
+        (Value::Object(existing), Value::Object(m)) => {
+            for (key, item) in m {
+                path.push(key.clone());
+                match existing.get_mut(&key) {
+                    Some(slot) => merge(slot, item, path, layer),
+                    None => {
+                        layer.record(path, &item);
+                        existing.insert(key, item);
+                    }
+                }
+                path.pop();
+            }
+        }
+        (target, value) => {
+            layer.forget(path);
+            layer.record(path, &value);
+            *target = value;
+        }
+    }
+}
+
+fn is_nested(value: &Value) -> bool {
+    match value {
+        Value::Array(v) => !v.is_empty(),
+        Value::Object(m) => !m.is_empty(),
+        _ => false,
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.extend(core::iter::repeat_n(' ', indent));
+}
@@ -0,0 +1,223 @@
+use alloc::vec::Vec;
+
+use crate::options::LineEnding;
+use crate::span::{Position, Span};
+
+/// What a `Token` is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyntaxKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
+    Comma,
+    String,
+    /// A number, `Infinity` and `NaN` included.
+    Number,
+    /// An unquoted key, or one of `true`, `false` and `null`.
+    Identifier,
+    /// A character that can't start a token, or an unterminated string or
+    /// block comment.
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: SyntaxKind,
+    pub span: Span,
+}
+
+impl Token {
+    /// The text of the token in `json`, the document it was read from.
+    pub fn text<'a>(&self, json: &'a str) -> &'a str {
+        &json[self.span.start.offset..self.span.end.offset]
+    }
+
+    /// Whether the token is whitespace or a comment.
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            SyntaxKind::Whitespace | SyntaxKind::LineComment | SyntaxKind::BlockComment
+        )
+    }
+}
+
+/// Splits a JSON5 document into tokens, keeping whitespace and comments, so
+/// that the tokens' text put back together is the document itself.
+///
+/// This never fails: anything that isn't a token becomes an `Error` token
+/// and reading resumes after it. Whether the tokens make a valid document is
+/// for the parser to say.
+pub fn tokenize(json: &str) -> Vec<Token> {
+    let mut lexer = Lexer {
+        json,
+        position: Position::default(),
+    };
+    let mut tokens = Vec::new();
+
+    while let Some(c) = lexer.peek(0) {
+        let start = lexer.position;
+        let kind = lexer.token(c);
+        tokens.push(Token {
+            kind,
+            span: Span {
+                start,
+                end: lexer.position,
+            },
+        });
+    }
+    tokens
+}
+
+struct Lexer<'a> {
+    json: &'a str,
+    position: Position,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self, n: usize) -> Option<char> {
+        self.json[self.position.offset..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        let next = self.peek(1);
+        self.position.advance(c, next);
+        Some(c)
+    }
+
+    fn bump_while(&mut self, f: impl Fn(char) -> bool) {
+        while self.peek(0).is_some_and(&f) {
+            self.bump();
+        }
+    }
+
+    fn token(&mut self, c: char) -> SyntaxKind {
+        self.bump();
+
+        match c {
+            '{' => SyntaxKind::LeftBrace,
+            '}' => SyntaxKind::RightBrace,
+            '[' => SyntaxKind::LeftBracket,
+            ']' => SyntaxKind::RightBracket,
+            ':' => SyntaxKind::Colon,
+            ',' => SyntaxKind::Comma,
+            '"' | '\'' => self.string(c),
+            '/' => match self.peek(0) {
+                Some('/') => {
+                    self.bump_while(|c| !is_line_terminator(c));
+                    SyntaxKind::LineComment
+                }
+                Some('*') => {
+                    self.bump();
+                    self.block_comment()
+                }
+                _ => SyntaxKind::Error,
+            },
+            '0'..='9' | '.' => self.number(),
+            '+' | '-' => match self.peek(0) {
+                Some(c) if c.is_ascii_digit() || c == '.' || c == 'I' || c == 'N' => self.number(),
+                _ => SyntaxKind::Error,
+            },
+            _ if is_whitespace(c) => {
+                self.bump_while(is_whitespace);
+                SyntaxKind::Whitespace
+            }
+            _ if is_identifier_start(c) || c == '\\' => {
+                let start = self.position.offset - c.len_utf8();
+                self.bump_while(|c| is_identifier_part(c) || c == '\\');
+                match &self.json[start..self.position.offset] {
+                    "Infinity" | "NaN" => SyntaxKind::Number,
+                    _ => SyntaxKind::Identifier,
+                }
+            }
+            _ => SyntaxKind::Error,
+        }
+    }
+
+    fn string(&mut self, quote: char) -> SyntaxKind {
+        loop {
+            match self.peek(0) {
+                Some(c) if c == quote => {
+                    self.bump();
+                    return SyntaxKind::String;
+                }
+                Some('\\') => {
+                    self.bump();
+                    // A backslash followed by CR LF continues the line.
+                    if self.bump() == Some('\r') && self.peek(0) == Some('\n') {
+                        self.bump();
+                    }
+                }
+                Some('\n') | Some('\r') | None => return SyntaxKind::Error,
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn block_comment(&mut self) -> SyntaxKind {
+        loop {
+            match self.bump() {
+                Some('*') if self.peek(0) == Some('/') => {
+                    self.bump();
+                    return SyntaxKind::BlockComment;
+                }
+                Some(_) => (),
+                None => return SyntaxKind::Error,
+            }
+        }
+    }
+
+    // Numbers are read loosely, as a run of the characters they can be made
+    // of, and left to the parser to validate.
+    fn number(&mut self) -> SyntaxKind {
+        let mut previous = None;
+
+        while let Some(c) = self.peek(0) {
+            let exponent_sign = (c == '+' || c == '-') && matches!(previous, Some('e' | 'E'));
+            if !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign) {
+                break;
+            }
+            previous = Some(c);
+            self.bump();
+        }
+        SyntaxKind::Number
+    }
+}
+
+// What ends the first line of the document with these tokens, outside
+// comments and strings.
+pub(crate) fn line_ending(json: &str, tokens: &[Token]) -> LineEnding {
+    let whitespace = tokens.iter().filter(|t| t.kind == SyntaxKind::Whitespace);
+    for text in whitespace.map(|t| t.text(json)) {
+        match text.find(['\n', '\r']) {
+            Some(i) if text[i..].starts_with("\r\n") => return LineEnding::CrLf,
+            Some(i) if text[i..].starts_with('\r') => return LineEnding::Cr,
+            Some(_) => return LineEnding::Lf,
+            None => (),
+        }
+    }
+    LineEnding::default()
+}
+
+fn is_whitespace(c: char) -> bool {
+    c.is_whitespace() || c == '\u{FEFF}'
+}
+
+fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '$' || c == '_'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    is_identifier_start(c) || c.is_alphanumeric() || c == '\u{200C}' || c == '\u{200D}'
+}
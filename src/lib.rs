@@ -1,26 +1,409 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "cst")]
+mod actions;
+#[cfg(feature = "values")]
+mod aggregate;
+#[cfg(feature = "values")]
+mod approx;
+#[cfg(feature = "values")]
+mod arc;
+#[cfg(feature = "values")]
+mod batch;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod buffer;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+mod bytes;
+#[cfg(feature = "std")]
+pub mod cached;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "codegen")]
+mod codegen;
+#[cfg(feature = "cst")]
+mod comment;
+#[cfg(feature = "schema")]
+mod complete;
+#[cfg(feature = "config")]
+mod conditions;
+#[cfg(feature = "std")]
+mod conformance;
+#[cfg(feature = "config")]
+mod convention;
+#[cfg(feature = "csv")]
+mod csv;
+mod custom;
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "values")]
+mod debug;
+#[cfg(feature = "cst")]
+mod document;
 mod error;
+#[cfg(feature = "config")]
+mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(feature = "std", feature = "hash", feature = "values"))]
+mod fnv;
+#[cfg(feature = "format")]
+mod format;
+#[cfg(feature = "values")]
+mod group;
+#[cfg(feature = "hash")]
+mod hash;
+#[cfg(feature = "cst")]
+mod highlight;
+#[cfg(feature = "hints")]
+mod hints;
+#[cfg(feature = "config")]
+mod layers;
+#[cfg(feature = "cst")]
+mod lexer;
+#[cfg(feature = "lines")]
+mod lines;
+#[cfg(feature = "config")]
+mod literals;
+#[cfg(feature = "std")]
+mod load;
+mod macros;
+mod map;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(feature = "config")]
+mod multi;
+#[cfg(feature = "serde")]
+mod optional;
+mod options;
 mod parser;
+mod pointer;
+#[cfg(feature = "config")]
+mod redacted;
+#[cfg(feature = "values")]
+mod sample;
+#[cfg(feature = "values")]
+mod search;
+mod ser;
+mod span;
+#[cfg(feature = "macros")]
+mod static_value;
+#[cfg(feature = "config")]
+mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "serde")]
+mod transcode;
+#[cfg(feature = "cst")]
+mod tree;
+#[cfg(feature = "values")]
+mod try_index;
+#[cfg(any(feature = "duration", feature = "byte-size"))]
+mod units;
 mod value;
+mod warning;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "notify")]
+mod watch;
+#[cfg(feature = "std")]
+mod writer;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+use alloc::string::String;
+use alloc::vec::Vec;
 
-pub use crate::error::Error;
+#[cfg(feature = "cst")]
+pub use crate::actions::{remove_duplicate_keys, sort_keys, to_double_quotes};
+#[cfg(feature = "values")]
+pub use crate::aggregate::NonNumeric;
+#[cfg(feature = "values")]
+pub use crate::approx::EqOptions;
+#[cfg(feature = "values")]
+pub use crate::arc::ArcValue;
+pub use crate::buffer::ParseBuffer;
+#[cfg(feature = "cst")]
+pub use crate::comment::{parse_with_comments, Comment};
+#[cfg(feature = "schema")]
+pub use crate::complete::{complete, Completion, CompletionKind};
+#[cfg(feature = "std")]
+pub use crate::conformance::{run_conformance, ConformanceFailure, ConformanceReport, Expectation};
+#[cfg(feature = "config")]
+pub use crate::convention::{Convention, KeyCollision};
+#[cfg(feature = "csv")]
+pub use crate::csv::{from_csv, to_csv, CsvOptions};
+pub use crate::custom::Scalar;
+#[cfg(feature = "serde")]
+pub use crate::de::{from_str, from_str_with_unused, ArrayIter, Deserializer, UnusedKey};
+#[cfg(feature = "values")]
+pub use crate::debug::DebugValue;
+#[cfg(feature = "cst")]
+pub use crate::document::Document;
+pub use crate::error::{Error, ErrorKind, TokenKind};
+#[cfg(feature = "format")]
+pub use crate::format::{detect_style, reformat, reformat_with_options, FormatOptions, Indent};
+#[cfg(feature = "hash")]
+pub use crate::hash::ContentCache;
+#[cfg(feature = "cst")]
+pub use crate::highlight::{semantic_tokens, SemanticKind, SemanticToken};
+#[cfg(feature = "hints")]
+pub use crate::hints::{
+    parse_with_hints, stringify_with_hints, stringify_with_hints_and_options, ScalarHint,
+};
+#[cfg(feature = "config")]
+pub use crate::layers::{Layered, Provenance};
+#[cfg(feature = "cst")]
+pub use crate::lexer::{tokenize, SyntaxKind, Token};
+#[cfg(feature = "lines")]
+pub use crate::lines::{lines, stringify_lines, Lines};
+#[cfg(feature = "config")]
+pub use crate::literals::Literals;
+#[cfg(feature = "std")]
+pub use crate::load::{load_dir, load_dir_all, LoadError};
+pub use crate::map::Map;
+#[cfg(feature = "config")]
+pub use crate::multi::{parse_multi, MultiValue};
+#[cfg(feature = "serde")]
+pub use crate::optional::OptionalField;
+pub use crate::options::{
+    DuplicateKeys, KeyMatching, Limits, LineEnding, NonFinite, ParseOptions, StringifyOptions,
+    TrailingCommas,
+};
 use crate::parser::Parser;
+pub use crate::pointer::Pointer;
+#[cfg(feature = "config")]
+pub use crate::redacted::{Redacted, RedactionRules};
+#[cfg(feature = "values")]
+pub use crate::search::KeyIndex;
+use crate::ser::Serializer;
+pub use crate::span::{Position, Span};
+#[cfg(feature = "macros")]
+pub use crate::static_value::StaticValue;
+#[cfg(feature = "config")]
+pub use crate::template::render;
+#[cfg(feature = "serde")]
+pub use crate::transcode::transcode;
+#[cfg(feature = "values")]
+pub use crate::try_index::{IndexError, ValueIndex};
 pub use crate::value::Value;
+pub use crate::warning::{Duplicate, Warning, WarningKind};
+#[cfg(feature = "wasm")]
+pub use crate::wasm::{parse_js, stringify_js};
+#[cfg(feature = "notify")]
+pub use crate::watch::{watch, WatchError, Watcher};
+#[cfg(feature = "std")]
+pub use crate::writer::Json5Writer;
+#[cfg(feature = "macros")]
+pub use json5_macros::json5_static;
 
+/// Parses a JSON5 document.
+///
+/// This function never panics: any malformed input, including documents
+/// nested more than 128 arrays or objects deep, is reported as an `Error`.
 pub fn parse(json: &str) -> Result<Value, Error> {
-    let mut parser = Parser {
-        chars: json.chars().peekable(),
-        ch: None,
-    };
+    parse_with_options(json, &ParseOptions::default())
+}
+
+pub fn parse_with_options(json: &str, options: &ParseOptions) -> Result<Value, Error> {
+    parse_with_warnings(json, options).map(|(value, _)| value)
+}
+
+/// Like `parse_with_options`, but also returns findings that didn't prevent
+/// parsing, such as duplicate keys.
+pub fn parse_with_warnings(
+    json: &str,
+    options: &ParseOptions,
+) -> Result<(Value, Vec<Warning>), Error> {
+    let mut parser = Parser::with_options(json, *options);
+
+    let value = parse_document(&mut parser)?;
+    Ok((value, parser.warnings))
+}
+
+/// Like `parse_with_options`, but also returns how each repeated key was
+/// resolved, as `options.duplicate_keys` says, for tools that annotate or
+/// rewrite the document. A key repeated three times is reported twice, each
+/// time against the member kept until then.
+pub fn parse_with_duplicates(
+    json: &str,
+    options: &ParseOptions,
+) -> Result<(Value, Vec<Duplicate>), Error> {
+    let mut parser = Parser::with_options(json, *options);
+    parser.duplicates = Some(Vec::new());
+
+    let value = parse_document(&mut parser)?;
+    Ok((value, parser.duplicates.unwrap_or_default()))
+}
+
+/// Like `parse_with_options`, but giving up on documents that take more
+/// work to parse than `limits` allow, or once they say to.
+pub fn parse_with_limits(
+    json: &str,
+    options: &ParseOptions,
+    limits: &Limits,
+) -> Result<Value, Error> {
+    if limits.max_bytes.is_some_and(|max| json.len() > max) {
+        return Err(Error::new(ErrorKind::BudgetExceeded));
+    }
+    let mut parser = Parser::with_options(json, *options);
+    parser.limits = *limits;
+
+    parse_document(&mut parser)
+}
+
+/// Like `parse_with_options`, but calling `progress` with the byte offset
+/// reached every `every` bytes or so, e.g. to drive a progress bar, and with
+/// the length of the document once it has been parsed.
+///
+/// Progress is reported between values, so not within a long string.
+pub fn parse_with_progress<F>(
+    json: &str,
+    options: &ParseOptions,
+    every: usize,
+    mut progress: F,
+) -> Result<Value, Error>
+where
+    F: FnMut(usize),
+{
+    let mut parser = Parser::with_options(json, *options);
+    parser.progress = Some(parser::Progress {
+        every,
+        next: every,
+        callback: &mut progress,
+    });
+
+    let value = parse_document(&mut parser)?;
+    drop(parser);
+    progress(json.len());
+    Ok(value)
+}
+
+/// Like `parse`, but with values that may be expressions over `variables`,
+/// as in `port: 8000 + instance`, and strings that may interpolate them, as
+/// in `'db-${region}'`.
+///
+/// Expressions join values and variables with `+`, `-`, `*`, `/` and `%`,
+/// and group them with parentheses. `+` also joins strings with strings and
+/// numbers. In strings, `$${` stands for a literal `${`.
+#[cfg(feature = "config")]
+pub fn parse_with_variables(json: &str, variables: &Map<String, Value>) -> Result<Value, Error> {
+    let mut parser = Parser::with_options(json, ParseOptions::default());
+    parser.variables = Some(variables);
+
+    parse_document(&mut parser)
+}
+
+/// Like `parse_with_options`, but with numbers that may carry a unit from
+/// `units`, as in `10s` or `5MiB`, read as the number times the unit's
+/// factor: `{ s: 1.0, m: 60.0 }` makes `2m` 120. Results that are whole
+/// numbers within the range of `i32` are integers.
+///
+/// A unit is the run of letters and `%` right after a number. Units not in
+/// the table are errors.
+pub fn parse_with_units(
+    json: &str,
+    options: &ParseOptions,
+    units: &Map<String, f64>,
+) -> Result<Value, Error> {
+    let mut parser = Parser::with_options(json, *options);
+    parser.units = Some(units);
 
+    parse_document(&mut parser)
+}
+
+/// Like `parse_with_options`, but with values that may be `@name(argument)`
+/// literals, as in `@base64(aGVsbG8=)`, turned into values by the hooks of
+/// `literals`. Literals without a hook are errors.
+#[cfg(feature = "config")]
+pub fn parse_with_literals(
+    json: &str,
+    options: &ParseOptions,
+    literals: &Literals,
+) -> Result<Value, Error> {
+    let mut parser = Parser::with_options(json, *options);
+    parser.literals = Some(literals);
+
+    parse_document(&mut parser)
+}
+
+// Parses a whole document, one value surrounded by comments.
+fn parse_document(parser: &mut Parser) -> Result<Value, Error> {
+    parse_document_with(parser, Parser::parse_value)
+}
+
+// As `parse_document`, with `parse` reading the value.
+fn parse_document_with<'a, T>(
+    parser: &mut Parser<'a>,
+    parse: fn(&mut Parser<'a>) -> Result<T, Error>,
+) -> Result<T, Error> {
     parser.next();
     parser.skip_comments()?;
 
-    let value = parser.parse_value()?;
+    let value = parse(parser)?;
 
     parser.skip_comments()?;
 
     if parser.ch.is_some() {
-        return Err(Error::UnexpectedCharacter);
+        return Err(parser.unexpected(&[TokenKind::EndOfInput]));
     }
     Ok(value)
 }
+
+/// Like `parse`, but also returns the span of every value in the document,
+/// keyed by its JSON Pointer. Values come before the arrays and objects
+/// containing them, so the document itself is last.
+pub fn parse_with_spans(json: &str) -> Result<(Value, Vec<(Pointer, Span)>), Error> {
+    let mut parser = Parser::with_options(json, ParseOptions::default());
+    parser.spans = Some(Vec::new());
+
+    let value = parse_document(&mut parser)?;
+    Ok((value, parser.spans.unwrap_or_default()))
+}
+
+/// Like `parse`, but also returns the span of every object key in the
+/// document, quotes included, keyed by the JSON Pointer of its value. Keys
+/// come in the order they are written.
+pub fn parse_with_key_spans(json: &str) -> Result<(Value, Vec<(Pointer, Span)>), Error> {
+    let mut parser = Parser::with_options(json, ParseOptions::default());
+    parser.key_spans = Some(Vec::new());
+
+    let value = parse_document(&mut parser)?;
+    Ok((value, parser.key_spans.unwrap_or_default()))
+}
+
+/// Like `parse`, but errors carry `name`, typically the path of the file
+/// the document was read from.
+pub fn parse_named(json: &str, name: &str) -> Result<Value, Error> {
+    parse(json).map_err(|error| error.with_name(name.into()))
+}
+
+pub fn stringify(value: &Value) -> String {
+    stringify_with_options(value, &StringifyOptions::default())
+}
+
+pub fn stringify_with_options(value: &Value, options: &StringifyOptions) -> String {
+    let mut serializer = Serializer::new(*options);
+
+    serializer.write_value(value);
+    serializer.out
+}
+
+/// Like `stringify_with_options`, but failing on NaN and infinite floats
+/// with `NonFinite::Error`.
+pub fn try_stringify_with_options(
+    value: &Value,
+    options: &StringifyOptions,
+) -> Result<String, Error> {
+    let mut serializer = Serializer::new(*options);
+
+    serializer.write_value(value);
+    match serializer.error {
+        Some(error) => Err(error),
+        None => Ok(serializer.out),
+    }
+}
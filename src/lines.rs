@@ -0,0 +1,77 @@
+use alloc::string::String;
+
+use crate::error::Error;
+use crate::span::{Position, Span};
+use crate::value::Value;
+
+/// An iterator over the records of a JSON5 Lines document; see `lines`.
+#[derive(Clone, Debug)]
+pub struct Lines<'a> {
+    rest: &'a str,
+    start: Position,
+}
+
+/// Parses `input` as one JSON5 document per line, skipping blank lines.
+/// Error positions are relative to `input`, not to the line.
+pub fn lines(input: &str) -> Lines<'_> {
+    Lines {
+        rest: input,
+        start: Position::default(),
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+            let (line, rest) = match self.rest.find('\n') {
+                Some(i) => (&self.rest[..i], &self.rest[i + 1..]),
+                None => (self.rest, ""),
+            };
+            let start = self.start;
+
+            self.rest = rest;
+            self.start.offset += line.len() + 1;
+            self.start.line += 1;
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            if !line.trim().is_empty() {
+                return Some(crate::parse(line).map_err(|e| relocate(e, start)));
+            }
+        }
+    }
+}
+
+fn relocate(error: Error, start: Position) -> Error {
+    let shift = |p: Position| Position {
+        offset: start.offset + p.offset,
+        line: start.line + p.line - 1,
+        column: p.column,
+    };
+
+    match error.span() {
+        Some(span) => error.with_span(Span {
+            start: shift(span.start),
+            end: shift(span.end),
+        }),
+        None => error,
+    }
+}
+
+/// Stringifies each value on a line of its own, the inverse of `lines`.
+pub fn stringify_lines<'a, I>(values: I) -> String
+where
+    I: IntoIterator<Item = &'a Value>,
+{
+    let mut out = String::new();
+
+    for value in values {
+        out.push_str(&crate::stringify(value));
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,34 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use crate::map::Map;
+use crate::value::Value;
+
+type Hook<'a> = Box<dyn Fn(&str) -> Result<Value, String> + 'a>;
+
+/// Hooks for literals of the form `@name(argument)`, for
+/// `parse_with_literals`. Each turns the text of the argument into a value,
+/// or into an error message.
+#[derive(Default)]
+pub struct Literals<'a> {
+    hooks: Map<String, Hook<'a>>,
+}
+
+impl<'a> Literals<'a> {
+    pub fn new() -> Self {
+        Literals { hooks: Map::new() }
+    }
+
+    /// Adds a hook for `@name(...)`, replacing any before it.
+    pub fn with<F>(mut self, name: &str, hook: F) -> Self
+    where
+        F: Fn(&str) -> Result<Value, String> + 'a,
+    {
+        self.hooks.insert(name.into(), Box::new(hook));
+        self
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Hook<'a>> {
+        self.hooks.get(name)
+    }
+}
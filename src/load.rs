@@ -0,0 +1,149 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::map::Map;
+use crate::value::Value;
+
+/// An error loading one of the files of a directory, with its path.
+#[derive(Debug)]
+pub enum LoadError {
+    Io { path: PathBuf, error: io::Error },
+    Parse { path: PathBuf, error: Error },
+}
+
+impl LoadError {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Io { path, .. } | Self::Parse { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            Self::Parse { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Parses the files directly inside `dir` whose names match `pattern`, in
+/// which `*` matches any run of characters and `?` any single one. Values are
+/// keyed by file name. Stops at the first failure; see `load_dir_all`.
+pub fn load_dir<P>(dir: P, pattern: &str) -> Result<Map<String, Value>, LoadError>
+where
+    P: AsRef<Path>,
+{
+    let mut values = Map::new();
+
+    for path in matching_files(dir.as_ref(), pattern)? {
+        let (name, value) = load_file(&path)?;
+        values.insert(name, value);
+    }
+    Ok(values)
+}
+
+/// Like `load_dir`, but carries on past failures and reports every file that
+/// couldn't be loaded.
+pub fn load_dir_all<P>(dir: P, pattern: &str) -> Result<Map<String, Value>, Vec<LoadError>>
+where
+    P: AsRef<Path>,
+{
+    let mut values = Map::new();
+    let mut errors = Vec::new();
+
+    for path in matching_files(dir.as_ref(), pattern).map_err(|e| vec![e])? {
+        match load_file(&path) {
+            Ok((name, value)) => {
+                values.insert(name, value);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors)
+    }
+}
+
+// Sorted, so that errors are reported in a stable order.
+fn matching_files(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>, LoadError> {
+    let io_error = |error| LoadError::Io {
+        path: dir.to_path_buf(),
+        error,
+    };
+    let mut paths = Vec::new();
+
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        let is_file = entry.file_type().map_err(io_error)?.is_file();
+        let name = entry.file_name();
+
+        if is_file && name.to_str().is_some_and(|n| matches(pattern, n)) {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn load_file(path: &Path) -> Result<(String, Value), LoadError> {
+    let json = fs::read_to_string(path).map_err(|error| LoadError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let value = crate::parse(&json).map_err(|error| LoadError::Parse {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok((name, value))
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where to resume after the last `*` if the rest fails to match
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    p = star + 1;
+                    n = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
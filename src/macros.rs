@@ -0,0 +1,34 @@
+/// The value at a path written as Rust accessors, if there is one:
+/// `path!(config.servers[0].host)` is `Option<&Value>`.
+///
+/// Keys that aren't identifiers are written as string literals, as in
+/// `path!(config."log-level")`, indices may be any expression, and the
+/// value may be a parenthesized expression. The path is checked when
+/// compiling, and followed without parsing a pointer or allocating.
+#[macro_export]
+macro_rules! path {
+    (@step $value:expr ;) => {
+        $value
+    };
+    (@step $value:expr ; . $key:ident $($rest:tt)*) => {
+        $crate::path!(@step $value.and_then(|v| v.get(stringify!($key))) ; $($rest)*)
+    };
+    (@step $value:expr ; . $key:literal $($rest:tt)*) => {
+        $crate::path!(@step $value.and_then(|v| v.get($key)) ; $($rest)*)
+    };
+    (@step $value:expr ; [$index:expr] $($rest:tt)*) => {
+        $crate::path!(
+            @step $value.and_then(|v| v.to_vec()).and_then(|v| v.get($index)) ; $($rest)*
+        )
+    };
+    ($value:ident $($rest:tt)*) => {
+        $crate::path!(@step $crate::path!(@root $value) ; $($rest)*)
+    };
+    (($value:expr) $($rest:tt)*) => {
+        $crate::path!(@step $crate::path!(@root $value) ; $($rest)*)
+    };
+    // A value or a reference to one, as `Option<&Value>`.
+    (@root $value:expr) => {
+        ::core::option::Option::Some(::core::borrow::Borrow::<$crate::Value>::borrow(&$value))
+    };
+}
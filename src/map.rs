@@ -0,0 +1,17 @@
+// The object representation is chosen at compile time. `preserve_order`
+// keeps keys in insertion order and takes precedence over `btree`, which
+// keeps them sorted. Without either, objects are hash maps, falling back to
+// sorted maps when `std` is unavailable.
+#[cfg(feature = "preserve_order")]
+pub type Map<K, V> = indexmap::IndexMap<K, V>;
+#[cfg(all(
+    not(feature = "preserve_order"),
+    any(feature = "btree", not(feature = "std"))
+))]
+pub type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+#[cfg(all(
+    not(feature = "preserve_order"),
+    not(feature = "btree"),
+    feature = "std"
+))]
+pub type Map<K, V> = std::collections::HashMap<K, V>;
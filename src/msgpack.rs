@@ -0,0 +1,177 @@
+//! Conversion to and from MessagePack.
+
+use alloc::vec::Vec;
+
+use crate::bytes::{self, Reader};
+use crate::error::Error;
+use crate::map::Map;
+use crate::value::Value;
+
+impl Value {
+    /// Encodes the value as MessagePack, using the smallest representation
+    /// for integers and 64-bit floats so that every value, NaN and the
+    /// infinities included, reads back unchanged.
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_value(&mut out, self);
+        out
+    }
+
+    /// Decodes a MessagePack value. Binary and extension types are rejected,
+    /// as are map keys that aren't strings.
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Value, Error> {
+        let mut reader = Reader::new(bytes);
+        let value = read_value(&mut reader)?;
+        reader.finish(value)
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Boolean(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Integer(i) => write_integer(out, *i),
+        Value::Float(f) => {
+            out.push(0xcb);
+            out.extend_from_slice(&f.to_bits().to_be_bytes());
+        }
+        Value::String(s) => write_str(out, s),
+        Value::Array(v) => {
+            write_len(out, v.len(), 0x90, 0xdc);
+            for item in v {
+                write_value(out, item);
+            }
+        }
+        Value::Object(m) => {
+            write_len(out, m.len(), 0x80, 0xde);
+            for (key, item) in m {
+                write_str(out, key);
+                write_value(out, item);
+            }
+        }
+        Value::Custom(c) => write_value(out, &c.to_value()),
+    }
+}
+
+fn write_integer(out: &mut Vec<u8>, i: i32) {
+    match i {
+        -32..=0x7f => out.push(i as u8),
+        0x80..=0xff => out.extend_from_slice(&[0xcc, i as u8]),
+        0x100..=0xffff => {
+            out.push(0xcd);
+            out.extend_from_slice(&(i as u16).to_be_bytes());
+        }
+        -128..=-33 => out.extend_from_slice(&[0xd0, i as u8]),
+        -32768..=-129 => {
+            out.push(0xd1);
+            out.extend_from_slice(&(i as i16).to_be_bytes());
+        }
+        _ if i > 0 => {
+            out.push(0xce);
+            out.extend_from_slice(&(i as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xd2);
+            out.extend_from_slice(&i.to_be_bytes());
+        }
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    let len = s.len();
+
+    if len < 32 {
+        out.push(0xa0 | len as u8);
+    } else if len <= 0xff {
+        out.extend_from_slice(&[0xd9, len as u8]);
+    } else if len <= 0xffff {
+        out.push(0xda);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(0xdb);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    out.extend_from_slice(s.as_bytes());
+}
+
+// Arrays and maps share their encoding but for the markers: `fix` for up to
+// 15 elements, followed by those for 16-bit and 32-bit lengths.
+fn write_len(out: &mut Vec<u8>, len: usize, fix: u8, marker16: u8) {
+    if len < 16 {
+        out.push(fix | len as u8);
+    } else if len <= 0xffff {
+        out.push(marker16);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(marker16 + 1);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+fn read_value(reader: &mut Reader) -> Result<Value, Error> {
+    let marker = reader.byte()?;
+
+    let value = match marker {
+        0x00..=0x7f => Value::Integer(marker.into()),
+        0x80..=0x8f => read_map(reader, u64::from(marker & 0x0f))?,
+        0x90..=0x9f => read_array(reader, u64::from(marker & 0x0f))?,
+        0xa0..=0xbf => Value::String(reader.string(u64::from(marker & 0x1f))?),
+        0xc0 => Value::Null,
+        0xc2 => Value::Boolean(false),
+        0xc3 => Value::Boolean(true),
+        0xca => Value::Float(f32::from_bits(reader.uint(4)? as u32).into()),
+        0xcb => Value::Float(f64::from_bits(reader.uint(8)?)),
+        0xcc..=0xcf => {
+            let n = reader.uint(1 << (marker - 0xcc))?;
+            bytes::integer(n.into())
+        }
+        0xd0..=0xd3 => {
+            let len = 1 << (marker - 0xd0);
+            let n = reader.uint(len)?;
+            // Sign-extend from the encoded width.
+            let shift = 64 - 8 * len;
+            bytes::integer((((n << shift) as i64) >> shift).into())
+        }
+        0xd9..=0xdb => {
+            let len = reader.uint(1 << (marker - 0xd9))?;
+            Value::String(reader.string(len)?)
+        }
+        0xdc | 0xdd => {
+            let len = reader.uint(2 << (marker - 0xdc))?;
+            read_array(reader, len)?
+        }
+        0xde | 0xdf => {
+            let len = reader.uint(2 << (marker - 0xde))?;
+            read_map(reader, len)?
+        }
+        0xe0..=0xff => Value::Integer((marker as i8).into()),
+        _ => return Err(reader.error("unsupported MessagePack type")),
+    };
+    Ok(value)
+}
+
+fn read_array(reader: &mut Reader, len: u64) -> Result<Value, Error> {
+    let mut items = Vec::with_capacity(reader.capacity(len));
+
+    reader.enter()?;
+    for _ in 0..len {
+        items.push(read_value(reader)?);
+    }
+    reader.leave();
+    Ok(Value::Array(items))
+}
+
+fn read_map(reader: &mut Reader, len: u64) -> Result<Value, Error> {
+    let mut map = Map::new();
+
+    reader.enter()?;
+    for _ in 0..len {
+        let key = match read_value(reader)? {
+            Value::String(key) => key,
+            _ => return Err(reader.error("map key is not a string")),
+        };
+        map.insert(key, read_value(reader)?);
+    }
+    reader.leave();
+    Ok(Value::Object(map))
+}
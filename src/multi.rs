@@ -0,0 +1,133 @@
+//! Documents whose objects keep every member, for configurations that
+//! repeat keys on purpose.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::{Error, TokenKind};
+use crate::map::Map;
+use crate::options::ParseOptions;
+use crate::parser::Parser;
+use crate::value::Value;
+
+/// A value in which objects are lists of members, in the order written,
+/// with repeated keys kept.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultiValue {
+    /// Null, a boolean, a number or a string.
+    Scalar(Value),
+    Array(Vec<MultiValue>),
+    Object(Vec<(String, MultiValue)>),
+}
+
+impl MultiValue {
+    /// The last value for `key`, the one parsing would keep, if this is an
+    /// object that has it.
+    pub fn get(&self, key: &str) -> Option<&MultiValue> {
+        match self {
+            MultiValue::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Every value for `key`, in order, if this is an object.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a MultiValue> + 'a {
+        let members = match self {
+            MultiValue::Object(members) => &members[..],
+            _ => &[],
+        };
+        members
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The value with the last of repeated keys kept, in the place of the
+    /// first, as `DuplicateKeys::LastWins` has it.
+    pub fn into_value(self) -> Value {
+        match self {
+            MultiValue::Scalar(value) => value,
+            MultiValue::Array(v) => Value::Array(v.into_iter().map(Self::into_value).collect()),
+            MultiValue::Object(members) => {
+                let mut m = Map::new();
+                for (key, value) in members {
+                    m.insert(key, value.into_value());
+                }
+                Value::Object(m)
+            }
+        }
+    }
+}
+
+/// Like `parse_with_options`, but keeping every value of repeated keys
+/// rather than handling them as `options.duplicate_keys` says.
+pub fn parse_multi(json: &str, options: &ParseOptions) -> Result<MultiValue, Error> {
+    let mut parser = Parser::with_options(json, *options);
+
+    crate::parse_document_with(&mut parser, Parser::parse_multi_value)
+}
+
+impl Parser<'_> {
+    fn parse_multi_value(&mut self) -> Result<MultiValue, Error> {
+        let parse = match self.ch {
+            Some('[') => Self::parse_multi_array,
+            Some('{') => Self::parse_multi_object,
+            _ => return self.parse_value().map(MultiValue::Scalar),
+        };
+        self.enter()?;
+        let result = parse(self);
+        self.leave();
+        result
+    }
+
+    fn parse_multi_array(&mut self) -> Result<MultiValue, Error> {
+        let mut v = Vec::new();
+
+        self.next();
+        self.skip_comments()?;
+
+        loop {
+            if let Some(']') = self.ch {
+                self.next();
+                return Ok(MultiValue::Array(v));
+            }
+            if self.ch.is_none() {
+                return Err(self.unexpected(&[TokenKind::Value, TokenKind::Char(']')]));
+            }
+            v.push(self.parse_multi_value()?);
+            if self.parse_separator(']', TokenKind::Value)? {
+                return Ok(MultiValue::Array(v));
+            }
+        }
+    }
+
+    fn parse_multi_object(&mut self) -> Result<MultiValue, Error> {
+        let mut members = Vec::new();
+
+        self.next();
+        self.skip_comments()?;
+
+        loop {
+            let key = match self.ch {
+                Some('}') => {
+                    self.next();
+                    return Ok(MultiValue::Object(members));
+                }
+                Some(_) => self.parse_key()?,
+                None => return Err(self.unexpected(&[TokenKind::Key, TokenKind::Char('}')])),
+            };
+            self.skip_comments()?;
+            self.consume(':')?;
+            self.skip_comments()?;
+
+            members.push((key, self.parse_multi_value()?));
+            if self.parse_separator('}', TokenKind::Key)? {
+                return Ok(MultiValue::Object(members));
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// A struct field that tells a missing key from one set to `null`, which
+/// `Option` doesn't, e.g. so that merging configurations can let `null`
+/// clear a value that a missing key would have kept.
+///
+/// The field needs `#[serde(default)]` to be `Absent` when the key is
+/// missing. When serializing, `#[serde(skip_serializing_if =
+/// "OptionalField::is_absent")]` leaves it out in that case; otherwise it is
+/// written as `null`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OptionalField<T> {
+    #[default]
+    Absent,
+    Null,
+    Present(T),
+}
+
+impl<T> OptionalField<T> {
+    pub fn is_absent(&self) -> bool {
+        matches!(self, OptionalField::Absent)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, OptionalField::Null)
+    }
+
+    pub fn as_ref(&self) -> OptionalField<&T> {
+        match self {
+            OptionalField::Absent => OptionalField::Absent,
+            OptionalField::Null => OptionalField::Null,
+            OptionalField::Present(value) => OptionalField::Present(value),
+        }
+    }
+
+    /// The value, if there is one, whether the key was missing or `null`.
+    pub fn present(self) -> Option<T> {
+        match self {
+            OptionalField::Present(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// This field, or `other` when the key was missing, as when `self` comes
+    /// from a configuration layered over `other`'s.
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            OptionalField::Absent => other,
+            _ => self,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for OptionalField<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => OptionalField::Present(value),
+            None => OptionalField::Null,
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for OptionalField<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(OptionalFieldVisitor(PhantomData))
+    }
+}
+
+struct OptionalFieldVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for OptionalFieldVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = OptionalField<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an optional value")
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(OptionalField::Null)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(OptionalField::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(OptionalField::Present)
+    }
+}
+
+impl<T> Serialize for OptionalField<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            OptionalField::Present(value) => serializer.serialize_some(value),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use core::sync::atomic::AtomicBool;
+
+/// What to do when an object contains the same key more than once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Keep the last value, reporting a warning.
+    LastWins,
+    /// Keep the first value, reporting a warning.
+    FirstWins,
+    /// Fail with `ErrorKind::DuplicateKey`.
+    Error,
+}
+
+/// The default options accept all of JSON5; `ParseOptions::strict` accepts
+/// only JSON, and `ParseOptions::hjson` adds the non-standard HJSON syntax.
+/// Each extension can also be switched on or off individually.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub duplicate_keys: DuplicateKeys,
+    /// Replace unpaired surrogates in `\u` escapes with U+FFFD, reporting a
+    /// warning, rather than failing with `ErrorKind::InvalidEscape`.
+    pub replace_lone_surrogates: bool,
+    pub allow_comments: bool,
+    pub allow_single_quotes: bool,
+    pub allow_trailing_commas: bool,
+    pub allow_unquoted_keys: bool,
+    /// Unquoted keys that are reserved words or literals, such as `null`,
+    /// `true`, `if` and `Infinity`. JSON5 allows them, as JavaScript does,
+    /// but with this off they must be quoted.
+    pub allow_reserved_word_keys: bool,
+    pub allow_infinity_nan: bool,
+    pub allow_hex: bool,
+    pub allow_leading_plus: bool,
+    pub allow_leading_decimal_point: bool,
+    /// Numbers ending with a decimal point, as in `1.` or `1.e5`.
+    pub allow_trailing_decimal_point: bool,
+    /// The escapes JSON doesn't have: `\'`, `\v`, `\0`, `\xXX`, and a
+    /// backslash before any other character, which stands for that
+    /// character.
+    pub allow_json5_escapes: bool,
+    /// A backslash at the end of a line inside a string, which continues
+    /// the string on the next line.
+    pub allow_line_continuations: bool,
+    /// HJSON: unquoted keys made of any non-punctuation characters, and
+    /// unquoted string values running to the end of the line.
+    pub allow_quoteless_strings: bool,
+    /// HJSON: array elements and object entries separated by line breaks
+    /// alone.
+    pub allow_missing_commas: bool,
+    /// HJSON: `'''` strings spanning lines, without escapes, and with the
+    /// indentation of the opening `'''` removed from every line.
+    pub allow_triple_quoted_strings: bool,
+    /// HJSON: `#` comments to the end of the line.
+    pub allow_hash_comments: bool,
+    /// Not part of JSON5 or HJSON: `` ` ``-delimited strings, which may span
+    /// lines and are taken verbatim, without escape sequences.
+    pub allow_backtick_strings: bool,
+    /// Accept control characters other than line breaks in strings as they
+    /// are, reporting a warning, rather than requiring them to be escaped.
+    pub allow_control_characters: bool,
+    /// Not part of JSON5: strings joined with `+`, as in `"a" + "b"`, so that
+    /// long values can be wrapped over several lines.
+    pub allow_string_concatenation: bool,
+    /// Not part of JSON5: RFC 3339 dates and timestamps left unquoted, as in
+    /// TOML, or after `@`, as in `@2024-05-01T00:00:00Z`. They are read as
+    /// strings, for `Value::to_datetime` to convert, as dates from `chrono`
+    /// are. Dates that don't exist, such as `2024-02-31`, are errors.
+    pub allow_date_literals: bool,
+}
+
+impl ParseOptions {
+    /// The default options, for constants and statics.
+    pub const fn new() -> Self {
+        ParseOptions {
+            duplicate_keys: DuplicateKeys::LastWins,
+            replace_lone_surrogates: false,
+            allow_comments: true,
+            allow_single_quotes: true,
+            allow_trailing_commas: true,
+            allow_unquoted_keys: true,
+            allow_reserved_word_keys: true,
+            allow_infinity_nan: true,
+            allow_hex: true,
+            allow_leading_plus: true,
+            allow_leading_decimal_point: true,
+            allow_trailing_decimal_point: true,
+            allow_json5_escapes: true,
+            allow_line_continuations: true,
+            allow_quoteless_strings: false,
+            allow_missing_commas: false,
+            allow_triple_quoted_strings: false,
+            allow_hash_comments: false,
+            allow_backtick_strings: false,
+            allow_control_characters: false,
+            allow_string_concatenation: false,
+            allow_date_literals: false,
+        }
+    }
+
+    pub const fn strict() -> Self {
+        ParseOptions {
+            allow_comments: false,
+            allow_single_quotes: false,
+            allow_trailing_commas: false,
+            allow_unquoted_keys: false,
+            allow_infinity_nan: false,
+            allow_hex: false,
+            allow_leading_plus: false,
+            allow_leading_decimal_point: false,
+            allow_trailing_decimal_point: false,
+            allow_json5_escapes: false,
+            allow_line_continuations: false,
+            ..Self::new()
+        }
+    }
+
+    pub const fn hjson() -> Self {
+        ParseOptions {
+            allow_quoteless_strings: true,
+            allow_missing_commas: true,
+            allow_triple_quoted_strings: true,
+            allow_hash_comments: true,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StringifyOptions {
+    /// Splits strings longer than this many characters into pieces joined
+    /// with `+` on separate lines, preferably after whitespace. The output
+    /// needs `ParseOptions::allow_string_concatenation` to be read back.
+    pub wrap_strings_at: Option<usize>,
+    /// Writes object keys in sorted order, so that the output is the same
+    /// from one run to the next. Without the `preserve_order` or `btree`
+    /// features, objects are hash maps, whose order changes between runs.
+    pub sort_keys: bool,
+    pub non_finite: NonFinite,
+    /// Rounds floats to this many significant digits, e.g. to write
+    /// computed values as people would: `0.30000000000000004` as `0.3`.
+    pub significant_digits: Option<u8>,
+    /// Ends the lines of strings split by `wrap_strings_at`, and those
+    /// `Json5Writer` writes.
+    pub line_ending: LineEnding,
+    pub trailing_commas: TrailingCommas,
+    /// Writes arrays of more numbers than this with this many to a line,
+    /// e.g. for the rows of a matrix.
+    pub numbers_per_line: Option<usize>,
+}
+
+impl StringifyOptions {
+    /// The default options, for constants and statics.
+    pub const fn new() -> Self {
+        StringifyOptions {
+            wrap_strings_at: None,
+            sort_keys: false,
+            non_finite: NonFinite::Literal,
+            significant_digits: None,
+            line_ending: LineEnding::Lf,
+            trailing_commas: TrailingCommas::Never,
+            numbers_per_line: None,
+        }
+    }
+}
+
+impl Default for StringifyOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the last element of an array, or member of an object, is
+/// followed by a comma.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingCommas {
+    Always,
+    #[default]
+    Never,
+    /// Only when the array or object spans lines, so that its closing
+    /// bracket is on a line of its own.
+    Multiline,
+}
+
+/// What ends each line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineEnding {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
+/// How NaN and infinite floats are written. JSON has no way to write them,
+/// so all but the default make output meant for JSON parsers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonFinite {
+    /// As `NaN`, `Infinity` and `-Infinity`, which JSON5 reads.
+    #[default]
+    Literal,
+    Null,
+    /// As the strings `"NaN"`, `"Infinity"` and `"-Infinity"`.
+    String,
+    /// Infinities as the largest finite floats of their sign, and NaN as
+    /// null.
+    Clamp,
+    /// Fail with the pointer to the float. Only `try_stringify_with_options`
+    /// can fail; `stringify_with_options` writes them as `Literal` does.
+    Error,
+}
+
+/// How object keys are matched by `Value::get_with`, `pointer_with` and
+/// `at_with`, and by a `Deserializer` made `with_key_matching` to struct
+/// fields. The default matches keys exactly.
+///
+/// Of several keys that match, one that matches exactly is taken first,
+/// then one that differs only in case, then only in separators, then in
+/// both. Among those that match equally well, the key that sorts first is
+/// taken, so that the choice doesn't depend on the order of the object.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyMatching {
+    /// `maxRetries` matches `MaxRetries` and `MAXRETRIES`.
+    pub ignore_case: bool,
+    /// `-` and `_` are the same, so that `max-retries` matches `max_retries`.
+    pub ignore_separators: bool,
+}
+
+impl KeyMatching {
+    /// Both case and separators ignored.
+    pub const fn loose() -> Self {
+        KeyMatching {
+            ignore_case: true,
+            ignore_separators: true,
+        }
+    }
+
+    pub fn matches(&self, a: &str, b: &str) -> bool {
+        let (a, b) = (self.chars(a), self.chars(b));
+        if self.ignore_case {
+            a.flat_map(char::to_lowercase)
+                .eq(b.flat_map(char::to_lowercase))
+        } else {
+            a.eq(b)
+        }
+    }
+
+    // The entry whose key best matches `key`, as the type's doc says.
+    pub(crate) fn best<'a, T, I>(&self, entries: I, key: &str) -> Option<(&'a str, T)>
+    where
+        I: IntoIterator<Item = (&'a str, T)>,
+    {
+        entries
+            .into_iter()
+            .filter_map(|(k, value)| Some((self.rank(k, key)?, k, value)))
+            .min_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)))
+            .map(|(_, k, value)| (k, value))
+    }
+
+    // How far `a` is from `b`, from an exact match up, if it matches.
+    fn rank(&self, a: &str, b: &str) -> Option<u8> {
+        let case_only = KeyMatching {
+            ignore_case: true,
+            ignore_separators: false,
+        };
+        let separators_only = KeyMatching {
+            ignore_case: false,
+            ignore_separators: true,
+        };
+        if a == b {
+            Some(0)
+        } else if self.ignore_case && case_only.matches(a, b) {
+            Some(1)
+        } else if self.ignore_separators && separators_only.matches(a, b) {
+            Some(2)
+        } else if self.matches(a, b) {
+            Some(3)
+        } else {
+            None
+        }
+    }
+
+    fn chars<'a>(&self, s: &'a str) -> impl Iterator<Item = char> + 'a {
+        let ignore_separators = self.ignore_separators;
+        s.chars().map(move |c| match c {
+            '-' if ignore_separators => '_',
+            c => c,
+        })
+    }
+}
+
+/// Bounds on the work done parsing a document, for `parse_with_limits`, so
+/// that untrusted input takes bounded time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Limits<'a> {
+    /// Fail with `ErrorKind::BudgetExceeded` for longer documents.
+    pub max_bytes: Option<usize>,
+    /// Fail with `ErrorKind::BudgetExceeded` after this many values, arrays
+    /// and objects included.
+    pub max_values: Option<usize>,
+    /// Fail with `ErrorKind::Cancelled` once this is set, e.g. by another
+    /// thread when a deadline has passed. It is checked before each value.
+    pub cancel: Option<&'a AtomicBool>,
+}
@@ -1,63 +1,289 @@
-use std::collections::HashMap;
-use std::iter::Peekable;
-use std::str::Chars;
-use std::str::FromStr;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::Peekable;
+use core::num::IntErrorKind;
+use core::str::Chars;
+use core::str::FromStr;
+use core::sync::atomic::Ordering;
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind, TokenKind};
+#[cfg(feature = "config")]
+use crate::expr;
+#[cfg(feature = "config")]
+use crate::literals::Literals;
+use crate::map::Map;
+use crate::options::{DuplicateKeys, Limits, ParseOptions};
+use crate::pointer::Pointer;
+use crate::span::{Position, Span};
 use crate::value::Value;
+use crate::warning::{Duplicate, Warning, WarningKind};
+
+pub(crate) const MAX_DEPTH: usize = 128;
+
+// Words JavaScript reserves, and the literals JSON5 has, which may be keys
+// unless `ParseOptions::allow_reserved_word_keys` is off.
+const RESERVED_WORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "Infinity",
+    "NaN",
+];
 
 pub struct Parser<'a> {
     pub chars: Peekable<Chars<'a>>,
     pub ch: Option<char>,
+    pub position: Position,
+    pub warnings: Vec<Warning>,
+    /// The span of every value parsed, by JSON Pointer, when set.
+    pub spans: Option<Vec<(Pointer, Span)>>,
+    /// Every repeated key and how it was resolved, when set.
+    pub duplicates: Option<Vec<Duplicate>>,
+    /// The span of every object key parsed, by the JSON Pointer of its
+    /// value, when set.
+    pub key_spans: Option<Vec<(Pointer, Span)>>,
+    options: ParseOptions,
+    depth: usize,
+    path: Vec<String>,
+    /// Reused for the text of every number.
+    pub number: String,
+    /// How many characters have been read, counting those read again after
+    /// backtracking.
+    #[cfg(feature = "bench")]
+    pub steps: usize,
+    pub limits: Limits<'a>,
+    values: usize,
+    /// Called with the offset reached, between values, every so many bytes.
+    pub progress: Option<Progress<'a>>,
+    /// When set, values may be expressions over these variables, and strings
+    /// may interpolate them.
+    #[cfg(feature = "config")]
+    pub variables: Option<&'a Map<String, Value>>,
+    /// When set, numbers may be followed by one of these units, and are
+    /// multiplied by its factor.
+    pub units: Option<&'a Map<String, f64>>,
+    /// When set, values may be `@name(...)` literals for these hooks.
+    #[cfg(feature = "config")]
+    pub literals: Option<&'a Literals<'a>>,
+}
+
+pub struct Progress<'a> {
+    pub every: usize,
+    pub next: usize,
+    pub callback: &'a mut dyn FnMut(usize),
+}
+
+// Parser state to backtrack to.
+struct Checkpoint<'a> {
+    chars: Peekable<Chars<'a>>,
+    ch: Option<char>,
+    position: Position,
+    warnings: usize,
 }
 
 impl<'a> Parser<'a> {
+    pub fn with_options(json: &'a str, options: ParseOptions) -> Self {
+        Parser {
+            chars: json.chars().peekable(),
+            ch: None,
+            position: Position::default(),
+            warnings: Vec::new(),
+            spans: None,
+            duplicates: None,
+            key_spans: None,
+            options,
+            depth: 0,
+            path: Vec::new(),
+            number: String::new(),
+            #[cfg(feature = "bench")]
+            steps: 0,
+            limits: Limits::default(),
+            values: 0,
+            progress: None,
+            #[cfg(feature = "config")]
+            variables: None,
+            units: None,
+            #[cfg(feature = "config")]
+            literals: None,
+        }
+    }
+
+    /// A parser for the value at `path` in a larger document, where `json`
+    /// starts at `start`, so that positions are those in the document.
+    #[cfg(feature = "cst")]
+    pub fn resume(
+        json: &'a str,
+        options: ParseOptions,
+        start: Position,
+        path: Vec<String>,
+    ) -> Self {
+        Parser {
+            position: start,
+            depth: path.len(),
+            path,
+            ..Self::with_options(json, options)
+        }
+    }
+
     pub fn next(&mut self) {
+        if let Some(c) = self.ch {
+            self.position.advance(c, self.chars.peek().copied());
+        }
         self.ch = self.chars.next();
+        #[cfg(feature = "bench")]
+        {
+            self.steps += 1;
+        }
+    }
+
+    /// An error for the current character, or for the end of the input.
+    pub fn unexpected(&self, expected: &[TokenKind]) -> Error {
+        let expected = expected.to_vec();
+        let mut end = self.position;
+
+        let kind = match self.ch {
+            Some(found) => {
+                end.advance(found, self.chars.clone().next());
+                ErrorKind::UnexpectedCharacter { found, expected }
+            }
+            None => ErrorKind::UnexpectedEof { expected },
+        };
+        Error::new(kind).with_span(Span {
+            start: self.position,
+            end,
+        })
+    }
+
+    // An error covering everything from `start` up to the current character.
+    fn error_from(&self, start: Position, kind: ErrorKind) -> Error {
+        Error::new(kind).with_span(Span {
+            start,
+            end: self.position,
+        })
+    }
+
+    fn warn_from(&mut self, start: Position, kind: WarningKind) {
+        let span = Span {
+            start,
+            end: self.position,
+        };
+        self.warnings.push(Warning { kind, span });
     }
 
     fn peek(&mut self) -> Option<char> {
-        match self.chars.peek() {
-            None => None,
-            Some(c) => Some(c.clone()),
-        }
+        self.chars.peek().copied()
     }
 
-    fn expect(&mut self, ch: char) -> Result<(), Error> {
-        match self.ch {
-            None => Err(Error::UnexpectedEndOfJson),
-            Some(c) if c == ch => Ok(()),
-            _ => Err(Error::UnexpectedCharacter),
+    // Whether the input continues with `s`, starting at the current character.
+    fn looking_at(&self, s: &str) -> bool {
+        let mut expected = s.chars();
+        let mut chars = self.chars.clone();
+
+        expected.next() == self.ch && expected.all(|c| chars.next() == Some(c))
+    }
+
+    fn checkpoint(&self) -> Checkpoint<'a> {
+        Checkpoint {
+            chars: self.chars.clone(),
+            ch: self.ch,
+            position: self.position,
+            warnings: self.warnings.len(),
         }
     }
 
-    fn consume(&mut self, ch: char) -> Result<(), Error> {
-        self.expect(ch)?;
-        self.next();
-        Ok(())
+    fn restore(&mut self, checkpoint: Checkpoint<'a>) {
+        self.chars = checkpoint.chars;
+        self.ch = checkpoint.ch;
+        self.position = checkpoint.position;
+        self.warnings.truncate(checkpoint.warnings);
     }
 
-    fn consume_sequence(&mut self, s: &str) -> Result<(), Error> {
-        for ch in s.chars() {
-            self.expect(ch)?;
-            self.next();
+    pub fn consume(&mut self, ch: char) -> Result<(), Error> {
+        match self.ch {
+            Some(c) if c == ch => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.unexpected(&[TokenKind::Char(ch)])),
+        }
+    }
+
+    // Consumes the rest of `literal`, whose first character is current.
+    fn consume_literal(&mut self, literal: &'static str) -> Result<(), Error> {
+        for ch in literal.chars() {
+            match self.ch {
+                Some(c) if c == ch => self.next(),
+                _ => return Err(self.unexpected(&[TokenKind::Literal(literal)])),
+            }
         }
         Ok(())
     }
 
     pub fn skip_comments(&mut self) -> Result<(), Error> {
-        self.skip_whitespace();
+        let options = self.options;
 
-        while let Some('/') = self.ch {
-            match self.peek() {
-                Some('/') => self.skip_single_line_comment(),
-                Some('*') => self.skip_multi_line_comment()?,
-                None => return Err(Error::UnexpectedEndOfJson),
-                _ => return Err(Error::UnexpectedCharacter),
-            }
+        loop {
             self.skip_whitespace();
+            match self.ch {
+                Some('/') if options.allow_comments => match self.peek() {
+                    Some('/') => self.skip_line(),
+                    Some('*') => self.skip_multi_line_comment()?,
+                    // may start a quoteless string, or be a division
+                    _ if options.allow_quoteless_strings || self.has_variables() => return Ok(()),
+                    _ => {
+                        self.next();
+                        let expected = [TokenKind::Char('/'), TokenKind::Char('*')];
+                        return Err(self.unexpected(&expected));
+                    }
+                },
+                Some('#') if options.allow_hash_comments => self.skip_line(),
+                _ => return Ok(()),
+            }
         }
-        Ok(())
     }
 
     fn skip_whitespace(&mut self) {
@@ -69,15 +295,19 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn skip_single_line_comment(&mut self) {
-        self.next();
-        self.next();
-
+    // Skips past the next line break, "\n", "\r\n" or "\r".
+    fn skip_line(&mut self) {
         while let Some(c) = self.ch {
             self.next();
             if c == '\n' {
                 return;
             }
+            if c == '\r' {
+                if self.ch == Some('\n') {
+                    self.next();
+                }
+                return;
+            }
         }
     }
 
@@ -95,45 +325,292 @@ impl<'a> Parser<'a> {
                 return Ok(());
             }
         }
-        Err(Error::UnexpectedEndOfJson)
+        Err(self.unexpected(&[TokenKind::Literal("*/")]))
     }
 
     pub fn parse_value(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+
+        if self
+            .limits
+            .cancel
+            .is_some_and(|c| c.load(Ordering::Relaxed))
+        {
+            return Err(self.error_from(start, ErrorKind::Cancelled));
+        }
+        self.values += 1;
+        if self.limits.max_values.is_some_and(|max| self.values > max) {
+            return Err(self.error_from(start, ErrorKind::BudgetExceeded));
+        }
+        if let Some(progress) = &mut self.progress {
+            if start.offset >= progress.next {
+                (progress.callback)(start.offset);
+                progress.next = start.offset + progress.every.max(1);
+            }
+        }
+        #[cfg(feature = "config")]
+        let value = match self.variables {
+            Some(_) => self.parse_expression()?,
+            None => self.parse_any_value()?,
+        };
+        #[cfg(not(feature = "config"))]
+        let value = self.parse_any_value()?;
+
+        if let Some(spans) = &mut self.spans {
+            let pointer: Pointer = self.path.iter().collect();
+            spans.push((
+                pointer,
+                Span {
+                    start,
+                    end: self.position,
+                },
+            ));
+        }
+        Ok(value)
+    }
+
+    fn parse_any_value(&mut self) -> Result<Value, Error> {
+        let options = self.options;
+
+        if options.allow_triple_quoted_strings && self.looking_at("'''") {
+            return self.parse_triple_quoted_string().map(Value::String);
+        }
+        match self.ch {
+            Some('"') | Some('\'') | Some('[') | Some('{') => self.parse_json5_value(),
+            Some('`') if options.allow_backtick_strings => self.parse_json5_value(),
+            Some(c) if options.allow_quoteless_strings && !is_punctuator(c) => {
+                self.parse_quoteless_value()
+            }
+            _ => self.parse_json5_value(),
+        }
+    }
+
+    // Values and variables joined by `+`, `-`, `*`, `/` and `%`, with the
+    // usual precedence, and grouped with parentheses.
+    #[cfg(feature = "config")]
+    fn parse_expression(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        let mut value = self.parse_term()?;
+
+        while let Some(op) = self.parse_operator(&['+', '-'])? {
+            let term = self.parse_term()?;
+            value = expr::apply(op, value, term)
+                .map_err(|message| self.error_from(start, ErrorKind::Custom(message)))?;
+        }
+        Ok(value)
+    }
+
+    #[cfg(feature = "config")]
+    fn parse_term(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        let mut value = self.parse_operand()?;
+
+        while let Some(op) = self.parse_operator(&['*', '/', '%'])? {
+            let operand = self.parse_operand()?;
+            value = expr::apply(op, value, operand)
+                .map_err(|message| self.error_from(start, ErrorKind::Custom(message)))?;
+        }
+        Ok(value)
+    }
+
+    // Moves past the next operator, if it is one of `operators`, and the
+    // comments around it.
+    #[cfg(feature = "config")]
+    fn parse_operator(&mut self, operators: &[char]) -> Result<Option<char>, Error> {
+        let checkpoint = self.checkpoint();
+
+        self.skip_comments()?;
+        match self.ch {
+            Some(op) if operators.contains(&op) => {
+                self.next();
+                self.skip_comments()?;
+                Ok(Some(op))
+            }
+            _ => {
+                self.restore(checkpoint);
+                Ok(None)
+            }
+        }
+    }
+
+    #[cfg(feature = "config")]
+    fn parse_operand(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        let next = self.peek();
+
+        match self.ch {
+            Some('(') => {
+                self.enter()?;
+                self.next();
+                self.skip_comments()?;
+                let value = self.parse_expression()?;
+                self.skip_comments()?;
+                self.consume(')')?;
+                self.leave();
+                Ok(value)
+            }
+            Some('-') if next.is_some_and(|c| c == '(' || is_identifier_start(c)) => {
+                self.next();
+                let value = self.parse_operand()?;
+                expr::negate(value)
+                    .map_err(|message| self.error_from(start, ErrorKind::Custom(message)))
+            }
+            Some(c) if is_identifier_start(c) => {
+                let checkpoint = self.checkpoint();
+                let name = self.parse_identifier();
+                if let "null" | "true" | "false" | "Infinity" | "NaN" = name.as_str() {
+                    self.restore(checkpoint);
+                    return self.parse_any_value();
+                }
+                match expr::variable(self.variables, &name) {
+                    Ok(value) => Ok(value.clone()),
+                    Err(message) => Err(self.error_from(start, ErrorKind::Custom(message))),
+                }
+            }
+            _ => self.parse_any_value(),
+        }
+    }
+
+    fn parse_json5_value(&mut self) -> Result<Value, Error> {
+        let options = self.options;
+
         match self.ch {
-            None => Err(Error::UnexpectedEndOfJson),
-            Some(c) => match c {
-                'n' => self.parse_null(),
-                't' | 'f' => self.parse_boolean(),
-                '0'..='9' | '+' | '-' | '.' | 'I' | 'N' => self.parse_number(),
-                '"' | '\'' => self.parse_string(),
-                '[' => self.parse_array(),
-                '{' => self.parse_object(),
-                _ => Err(Error::UnexpectedCharacter),
-            },
+            Some('n') => self.parse_null(),
+            Some(c @ 't') | Some(c @ 'f') => self.parse_boolean(c),
+            Some('0'..='9') if options.allow_date_literals && self.looking_at_date() => {
+                self.parse_date_literal()
+            }
+            #[cfg(feature = "config")]
+            Some('@') if self.literals.is_some() => self.parse_custom_literal(),
+            Some('@') if options.allow_date_literals => self.parse_date_literal(),
+            Some(c @ '0'..='9') | Some(c @ '-') => self.parse_number(c),
+            Some(c @ '+') if options.allow_leading_plus => self.parse_number(c),
+            Some(c @ '.') if options.allow_leading_decimal_point => self.parse_number(c),
+            Some(c @ 'I') | Some(c @ 'N') if options.allow_infinity_nan => self.parse_number(c),
+            Some('"') => self.parse_string_value('"'),
+            Some('\'') if options.allow_single_quotes => self.parse_string_value('\''),
+            Some('`') if options.allow_backtick_strings => {
+                self.parse_raw_string().map(Value::String)
+            }
+            Some('[') => self.nested(Self::parse_array),
+            Some('{') => self.nested(Self::parse_object),
+            _ => Err(self.unexpected(&[TokenKind::Value])),
+        }
+    }
+
+    // A literal or number if the line continues with nothing but a separator
+    // or a comment, otherwise the rest of the line as a string.
+    fn parse_quoteless_value(&mut self) -> Result<Value, Error> {
+        let checkpoint = self.checkpoint();
+
+        if let Ok(value) = self.parse_json5_value() {
+            while let Some(' ') | Some('\t') = self.ch {
+                self.next();
+            }
+            let at_end = match self.ch {
+                None | Some('\n') | Some('\r') | Some(',') | Some(']') | Some('}') | Some('#') => {
+                    true
+                }
+                Some('/') => matches!(self.peek(), Some('/') | Some('*')),
+                _ => false,
+            };
+            if at_end {
+                return Ok(value);
+            }
+        }
+        self.restore(checkpoint);
+
+        let mut s = String::new();
+        while let Some(c) = self.ch {
+            if c == '\n' || c == '\r' {
+                break;
+            }
+            s.push(c);
+            self.next();
         }
+        s.truncate(s.trim_end().len());
+        Ok(Value::String(s))
     }
 
-    fn parse_null(&mut self) -> Result<Value, Error> {
+    fn parse_triple_quoted_string(&mut self) -> Result<String, Error> {
+        let indent = self.position.column - 1;
+        let mut raw = String::new();
+
         self.next();
-        self.consume_sequence("ull")?;
-        Ok(Value::Null)
+        self.next();
+        self.next();
+
+        while !self.looking_at("'''") {
+            match self.ch {
+                Some(c) => raw.push(c),
+                None => return Err(self.unexpected(&[TokenKind::Literal("'''")])),
+            }
+            self.next();
+        }
+        self.next();
+        self.next();
+        self.next();
+
+        Ok(dedent(&raw, indent))
     }
 
-    fn parse_boolean(&mut self) -> Result<Value, Error> {
-        if self.ch.unwrap() == 't' {
+    fn parse_raw_string(&mut self) -> Result<String, Error> {
+        let mut s = String::new();
+
+        self.next();
+        loop {
+            match self.ch {
+                Some('`') => break,
+                Some(c) => s.push(c),
+                None => return Err(self.unexpected(&[TokenKind::Char('`')])),
+            }
             self.next();
-            self.consume_sequence("rue")?;
+        }
+        self.next();
+        Ok(s)
+    }
+
+    pub fn enter(&mut self) -> Result<(), Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error_from(self.position, ErrorKind::ExceededMaxDepth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn nested<F>(&mut self, parse: F) -> Result<Value, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<Value, Error>,
+    {
+        self.enter()?;
+        let result = parse(self);
+        self.leave();
+        result
+    }
+
+    pub fn parse_null(&mut self) -> Result<Value, Error> {
+        self.consume_literal("null")?;
+        Ok(Value::Null)
+    }
+
+    pub fn parse_boolean(&mut self, c: char) -> Result<Value, Error> {
+        if c == 't' {
+            self.consume_literal("true")?;
             Ok(Value::Boolean(true))
         } else {
-            self.next();
-            self.consume_sequence("alse")?;
+            self.consume_literal("false")?;
             Ok(Value::Boolean(false))
         }
     }
 
-    fn parse_number(&mut self) -> Result<Value, Error> {
-        let sign = match self.ch.unwrap() {
-            '+' | '-' => self.ch,
+    pub fn parse_number(&mut self, c: char) -> Result<Value, Error> {
+        let start = self.position;
+        let sign = match c {
+            '+' | '-' => Some(c),
             _ => None,
         };
 
@@ -141,104 +618,288 @@ impl<'a> Parser<'a> {
             self.next();
         }
 
+        let next = self.peek();
+        let options = self.options;
+
         match self.ch {
-            None => Err(Error::UnexpectedEndOfJson),
-            Some(c) => match c {
-                '0' => match self.peek() {
-                    None => self.parse_decimal_literal(sign),
-                    Some(c) => match c {
-                        '0'..='9' => Err(Error::UnparseableNumber),
-                        'x' | 'X' => self.parse_hex_integer_literal(sign),
-                        _ => self.parse_decimal_literal(sign),
-                    },
-                },
-                'I' => self.parse_infinity(sign),
-                'N' => self.parse_nan(),
-                _ => self.parse_decimal_literal(sign),
-            },
+            Some('0') if options.allow_hex && matches!(next, Some('x') | Some('X')) => {
+                self.parse_hex_integer_literal(start, sign)
+            }
+            Some('I') if options.allow_infinity_nan => self.parse_infinity(sign),
+            Some('N') if options.allow_infinity_nan => self.parse_nan(),
+            Some('.') if !options.allow_leading_decimal_point => {
+                Err(self.unexpected(&[TokenKind::Number]))
+            }
+            Some('0'..='9') | Some('.') => {
+                let leading_zero = self.ch == Some('0') && matches!(next, Some('0'..='9'));
+
+                match self.parse_decimal_literal(sign) {
+                    Ok(_) if leading_zero => Err(self.error_from(start, ErrorKind::InvalidNumber)),
+                    Ok(_) if !options.allow_trailing_decimal_point && self.trailing_point() => {
+                        Err(self.error_from(start, ErrorKind::InvalidNumber))
+                    }
+                    Ok(value) if self.units.is_some() => self.parse_unit(start, value),
+                    result => result.map_err(|kind| self.error_from(start, kind)),
+                }
+            }
+            _ => Err(self.unexpected(&[TokenKind::Number])),
         }
     }
 
-    fn parse_hex_integer_literal(&mut self, sign: Option<char>) -> Result<Value, Error> {
-        let mut buf = String::with_capacity(16);
-
+    fn parse_hex_integer_literal(
+        &mut self,
+        start: Position,
+        sign: Option<char>,
+    ) -> Result<Value, Error> {
+        self.number.clear();
         if let Some(c) = sign {
-            buf.push(c);
+            self.number.push(c);
         }
         self.next();
         self.next();
 
+        match self.ch {
+            Some(c) if c.is_ascii_hexdigit() => (),
+            _ => return Err(self.unexpected(&[TokenKind::HexDigit])),
+        }
         while let Some(c) = self.ch {
             if !c.is_ascii_hexdigit() {
                 break;
             }
-            buf.push(c);
+            self.number.push(c);
             self.next();
         }
 
-        match i32::from_str_radix(&buf, 16) {
+        match i32::from_str_radix(&self.number, 16) {
             Ok(i) => Ok(Value::Integer(i)),
-            Err(_) => Err(Error::UnparseableNumber),
+            Err(e) => Err(self.error_from(start, integer_error(e.kind()))),
         }
     }
 
-    fn parse_decimal_literal(&mut self, sign: Option<char>) -> Result<Value, Error> {
+    fn parse_decimal_literal(&mut self, sign: Option<char>) -> Result<Value, ErrorKind> {
         let mut is_float = false;
-        let mut buf = String::with_capacity(16);
 
+        self.number.clear();
         if let Some(c) = sign {
-            buf.push(c);
+            self.number.push(c);
         }
 
+        let units = self.units.is_some();
         while let Some(c) = self.ch {
             match c {
-                '0'..='9' | '+' | '-' => (),
+                '0'..='9' => (),
+                // only in an exponent, so that `1+1` is a sum
+                '+' | '-' if matches!(self.number.chars().last(), Some('e' | 'E')) => (),
+                // a unit, as in `5eV`
+                'e' | 'E' if units && !matches!(self.peek(), Some('0'..='9' | '+' | '-')) => break,
                 '.' | 'e' | 'E' => is_float = true,
                 _ => break,
             }
-            buf.push(c);
+            self.number.push(c);
             self.next();
         }
 
         if is_float {
-            match f64::from_str(&buf) {
-                Ok(f) => Ok(Value::Float(f)),
-                Err(_) => Err(Error::UnparseableNumber),
-            }
+            f64::from_str(&self.number)
+                .map(Value::Float)
+                .map_err(|_| ErrorKind::InvalidNumber)
         } else {
-            match i32::from_str(&buf) {
-                Ok(i) => Ok(Value::Integer(i)),
-                Err(_) => Err(Error::UnparseableNumber),
+            i32::from_str(&self.number)
+                .map(Value::Integer)
+                .map_err(|e| integer_error(e.kind()))
+        }
+    }
+
+    // Whether the number just read has a decimal point without digits after
+    // it.
+    fn trailing_point(&self) -> bool {
+        let mut chars = self.number.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '.' && !matches!(chars.peek(), Some('0'..='9')) {
+                return true;
             }
         }
+        false
     }
 
-    fn parse_infinity(&mut self, sign: Option<char>) -> Result<Value, Error> {
+    // Applies the unit following a number, if any.
+    fn parse_unit(&mut self, start: Position, value: Value) -> Result<Value, Error> {
+        let mut unit = String::new();
+        while let Some(c) = self.ch {
+            if !(c.is_alphabetic() || c == '%') {
+                break;
+            }
+            unit.push(c);
+            self.next();
+        }
+        if unit.is_empty() {
+            return Ok(value);
+        }
+
+        let factor = match self.units.and_then(|units| units.get(&unit)) {
+            Some(factor) => *factor,
+            None => {
+                let message = format!("unknown unit `{}`", unit);
+                return Err(self.error_from(start, ErrorKind::Custom(message)));
+            }
+        };
+        let n = value.to_f64().unwrap_or_default() * factor;
+        match n as i32 {
+            i if f64::from(i) == n => Ok(Value::Integer(i)),
+            _ => Ok(Value::Float(n)),
+        }
+    }
+
+    // Whether a date, `YYYY-`, starts at the current character.
+    fn looking_at_date(&self) -> bool {
+        let mut chars = self.chars.clone();
+        (0..3).all(|_| chars.next().is_some_and(|c| c.is_ascii_digit()))
+            && chars.next() == Some('-')
+    }
+
+    fn parse_date_literal(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        if self.ch == Some('@') {
+            self.next();
+        }
+
+        let mut s = String::new();
+        while let Some(c) = self.ch {
+            if !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | ':' | '.')) {
+                break;
+            }
+            s.push(c);
+            self.next();
+        }
+        if !is_date_time(&s) {
+            let message = "invalid date or time".to_string();
+            return Err(self.error_from(start, ErrorKind::Custom(message)));
+        }
+        Ok(Value::String(s))
+    }
+
+    // `@name(argument)`, where the argument is everything up to the matching
+    // parenthesis. Dates after `@` are left to `parse_date_literal`.
+    #[cfg(feature = "config")]
+    fn parse_custom_literal(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        let checkpoint = self.checkpoint();
+        self.next();
+
+        match self.ch {
+            Some(c) if is_identifier_start(c) => (),
+            _ if self.options.allow_date_literals => {
+                self.restore(checkpoint);
+                return self.parse_date_literal();
+            }
+            _ => return Err(self.unexpected(&[TokenKind::Key])),
+        }
+        let name = self.parse_identifier();
+        self.consume('(')?;
+
+        let mut argument = String::new();
+        let mut depth = 0;
+        loop {
+            match self.ch {
+                Some(')') if depth == 0 => break,
+                Some(c) => {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => (),
+                    }
+                    argument.push(c);
+                    self.next();
+                }
+                None => return Err(self.unexpected(&[TokenKind::Char(')')])),
+            }
+        }
         self.next();
-        self.consume_sequence("nfinity")?;
+
+        let hook = self.literals.and_then(|literals| literals.get(&name));
+        let result = match hook {
+            Some(hook) => hook(&argument),
+            None => Err(format!("unknown literal `@{}`", name)),
+        };
+        result.map_err(|message| self.error_from(start, ErrorKind::Custom(message)))
+    }
+
+    fn parse_infinity(&mut self, sign: Option<char>) -> Result<Value, Error> {
+        self.consume_literal("Infinity")?;
         match sign {
-            Some('-') => Ok(Value::Float(std::f64::NEG_INFINITY)),
-            _ => Ok(Value::Float(std::f64::INFINITY)),
+            Some('-') => Ok(Value::Float(f64::NEG_INFINITY)),
+            _ => Ok(Value::Float(f64::INFINITY)),
         }
     }
 
     fn parse_nan(&mut self) -> Result<Value, Error> {
-        self.next();
-        self.consume_sequence("aN")?;
-        Ok(Value::Float(std::f64::NAN))
+        let start = self.position;
+
+        self.consume_literal("NaN")?;
+        self.warn_from(start, WarningKind::NaN);
+        Ok(Value::Float(f64::NAN))
+    }
+
+    fn parse_string_value(&mut self, mark: char) -> Result<Value, Error> {
+        #[cfg(feature = "config")]
+        let start = self.position;
+        let mut s = self.parse_string(mark)?;
+
+        while self.options.allow_string_concatenation {
+            let checkpoint = self.checkpoint();
+
+            self.skip_comments()?;
+            if self.ch != Some('+') {
+                self.restore(checkpoint);
+                break;
+            }
+            self.next();
+            self.skip_comments()?;
+
+            match self.ch {
+                Some('"') => s.push_str(&self.parse_string('"')?),
+                Some('\'') if self.options.allow_single_quotes => {
+                    s.push_str(&self.parse_string('\'')?)
+                }
+                _ => return Err(self.unexpected(&[TokenKind::String])),
+            }
+        }
+        #[cfg(feature = "config")]
+        if let Some(variables) = self.variables {
+            s = expr::interpolate(&s, variables)
+                .map_err(|message| self.error_from(start, ErrorKind::Custom(message)))?;
+        }
+        Ok(Value::String(s))
     }
 
-    fn parse_string(&mut self) -> Result<Value, Error> {
-        let mark = self.ch.unwrap(); // " or '
-        let mut s = String::with_capacity(64);
+    pub fn parse_string(&mut self, mark: char) -> Result<String, Error> {
+        // Most strings are short: start small rather than reserve for all.
+        let mut s = String::new();
 
         self.next();
 
         while let Some(c) = self.ch {
             match c {
-                '\u{000A}' | '\u{000D}' => return Err(Error::UnexpectedCharacter),
+                '\u{000A}' | '\u{000D}' => return Err(self.unexpected(&[TokenKind::Char(mark)])),
+                '\u{0000}'..='\u{001F}' => {
+                    if !self.options.allow_control_characters {
+                        return Err(self.unexpected(&[]));
+                    }
+                    let start = self.position;
+                    self.next();
+                    self.warn_from(start, WarningKind::ControlCharacter(c));
+                    s.push(c);
+                }
                 '\\' => match self.peek() {
                     None => break,
+                    Some(c) if !self.options.allow_json5_escapes && is_json5_escape(c) => {
+                        return Err(self.error_from(self.position, ErrorKind::InvalidEscape));
+                    }
+                    Some('\u{000A}' | '\u{000D}' | '\u{2028}' | '\u{2029}')
+                        if !self.options.allow_line_continuations =>
+                    {
+                        return Err(self.error_from(self.position, ErrorKind::InvalidEscape));
+                    }
                     Some(c) => match c {
                         'x' => {
                             s.push_str(&self.parse_hex_escape_sequence()?);
@@ -262,76 +923,70 @@ impl<'a> Parser<'a> {
                 _ => {
                     self.next();
                     if c == mark {
-                        return Ok(Value::String(s));
+                        return Ok(s);
                     } else {
                         s.push(c);
                     }
                 }
             }
         }
-        Err(Error::UnexpectedEndOfJson)
+        Err(self.unexpected(&[TokenKind::Char(mark)]))
     }
 
-    fn parse_two_hex_digits(&mut self) -> Result<u8, Error> {
-        let mut buf = String::with_capacity(2);
+    // Reads `count` hex digits of an escape sequence starting at `start`.
+    fn parse_hex_digits(&mut self, start: Position, count: usize) -> Result<u32, Error> {
+        let mut n = 0;
 
-        for _ in 0..2 {
-            match self.ch {
-                None => return Err(Error::UnexpectedEndOfJson),
-                Some(c) => buf.push(c),
+        for _ in 0..count {
+            match self.ch.and_then(|c| c.to_digit(16)) {
+                Some(d) => n = n * 16 + d,
+                None if self.ch.is_none() => return Err(self.unexpected(&[TokenKind::HexDigit])),
+                None => return Err(self.error_from(start, ErrorKind::InvalidEscape)),
             }
             self.next();
         }
-        match u8::from_str_radix(&buf, 16) {
-            Ok(n) => Ok(n),
-            Err(_) => Err(Error::UnexpectedCharacter),
-        }
-    }
-
-    fn parse_four_hex_digits(&mut self) -> Result<u16, Error> {
-        let mut buf = String::with_capacity(4);
-
-        for _ in 0..4 {
-            match self.ch {
-                None => return Err(Error::UnexpectedEndOfJson),
-                Some(c) => buf.push(c),
-            }
-            self.next();
-        }
-        match u16::from_str_radix(&buf, 16) {
-            Ok(n) => Ok(n),
-            Err(_) => Err(Error::UnexpectedCharacter),
-        }
+        Ok(n)
     }
 
     fn parse_hex_escape_sequence(&mut self) -> Result<String, Error> {
+        let start = self.position;
+
         self.next();
         self.next();
 
-        let vec = vec![self.parse_two_hex_digits()?];
+        let vec = vec![self.parse_hex_digits(start, 2)? as u8];
 
-        match String::from_utf8(vec) {
-            Err(_) => Err(Error::UnexpectedCharacter),
-            Ok(s) => Ok(s),
-        }
+        String::from_utf8(vec).map_err(|_| self.error_from(start, ErrorKind::InvalidEscape))
     }
 
     fn parse_unicode_escape_sequence(&mut self) -> Result<String, Error> {
+        let start = self.position;
+
         self.next();
         self.next();
 
-        let mut vec = vec![self.parse_four_hex_digits()?];
+        let mut vec = vec![self.parse_hex_digits(start, 4)? as u16];
 
         // surrogate pair
-        if 0xD800 <= vec[0] && vec[0] <= 0xDBFF {
-            self.consume_sequence("\\u")?;
-            vec.push(self.parse_four_hex_digits()?);
+        if 0xD800 <= vec[0] && vec[0] <= 0xDBFF && self.ch == Some('\\') && self.peek() == Some('u')
+        {
+            self.next();
+            self.next();
+            vec.push(self.parse_hex_digits(start, 4)? as u16);
         }
 
-        match String::from_utf16(&vec) {
-            Err(_) => Err(Error::UnexpectedCharacter),
-            Ok(s) => Ok(s),
+        let mut s = String::new();
+        for c in char::decode_utf16(vec) {
+            match c {
+                Ok(c) => s.push(c),
+                Err(_) if self.options.replace_lone_surrogates => {
+                    s.push(char::REPLACEMENT_CHARACTER);
+                    self.warn_from(start, WarningKind::LoneSurrogate);
+                }
+                Err(_) => return Err(self.error_from(start, ErrorKind::InvalidEscape)),
+            }
         }
+        Ok(s)
     }
 
     fn parse_character_escape_sequence(&mut self, c: char) -> char {
@@ -349,7 +1004,7 @@ impl<'a> Parser<'a> {
             't' => '\u{0009}',
             'v' => '\u{000B}',
             '0' => '\u{0000}',
-            _ => unreachable!(),
+            _ => c,
         }
     }
 
@@ -370,69 +1025,388 @@ impl<'a> Parser<'a> {
         self.next();
         self.skip_comments()?;
 
-        while let Some(c) = self.ch {
-            if c == ']' {
+        loop {
+            if let Some(']') = self.ch {
                 self.next();
                 return Ok(Value::Array(v));
             }
 
+            if self.ch.is_none() {
+                return Err(self.unexpected(&[TokenKind::Value, TokenKind::Char(']')]));
+            }
+            if self.tracks_path() {
+                self.path.push(v.len().to_string());
+            }
             v.push(self.parse_value()?);
-            self.skip_comments()?;
+            if self.tracks_path() {
+                self.path.pop();
+            }
+            if self.parse_separator(']', TokenKind::Value)? {
+                return Ok(Value::Array(v));
+            }
+        }
+    }
 
-            match self.ch {
-                None => break,
-                Some(']') => {
+    /// Reads what follows an element or member: a comma, or `close`, which
+    /// ends the array or object and makes this return true. `next` is what
+    /// is expected after a comma when trailing commas aren't allowed.
+    pub fn parse_separator(&mut self, close: char, next: TokenKind) -> Result<bool, Error> {
+        let line = self.position.line;
+        self.skip_comments()?;
+
+        match self.ch {
+            Some(c) if c == close => {
+                self.next();
+                Ok(true)
+            }
+            Some(',') => {
+                self.next();
+                self.skip_comments()?;
+                if self.ch == Some(close) && !self.options.allow_trailing_commas {
+                    return Err(self.unexpected(&[next]));
+                }
+                Ok(false)
+            }
+            Some(_) if self.options.allow_missing_commas && self.position.line > line => Ok(false),
+            _ => Err(self.unexpected(&[TokenKind::Char(','), TokenKind::Char(close)])),
+        }
+    }
+
+    pub fn parse_key(&mut self) -> Result<String, Error> {
+        let options = self.options;
+        let start = self.position;
+
+        let key = match self.ch {
+            Some('"') => return self.parse_string('"'),
+            Some('\'') if options.allow_single_quotes => return self.parse_string('\''),
+            Some(c)
+                if options.allow_quoteless_strings && !is_punctuator(c) && !c.is_whitespace() =>
+            {
+                self.parse_quoteless_key()
+            }
+            Some(c) if options.allow_unquoted_keys && (is_identifier_start(c) || c == '\\') => {
+                self.parse_identifier_key()?
+            }
+            _ => return Err(self.unexpected(&[TokenKind::Key])),
+        };
+        if !options.allow_reserved_word_keys && RESERVED_WORDS.contains(&key.as_str()) {
+            return Err(self.error_from(start, ErrorKind::ReservedWordKey(key)));
+        }
+        Ok(key)
+    }
+
+    // An identifier, in which characters may be written as `\uXXXX`
+    // escapes, as long as they are characters an identifier may have.
+    fn parse_identifier_key(&mut self) -> Result<String, Error> {
+        let mut s = String::new();
+
+        loop {
+            let c = match self.ch {
+                Some('\\') => {
+                    let start = self.position;
+                    self.next();
+                    if self.ch != Some('u') {
+                        return Err(self.unexpected(&[TokenKind::Char('u')]));
+                    }
                     self.next();
-                    return Ok(Value::Array(v));
+                    let c = char::from_u32(self.parse_hex_digits(start, 4)?);
+                    let valid = if s.is_empty() {
+                        is_identifier_start
+                    } else {
+                        is_identifier_part
+                    };
+                    match c {
+                        Some(c) if valid(c) => c,
+                        _ => return Err(self.error_from(start, ErrorKind::InvalidEscape)),
+                    }
                 }
-                Some(',') => {
+                Some(c) if is_identifier_part(c) => {
                     self.next();
-                    self.skip_comments()?;
+                    c
                 }
-                _ => return Err(Error::UnexpectedCharacter),
+                _ => return Ok(s),
+            };
+            s.push(c);
+        }
+    }
+
+    fn parse_quoteless_key(&mut self) -> String {
+        let mut s = String::new();
+
+        while let Some(c) = self.ch {
+            if is_punctuator(c) || c.is_whitespace() {
+                break;
             }
+            s.push(c);
+            self.next();
         }
-        Err(Error::UnexpectedEndOfJson)
+        s
+    }
+
+    #[cfg(feature = "config")]
+    fn parse_identifier(&mut self) -> String {
+        let mut s = String::new();
+
+        while let Some(c) = self.ch {
+            if !is_identifier_part(c) {
+                break;
+            }
+            s.push(c);
+            self.next();
+        }
+        s
+    }
+
+    // Whether values may be expressions over variables.
+    #[cfg(feature = "config")]
+    fn has_variables(&self) -> bool {
+        self.variables.is_some()
+    }
+
+    #[cfg(not(feature = "config"))]
+    fn has_variables(&self) -> bool {
+        false
+    }
+
+    // Whether `path` is kept up to date.
+    fn tracks_path(&self) -> bool {
+        self.spans.is_some() || self.duplicates.is_some() || self.key_spans.is_some()
     }
 
     fn parse_object(&mut self) -> Result<Value, Error> {
-        let mut m = HashMap::new();
+        let mut m = Map::new();
+        // The span of the member kept for each key, when reporting
+        // duplicates.
+        let mut kept = Map::new();
 
         self.next();
         self.skip_comments()?;
 
-        while let Some(c) = self.ch {
-            if c == '}' {
+        loop {
+            if let Some('}') = self.ch {
                 self.next();
                 return Ok(Value::Object(m));
             }
 
-            let key = match self.parse_value() {
-                Ok(Value::String(s)) => s,
-                Err(e) => return Err(e),
-                _ => return Err(Error::UnexpectedCharacter),
+            let start = self.position;
+            let key = match self.ch {
+                Some(_) => self.parse_key()?,
+                None => return Err(self.unexpected(&[TokenKind::Key, TokenKind::Char('}')])),
             };
+            if let Some(key_spans) = &mut self.key_spans {
+                let pointer: Pointer = self.path.iter().chain(Some(&key)).collect();
+                let span = Span {
+                    start,
+                    end: self.position,
+                };
+                key_spans.push((pointer, span));
+            }
+            let duplicate = m.contains_key(&key);
+
+            if duplicate {
+                if self.options.duplicate_keys == DuplicateKeys::Error {
+                    return Err(self.error_from(start, ErrorKind::DuplicateKey(key)));
+                }
+                self.warn_from(start, WarningKind::DuplicateKey(key.clone()));
+            }
 
             self.skip_comments()?;
             self.consume(':')?;
             self.skip_comments()?;
 
-            m.insert(key, self.parse_value()?);
-            self.skip_comments()?;
-
-            match self.ch {
-                None => break,
-                Some('}') => {
-                    self.next();
-                    return Ok(Value::Object(m));
-                }
-                Some(',') => {
-                    self.next();
-                    self.skip_comments()?;
+            if self.tracks_path() {
+                self.path.push(key.clone());
+            }
+            let value = self.parse_value()?;
+            if self.tracks_path() {
+                self.path.pop();
+            }
+            if let Some(duplicates) = &mut self.duplicates {
+                let span = Span {
+                    start,
+                    end: self.position,
+                };
+                match kept.get_mut(&key) {
+                    Some(kept) => {
+                        let last_wins = self.options.duplicate_keys == DuplicateKeys::LastWins;
+                        let (winner, dropped) = if last_wins {
+                            (span, *kept)
+                        } else {
+                            (*kept, span)
+                        };
+                        duplicates.push(Duplicate {
+                            pointer: self.path.iter().chain(Some(&key)).collect(),
+                            kept: winner,
+                            dropped,
+                        });
+                        *kept = winner;
+                    }
+                    None => {
+                        kept.insert(key.clone(), span);
+                    }
                 }
-                _ => return Err(Error::UnexpectedCharacter),
+            }
+            if !duplicate || self.options.duplicate_keys == DuplicateKeys::LastWins {
+                m.insert(key, value);
+            }
+            if self.parse_separator('}', TokenKind::Key)? {
+                return Ok(Value::Object(m));
             }
         }
-        Err(Error::UnexpectedEndOfJson)
     }
 }
+
+fn integer_error(kind: &IntErrorKind) -> ErrorKind {
+    match kind {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => ErrorKind::NumberOverflow,
+        _ => ErrorKind::InvalidNumber,
+    }
+}
+
+// The key written as `text`, quoted or not, with its escapes decoded. Text
+// that isn't a key is taken as it is.
+#[cfg(feature = "cst")]
+pub(crate) fn unquote_key(text: &str) -> String {
+    let mut parser = Parser::with_options(text, ParseOptions::default());
+    parser.next();
+    match parser.parse_key() {
+        Ok(key) if parser.ch.is_none() => key,
+        _ => text.into(),
+    }
+}
+
+pub(crate) fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '$' || c == '_'
+}
+
+pub(crate) fn is_identifier_part(c: char) -> bool {
+    is_identifier_start(c) || c.is_alphanumeric() || c == '\u{200C}' || c == '\u{200D}'
+}
+
+// `2024-05-01`, `2024-05-01T09:30:00`, `2024-05-01T09:30:00.5+02:00`, and
+// the like, as RFC 3339 has them, with or without seconds and offset.
+fn is_date_time(s: &str) -> bool {
+    let mut s = s.as_bytes();
+    skip_date_time(&mut s).is_some() && s.is_empty()
+}
+
+fn skip_date_time(s: &mut &[u8]) -> Option<()> {
+    let year = skip_digits(s, 4, 9999)?;
+    skip_byte(s, b'-')?;
+    let month = skip_digits(s, 2, 12)?;
+    skip_byte(s, b'-')?;
+    let day = skip_digits(s, 2, 31)?;
+    if month == 0 || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    if s.is_empty() {
+        return Some(());
+    }
+
+    skip_byte(s, b'T').or_else(|| skip_byte(s, b't'))?;
+    skip_digits(s, 2, 23)?;
+    skip_byte(s, b':')?;
+    skip_digits(s, 2, 59)?;
+    if skip_byte(s, b':').is_some() {
+        skip_digits(s, 2, 60)?;
+        if skip_byte(s, b'.').is_some() {
+            let digits = s.iter().take_while(|c| c.is_ascii_digit()).count();
+            if digits == 0 {
+                return None;
+            }
+            *s = &s[digits..];
+        }
+    }
+    match s.first() {
+        Some(b'Z' | b'z') => *s = &s[1..],
+        Some(b'+' | b'-') => {
+            *s = &s[1..];
+            skip_digits(s, 2, 23)?;
+            skip_byte(s, b':')?;
+            skip_digits(s, 2, 59)?;
+        }
+        _ => (),
+    }
+    Some(())
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Skips a number of `digits` digits, up to `max`.
+fn skip_digits(s: &mut &[u8], digits: usize, max: u32) -> Option<u32> {
+    let (head, tail) = s.split_at_checked(digits)?;
+    if !head.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    *s = tail;
+    let n = head.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0'));
+    (n <= max).then_some(n)
+}
+
+fn skip_byte(s: &mut &[u8], byte: u8) -> Option<()> {
+    let (&first, rest) = s.split_first()?;
+    (first == byte).then(|| *s = rest)
+}
+
+fn is_punctuator(c: char) -> bool {
+    matches!(c, ',' | ':' | '[' | ']' | '{' | '}')
+}
+
+// Whether `\c` is an escape JSON5 has but JSON doesn't. Line continuations
+// are told apart from these.
+fn is_json5_escape(c: char) -> bool {
+    !matches!(
+        c,
+        '"' | '\\'
+            | '/'
+            | 'b'
+            | 'f'
+            | 'n'
+            | 'r'
+            | 't'
+            | 'u'
+            | '\u{000A}'
+            | '\u{000D}'
+            | '\u{2028}'
+            | '\u{2029}'
+    )
+}
+
+// Drops a blank first line (after the opening quotes) and a blank last line
+// (before the closing ones), and strips up to `indent` columns of whitespace
+// from the start of every other line.
+fn dedent(raw: &str, indent: usize) -> String {
+    let mut lines: Vec<&str> = raw
+        .split('\n')
+        .flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'))
+        .collect();
+    let is_blank = |line: &str| line.trim().is_empty();
+
+    if lines.len() > 1 && is_blank(lines[lines.len() - 1]) {
+        lines.pop();
+    }
+    let first = if lines.len() > 1 && is_blank(lines[0]) {
+        None
+    } else {
+        Some(lines[0].trim_start())
+    };
+
+    let rest = lines.iter().skip(1).map(|line| {
+        let strip = line
+            .char_indices()
+            .take(indent)
+            .take_while(|(_, c)| *c == ' ' || *c == '\t')
+            .last()
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        &line[strip..]
+    });
+    first.into_iter().chain(rest).collect::<Vec<_>>().join("\n")
+}
@@ -0,0 +1,105 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FromIterator;
+use core::str::FromStr;
+
+use crate::error::{Error, ErrorKind};
+
+/// A JSON Pointer (RFC 6901) such as `/servers/0/host`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pointer {
+    tokens: Vec<String>,
+}
+
+impl Pointer {
+    /// The pointer to the whole document, written as the empty string.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// This pointer, followed by the object key `key`.
+    pub fn key(mut self, key: &str) -> Self {
+        self.tokens.push(key.into());
+        self
+    }
+
+    /// This pointer, followed by the array index `index`.
+    pub fn index(mut self, index: usize) -> Self {
+        self.tokens.push(index.to_string());
+        self
+    }
+
+    /// The pointer to the array or object containing the value, unless this
+    /// is the root.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, tokens) = self.tokens.split_last()?;
+        Some(Pointer {
+            tokens: tokens.to_vec(),
+        })
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Returns `None` unless `s` is empty or starts with `/`.
+    pub fn parse(s: &str) -> Option<Self> {
+        if s.is_empty() {
+            return Some(Self::default());
+        }
+
+        let tokens = s.strip_prefix('/')?.split('/');
+        Some(
+            tokens
+                .map(|t| t.replace("~1", "/").replace("~0", "~"))
+                .collect(),
+        )
+    }
+
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+}
+
+impl<S> FromIterator<S> for Pointer
+where
+    S: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Pointer {
+            tokens: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl FromStr for Pointer {
+    type Err = Error;
+
+    /// As `Pointer::parse`, but with an error to report.
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Self::parse(s).ok_or_else(|| {
+            let message = format!("invalid JSON Pointer `{}`, expected `/` first", s);
+            Error::new(ErrorKind::Custom(message))
+        })
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+// Array indices are decimal without leading zeros, as RFC 6901 requires.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    match token.as_bytes() {
+        [b'0'] => Some(0),
+        [b'1'..=b'9', rest @ ..] if rest.iter().all(u8::is_ascii_digit) => token.parse().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,123 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::value::{Value, REDACTED};
+
+/// What `Value::display_redacted` leaves out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedactionRules {
+    /// The values of keys containing any of these, ignoring case, are
+    /// masked: by default `password`, `secret` and `token`.
+    pub secret_keys: Vec<String>,
+    /// Strings are cut after this many characters, 80 by default.
+    pub max_string_len: usize,
+    /// Arrays and objects are cut after this many elements, 20 by default.
+    pub max_elements: usize,
+}
+
+impl Default for RedactionRules {
+    fn default() -> Self {
+        RedactionRules {
+            secret_keys: ["password", "secret", "token"].map(String::from).to_vec(),
+            max_string_len: 80,
+            max_elements: 20,
+        }
+    }
+}
+
+impl RedactionRules {
+    fn is_secret(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        self.secret_keys
+            .iter()
+            .any(|secret| key.contains(&secret.to_lowercase()))
+    }
+}
+
+/// A value written on one line, without secrets and cut short where long,
+/// from `Value::display_redacted`. `Debug` writes the same.
+pub struct Redacted<'a> {
+    value: &'a Value,
+    rules: &'a RedactionRules,
+}
+
+impl Value {
+    /// Formats the value for logs, with the values of secret keys masked
+    /// and long strings, arrays and objects cut short, as `rules` say.
+    pub fn display_redacted<'a>(&'a self, rules: &'a RedactionRules) -> Redacted<'a> {
+        Redacted { value: self, rules }
+    }
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_value(f, self.value, self.rules)
+    }
+}
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+fn write_value(f: &mut fmt::Formatter, value: &Value, rules: &RedactionRules) -> fmt::Result {
+    match value {
+        Value::String(s) => write_string(f, s, rules),
+        Value::Array(v) => {
+            f.write_str("[")?;
+            for (i, value) in v.iter().take(rules.max_elements).enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write_value(f, value, rules)?;
+            }
+            write_rest(f, v.len(), rules)?;
+            f.write_str("]")
+        }
+        Value::Object(m) => {
+            f.write_str("{")?;
+            for (i, (key, value)) in m.iter().take(rules.max_elements).enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write_quoted(f, key)?;
+                f.write_str(": ")?;
+                if rules.is_secret(key) {
+                    write_quoted(f, REDACTED)?;
+                } else {
+                    write_value(f, value, rules)?;
+                }
+            }
+            write_rest(f, m.len(), rules)?;
+            f.write_str("}")
+        }
+        value => f.write_str(&crate::stringify(value)),
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str, rules: &RedactionRules) -> fmt::Result {
+    match s.char_indices().nth(rules.max_string_len) {
+        Some((end, _)) => {
+            write_quoted(f, &s[..end])?;
+            write!(f, "... ({} more characters)", s[end..].chars().count())
+        }
+        None => write_quoted(f, s),
+    }
+}
+
+fn write_quoted(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str(&crate::stringify(&Value::String(s.into())))
+}
+
+// Says how many of `len` elements were left out.
+fn write_rest(f: &mut fmt::Formatter, len: usize, rules: &RedactionRules) -> fmt::Result {
+    match len.checked_sub(rules.max_elements) {
+        Some(rest) if rest > 0 => {
+            let separator = if rules.max_elements > 0 { ", " } else { "" };
+            write!(f, "{}... {} more", separator, rest)
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,88 @@
+//! Smaller documents standing for large ones, e.g. for test fixtures or to
+//! attach to error reports.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::hash::Hasher;
+
+use crate::fnv::Fnv;
+use crate::value::Value;
+
+impl Value {
+    /// A copy in which every array, however deep, keeps at most `n` of its
+    /// elements, picked at random but in their order. The same `seed` picks
+    /// the same elements, from one run to the next.
+    pub fn sample(&self, n: usize, seed: u64) -> Value {
+        // Objects give each member a seed of its own, from the key, so that
+        // the order of their members doesn't change what is picked.
+        match self {
+            Value::Array(v) => {
+                let mut random = SplitMix64(seed);
+                // The first `count` of a partial shuffle of the indices.
+                let mut indices: Vec<usize> = (0..v.len()).collect();
+                let count = n.min(v.len());
+                for i in 0..count {
+                    let j = i + (random.next() % (v.len() - i) as u64) as usize;
+                    indices.swap(i, j);
+                }
+                indices.truncate(count);
+                indices.sort_unstable();
+                Value::Array(
+                    indices
+                        .iter()
+                        .map(|&i| v[i].sample(n, random.next()))
+                        .collect(),
+                )
+            }
+            Value::Object(m) => Value::Object(
+                m.iter()
+                    .map(|(key, value)| {
+                        let mut h = Fnv::new();
+                        h.write_u64(seed);
+                        h.write(key.as_bytes());
+                        (key.clone(), value.sample(n, h.finish()))
+                    })
+                    .collect(),
+            ),
+            value => value.clone(),
+        }
+    }
+
+    /// A copy in which every array keeps its first `max_elements` elements,
+    /// every object its first `max_elements` members, and every string its
+    /// first `max_string_len` characters.
+    pub fn truncate(&self, max_elements: usize, max_string_len: usize) -> Value {
+        match self {
+            Value::String(s) => Value::String(match s.char_indices().nth(max_string_len) {
+                Some((end, _)) => String::from(&s[..end]),
+                None => s.clone(),
+            }),
+            Value::Array(v) => Value::Array(
+                v.iter()
+                    .take(max_elements)
+                    .map(|value| value.truncate(max_elements, max_string_len))
+                    .collect(),
+            ),
+            Value::Object(m) => Value::Object(
+                m.iter()
+                    .take(max_elements)
+                    .map(|(key, value)| (key.clone(), value.truncate(max_elements, max_string_len)))
+                    .collect(),
+            ),
+            value => value.clone(),
+        }
+    }
+}
+
+// A small, fast generator, plenty for picking samples.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
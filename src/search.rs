@@ -0,0 +1,81 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::map::Map;
+use crate::value::Value;
+
+impl Value {
+    /// Searches this array, sorted by the values at `path` in its elements
+    /// as `compare` orders them, for an element whose value there is `key`,
+    /// as `slice::binary_search_by_key` does. `compare` is given the value
+    /// in an element first. `Err(0)` unless this is an array.
+    pub fn binary_search_by_key<S, F>(
+        &self,
+        path: &[S],
+        key: &Value,
+        mut compare: F,
+    ) -> Result<usize, usize>
+    where
+        S: AsRef<str>,
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        match self {
+            Self::Array(v) => v.binary_search_by(|element| compare(element.at(path), key)),
+            _ => Err(0),
+        }
+    }
+
+    /// Indexes the elements of this array by the values at `path` in them,
+    /// for lookups that don't go through the whole array. Empty unless this
+    /// is an array.
+    pub fn index_by<S>(&self, path: &[S]) -> KeyIndex<'_>
+    where
+        S: AsRef<str>,
+    {
+        let values = self.to_vec().map_or(&[][..], |v| v);
+        let keys: Vec<&Value> = values.iter().map(|value| value.at(path)).collect();
+        let mut positions: Map<u64, Vec<usize>> = Map::new();
+        for (i, key) in keys.iter().enumerate() {
+            positions.entry(key.structural_hash()).or_default().push(i);
+        }
+
+        KeyIndex {
+            values,
+            keys,
+            positions,
+        }
+    }
+}
+
+/// The elements of an array by the values at a path in them, from
+/// `Value::index_by`. Elements that lack the path are found by `Null`.
+pub struct KeyIndex<'a> {
+    values: &'a [Value],
+    keys: Vec<&'a Value>,
+    // Hashes narrow the search; equality rules out collisions.
+    positions: Map<u64, Vec<usize>>,
+}
+
+impl<'a> KeyIndex<'a> {
+    /// The index of the first element with `key`.
+    pub fn position(&self, key: &Value) -> Option<usize> {
+        self.positions
+            .get(&key.structural_hash())?
+            .iter()
+            .copied()
+            .find(|&i| self.keys[i] == key)
+    }
+
+    /// The first element with `key`.
+    pub fn get(&self, key: &Value) -> Option<&'a Value> {
+        self.position(key).map(|i| &self.values[i])
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
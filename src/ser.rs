@@ -0,0 +1,272 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::error::{Error, ErrorKind};
+#[cfg(feature = "hints")]
+use crate::hints::ScalarHint;
+#[cfg(feature = "hints")]
+use crate::map::Map;
+use crate::options::{NonFinite, StringifyOptions, TrailingCommas};
+#[cfg(feature = "hints")]
+use crate::pointer::Pointer;
+use crate::value::Value;
+
+pub struct Serializer {
+    pub out: String,
+    pub options: StringifyOptions,
+    /// Styles for scalars, by JSON Pointer.
+    #[cfg(feature = "hints")]
+    pub hints: Map<Pointer, ScalarHint>,
+    // The path to the value being written, tracked only with hints, or
+    // when non-finite floats are errors.
+    pub path: Vec<String>,
+    /// The first non-finite float found, with `NonFinite::Error`.
+    pub error: Option<Error>,
+}
+
+impl Serializer {
+    pub fn new(options: StringifyOptions) -> Self {
+        Serializer {
+            out: String::new(),
+            options,
+            #[cfg(feature = "hints")]
+            hints: Map::new(),
+            path: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn write_value(&mut self, value: &Value) {
+        #[cfg(feature = "hints")]
+        if self.write_hinted(value) {
+            return;
+        }
+
+        match value {
+            Value::Null => self.out.push_str("null"),
+            Value::Boolean(b) => self.out.push_str(if *b { "true" } else { "false" }),
+            Value::Integer(i) => {
+                let _ = write!(self.out, "{}", i);
+            }
+            Value::Float(f) => self.write_float(*f),
+            Value::String(s) => match self.options.wrap_strings_at {
+                Some(width) if s.chars().count() > width => self.write_wrapped_string(s, width),
+                _ => self.write_string(s),
+            },
+            Value::Array(v) => {
+                let start = self.out.len();
+                let per_line = match self.options.numbers_per_line {
+                    Some(n) if v.len() > n && v.iter().all(is_number) => n.max(1),
+                    _ => usize::MAX,
+                };
+                self.out.push('[');
+                for (i, value) in v.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push(',');
+                    }
+                    if i > 0 && i % per_line == 0 {
+                        self.out.push_str(self.options.line_ending.as_str());
+                    }
+                    self.enter(i);
+                    self.write_value(value);
+                    self.leave();
+                }
+                self.write_trailing_comma(start, v.is_empty());
+                self.out.push(']');
+            }
+            Value::Object(m) if self.options.sort_keys => {
+                let mut entries: Vec<_> = m.iter().collect();
+                entries.sort_unstable_by_key(|(key, _)| *key);
+                self.write_object(entries)
+            }
+            Value::Object(m) => self.write_object(m),
+            Value::Custom(c) => self.write_value(&c.to_value()),
+        }
+    }
+
+    fn write_object<'v, I>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = (&'v String, &'v Value)>,
+    {
+        let start = self.out.len();
+        let mut empty = true;
+        self.out.push('{');
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                self.out.push(',');
+            }
+            self.write_string(key);
+            self.out.push(':');
+            self.enter(key);
+            self.write_value(value);
+            self.leave();
+            empty = false;
+        }
+        self.write_trailing_comma(start, empty);
+        self.out.push('}');
+    }
+
+    // Before the closing bracket of the array or object written from
+    // `start` on.
+    fn write_trailing_comma(&mut self, start: usize, empty: bool) {
+        let comma = match self.options.trailing_commas {
+            _ if empty => false,
+            TrailingCommas::Always => true,
+            TrailingCommas::Never => false,
+            TrailingCommas::Multiline => self.out[start..].contains(['\n', '\r']),
+        };
+        if comma {
+            self.out.push(',');
+        }
+    }
+
+    // Writes `value` as the hint at its path says, if it has one that
+    // applies, and returns whether it did.
+    #[cfg(feature = "hints")]
+    fn write_hinted(&mut self, value: &Value) -> bool {
+        if self.hints.is_empty() {
+            return false;
+        }
+        let pointer: Pointer = self.path.iter().collect();
+        let hint = match self.hints.get(&pointer) {
+            Some(hint) => *hint,
+            None => return false,
+        };
+
+        match (value, hint) {
+            (Value::Integer(i), ScalarHint::Hex { uppercase }) => {
+                let sign = if *i < 0 { "-" } else { "" };
+                let _ = if uppercase {
+                    write!(self.out, "{}0x{:X}", sign, i.unsigned_abs())
+                } else {
+                    write!(self.out, "{}0x{:x}", sign, i.unsigned_abs())
+                };
+            }
+            (Value::Float(f), ScalarHint::Exponent) if f.is_finite() => {
+                let _ = write!(self.out, "{:e}", self.round(*f));
+            }
+            (Value::Float(f), ScalarHint::Fixed { decimals }) if f.is_finite() => {
+                let _ = write!(self.out, "{:.*}", usize::from(decimals), f);
+            }
+            // Strings to be wrapped are wrapped in double quotes instead.
+            (Value::String(s), ScalarHint::SingleQuoted) if !matches!(self.options.wrap_strings_at, Some(width) if s.chars().count() > width) => {
+                self.write_quoted(s, '\'')
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    #[cfg(feature = "hints")]
+    fn has_hints(&self) -> bool {
+        !self.hints.is_empty()
+    }
+
+    #[cfg(not(feature = "hints"))]
+    fn has_hints(&self) -> bool {
+        false
+    }
+
+    fn enter<T: ToString>(&mut self, token: T) {
+        if self.has_hints() || self.options.non_finite == NonFinite::Error {
+            self.path.push(token.to_string());
+        }
+    }
+
+    fn leave(&mut self) {
+        self.path.pop();
+    }
+
+    fn write_float(&mut self, f: f64) {
+        if f.is_finite() {
+            let f = self.round(f);
+            // Debug keeps a fractional part or an exponent ("1.0", "1e100"),
+            // so the output parses back as a float rather than an integer.
+            let _ = write!(self.out, "{:?}", f);
+            return;
+        }
+        let literal = match f {
+            _ if f.is_nan() => "NaN",
+            _ if f > 0.0 => "Infinity",
+            _ => "-Infinity",
+        };
+        match self.options.non_finite {
+            NonFinite::Null => self.out.push_str("null"),
+            NonFinite::String => self.write_string(literal),
+            NonFinite::Clamp if f.is_nan() => self.out.push_str("null"),
+            NonFinite::Clamp => self.write_float(f.clamp(f64::MIN, f64::MAX)),
+            NonFinite::Error if self.error.is_none() => {
+                let message = format!("{} can't be written as JSON", literal);
+                let pointer = self.path.iter().collect();
+                self.error = Some(Error::new(ErrorKind::Custom(message)).with_pointer(pointer));
+                self.out.push_str(literal);
+            }
+            NonFinite::Literal | NonFinite::Error => self.out.push_str(literal),
+        }
+    }
+
+    // `f` to the significant digits of the options.
+    fn round(&self, f: f64) -> f64 {
+        match self.options.significant_digits {
+            Some(digits) => {
+                let precision = usize::from(digits.max(1)) - 1;
+                format!("{:.*e}", precision, f).parse().unwrap_or(f)
+            }
+            None => f,
+        }
+    }
+
+    fn write_wrapped_string(&mut self, mut s: &str, width: usize) {
+        let width = width.max(1);
+
+        loop {
+            let end = match s.char_indices().nth(width) {
+                Some((hard, _)) => s[..hard]
+                    .rfind(char::is_whitespace)
+                    .map(|i| i + s[i..].chars().next().map_or(1, char::len_utf8))
+                    .unwrap_or(hard),
+                None => s.len(),
+            };
+            self.write_string(&s[..end]);
+            s = &s[end..];
+            if s.is_empty() {
+                return;
+            }
+            self.out.push_str(" +");
+            self.out.push_str(self.options.line_ending.as_str());
+        }
+    }
+
+    fn write_string(&mut self, s: &str) {
+        self.write_quoted(s, '"');
+    }
+
+    fn write_quoted(&mut self, s: &str, quote: char) {
+        self.out.push(quote);
+        for c in s.chars() {
+            match c {
+                '"' | '\'' if c == quote => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                '\\' => self.out.push_str("\\\\"),
+                '\u{0008}' => self.out.push_str("\\b"),
+                '\u{000C}' => self.out.push_str("\\f"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                '\u{0000}'..='\u{001F}' | '\u{2028}' | '\u{2029}' => {
+                    let _ = write!(self.out, "\\u{:04X}", c as u32);
+                }
+                _ => self.out.push(c),
+            }
+        }
+        self.out.push(quote);
+    }
+}
+
+fn is_number(value: &Value) -> bool {
+    matches!(value, Value::Integer(_) | Value::Float(_))
+}
@@ -0,0 +1,36 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl Position {
+    // Moves past `c`, followed by `next`. Lines end with "\n", "\r\n" or
+    // "\r".
+    pub(crate) fn advance(&mut self, c: char, next: Option<char>) {
+        self.offset += c.len_utf8();
+        if c == '\n' || (c == '\r' && next != Some('\n')) {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+}
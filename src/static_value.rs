@@ -0,0 +1,67 @@
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use crate::map::Map;
+use crate::value::Value;
+
+/// A value that only borrows `'static` data, so that it can be built in a
+/// constant or static, as `json5_static!` does, with nothing to do at run
+/// time.
+///
+/// The members of objects from `json5_static!` are sorted by key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StaticValue {
+    Null,
+    Boolean(bool),
+    Integer(i32),
+    Float(f64),
+    String(&'static str),
+    Array(&'static [StaticValue]),
+    Object(&'static [(&'static str, StaticValue)]),
+}
+
+impl StaticValue {
+    /// The value of `key`, if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&'static StaticValue> {
+        match self {
+            Self::Object(members) => members.iter().find(|(k, _)| *k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// An owned copy, to work with as any other value.
+    pub fn to_value(&self) -> Value {
+        match *self {
+            Self::Null => Value::Null,
+            Self::Boolean(b) => Value::Boolean(b),
+            Self::Integer(i) => Value::Integer(i),
+            Self::Float(f) => Value::Float(f),
+            Self::String(s) => Value::String(s.into()),
+            Self::Array(v) => Value::Array(v.iter().map(Self::to_value).collect::<Vec<_>>()),
+            Self::Object(m) => Value::Object(
+                m.iter()
+                    .map(|(k, v)| ((*k).into(), v.to_value()))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+impl Index<usize> for StaticValue {
+    type Output = StaticValue;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        match self {
+            Self::Array(v) => v.get(i).unwrap_or(&Self::Null),
+            _ => &Self::Null,
+        }
+    }
+}
+
+impl Index<&str> for StaticValue {
+    type Output = StaticValue;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key).unwrap_or(&Self::Null)
+    }
+}
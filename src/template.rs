@@ -0,0 +1,98 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::{Error, ErrorKind};
+use crate::expr;
+use crate::pointer::{self, Pointer};
+use crate::value::Value;
+
+/// Parses `template` and replaces the `{{name}}` placeholders in its string
+/// values with the values of `bindings`, an object in which `name` may be a
+/// path like `db.hosts.0`.
+///
+/// A string that is nothing but a placeholder becomes the value as it is,
+/// so `port: '{{port}}'` stays a number. Placeholders within longer strings
+/// are replaced by the text of scalars. A placeholder without a binding is
+/// an error, located at the string.
+pub fn render(template: &str, bindings: &Value) -> Result<Value, Error> {
+    let (mut value, spans) = crate::parse_with_spans(template)?;
+
+    render_value(&mut value, bindings, &mut Vec::new()).map_err(|(message, path)| {
+        let pointer: Pointer = path.iter().collect();
+        let error = Error::new(ErrorKind::Custom(message));
+        match spans.iter().find(|(p, _)| *p == pointer) {
+            Some((_, span)) => error.with_span(*span),
+            None => error,
+        }
+    })?;
+    Ok(value)
+}
+
+// Errors come with the path to the string they are in.
+fn render_value(
+    value: &mut Value,
+    bindings: &Value,
+    path: &mut Vec<String>,
+) -> Result<(), (String, Vec<String>)> {
+    match value {
+        Value::String(s) => match render_string(s, bindings) {
+            Ok(Some(rendered)) => *value = rendered,
+            Ok(None) => (),
+            Err(message) => return Err((message, path.clone())),
+        },
+        Value::Array(v) => {
+            for (i, value) in v.iter_mut().enumerate() {
+                path.push(i.to_string());
+                render_value(value, bindings, path)?;
+                path.pop();
+            }
+        }
+        Value::Object(m) => {
+            for (key, value) in m.iter_mut() {
+                path.push(key.clone());
+                render_value(value, bindings, path)?;
+                path.pop();
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+// The string with its placeholders replaced, or `None` if it has none.
+fn render_string(s: &str, bindings: &Value) -> Result<Option<Value>, String> {
+    if !s.contains("{{") {
+        return Ok(None);
+    }
+    if let Some(name) = s.strip_prefix("{{").and_then(|s| s.strip_suffix("}}")) {
+        if !name.contains("{{") && !name.contains("}}") {
+            return binding(bindings, name).cloned().map(Some);
+        }
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find("{{") {
+        out.push_str(&rest[..i]);
+        let end = match rest[i..].find("}}") {
+            Some(end) => i + end,
+            None => return Err("unterminated placeholder".into()),
+        };
+        out.push_str(&expr::text(binding(bindings, &rest[i + 2..end])?)?);
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(Some(Value::String(out)))
+}
+
+fn binding<'a>(bindings: &'a Value, name: &str) -> Result<&'a Value, String> {
+    name.trim()
+        .split('.')
+        .try_fold(bindings, |value, token| match value {
+            Value::Object(m) => m.get(token),
+            Value::Array(v) => v.get(pointer::parse_index(token)?),
+            _ => None,
+        })
+        .ok_or_else(|| format!("unknown placeholder `{}`", name.trim()))
+}
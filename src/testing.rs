@@ -0,0 +1,192 @@
+//! Generators of random `Value`s for property-based tests, and snapshot
+//! assertions against JSON5 files.
+//!
+//! Generated floats are never NaN, so every generated value compares equal
+//! to itself. Nesting stays well below the parser's depth limit.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::{env, fs};
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+
+use crate::map::Map;
+use crate::options::StringifyOptions;
+use crate::value::Value;
+
+const MAX_DEPTH: usize = 8;
+
+impl<'a> Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_value(u, MAX_DEPTH)
+    }
+}
+
+fn arbitrary_value(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Value> {
+    let variants = if depth == 0 { 5 } else { 7 };
+
+    Ok(match u.choose_index(variants)? {
+        0 => Value::Null,
+        1 => Value::Boolean(u.arbitrary()?),
+        2 => Value::Integer(u.arbitrary()?),
+        3 => {
+            let f: f64 = u.arbitrary()?;
+            Value::Float(if f.is_nan() { 0.0 } else { f })
+        }
+        4 => Value::String(u.arbitrary()?),
+        5 => {
+            let mut v = Vec::new();
+            for _ in 0..u.arbitrary_len::<Value>()? {
+                v.push(arbitrary_value(u, depth - 1)?);
+            }
+            Value::Array(v)
+        }
+        _ => {
+            let mut m = Map::new();
+            for _ in 0..u.arbitrary_len::<(String, Value)>()? {
+                m.insert(u.arbitrary()?, arbitrary_value(u, depth - 1)?);
+            }
+            Value::Object(m)
+        }
+    })
+}
+
+/// A strategy producing values nested at most `depth` levels, with roughly
+/// `desired_size` nodes in total and at most `collection_size` elements in
+/// each array or object.
+pub fn value_strategy(
+    depth: u32,
+    desired_size: u32,
+    collection_size: usize,
+) -> BoxedStrategy<Value> {
+    use proptest::num::f64::{INFINITE, NEGATIVE, NORMAL, POSITIVE, SUBNORMAL, ZERO};
+
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Boolean),
+        any::<i32>().prop_map(Value::Integer),
+        (POSITIVE | NEGATIVE | NORMAL | SUBNORMAL | ZERO | INFINITE).prop_map(Value::Float),
+        any::<String>().prop_map(Value::String),
+    ];
+
+    leaf.prop_recursive(depth, desired_size, collection_size as u32, move |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..=collection_size).prop_map(Value::Array),
+            prop::collection::vec((any::<String>(), inner), 0..=collection_size)
+                .prop_map(|entries| Value::Object(entries.into_iter().collect())),
+        ]
+    })
+    .boxed()
+}
+
+impl proptest::arbitrary::Arbitrary for Value {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Value>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        value_strategy(4, 64, 8)
+    }
+}
+
+/// Asserts that `value` equals the snapshot in `path`, relative to the
+/// directory of the crate's manifest. See `assert_snapshot`.
+#[macro_export]
+macro_rules! assert_json5_snapshot {
+    ($value:expr, $path:expr $(,)?) => {
+        $crate::testing::assert_snapshot(
+            &$value,
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+/// Asserts that `value` equals the snapshot in the file at `path`, listing
+/// the pointers at which they differ if not.
+///
+/// A missing snapshot is written rather than compared, as are all snapshots
+/// when the `JSON5_UPDATE_SNAPSHOTS` environment variable is set. They are
+/// written reformatted, one entry per line, with object keys sorted so that
+/// they don't change from one run to the next.
+#[track_caller]
+pub fn assert_snapshot<P>(value: &Value, path: P)
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if env::var_os("JSON5_UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        let options = StringifyOptions {
+            sort_keys: true,
+            ..StringifyOptions::default()
+        };
+        let json = crate::reformat(&crate::stringify_with_options(value, &options))
+            .expect("stringified values parse");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).expect("can't create the snapshot directory");
+        }
+        fs::write(path, json)
+            .unwrap_or_else(|e| panic!("can't write snapshot {}: {}", path.display(), e));
+        return;
+    }
+
+    let snapshot = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("can't read snapshot {}: {}", path.display(), e));
+    let snapshot = crate::parse_named(&snapshot, &path.display().to_string())
+        .unwrap_or_else(|e| panic!("invalid snapshot: {}", e));
+    let mut differences = String::new();
+    differ(
+        Some(&snapshot),
+        Some(value),
+        &mut Vec::new(),
+        &mut differences,
+    );
+    if !differences.is_empty() {
+        panic!(
+            "value doesn't match snapshot {}:\n{}(set JSON5_UPDATE_SNAPSHOTS to update it)",
+            path.display(),
+            differences
+        );
+    }
+}
+
+// Lists the differences between the snapshot and the value, one per line.
+fn differ(
+    expected: Option<&Value>,
+    actual: Option<&Value>,
+    path: &mut Vec<String>,
+    out: &mut String,
+) {
+    let pointer = path.iter().collect::<crate::Pointer>();
+    match (expected, actual) {
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for i in 0..a.len().max(b.len()) {
+                path.push(i.to_string());
+                differ(a.get(i), b.get(i), path, out);
+                path.pop();
+            }
+        }
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            for key in keys {
+                path.push(key.clone());
+                differ(a.get(key), b.get(key), path, out);
+                path.pop();
+            }
+        }
+        (Some(_), None) => writeln!(out, "  {}: missing", pointer).unwrap(),
+        (None, Some(b)) => {
+            writeln!(out, "  {}: unexpected {}", pointer, crate::stringify(b)).unwrap()
+        }
+        (Some(a), Some(b)) if a != b => writeln!(
+            out,
+            "  {}: expected {}, found {}",
+            pointer,
+            crate::stringify(a),
+            crate::stringify(b)
+        )
+        .unwrap(),
+        _ => (),
+    }
+}
@@ -0,0 +1,120 @@
+//! Conversion to and from TOML.
+//!
+//! TOML has no null: null object members are left out of tables, and nulls
+//! anywhere else (array elements, or the document itself) are errors. Arrays
+//! mixing types are allowed by TOML 1.0 and converted as they are. Going the
+//! other way, datetimes become RFC 3339 strings and integers outside the
+//! range of `i32` become floats.
+
+use std::convert::TryFrom;
+
+use crate::error::{Error, ErrorKind};
+use crate::span::{Position, Span};
+use crate::value::Value;
+
+impl From<::toml::Value> for Value {
+    fn from(value: ::toml::Value) -> Self {
+        match value {
+            ::toml::Value::String(s) => Self::String(s),
+            ::toml::Value::Integer(i) => match i32::try_from(i) {
+                Ok(i) => Self::Integer(i),
+                Err(_) => Self::Float(i as f64),
+            },
+            ::toml::Value::Float(f) => Self::Float(f),
+            ::toml::Value::Boolean(b) => Self::Boolean(b),
+            ::toml::Value::Datetime(datetime) => Self::String(datetime.to_string()),
+            ::toml::Value::Array(v) => Self::Array(v.into_iter().map(Self::from).collect()),
+            ::toml::Value::Table(t) => Self::from(t),
+        }
+    }
+}
+
+impl From<::toml::Table> for Value {
+    fn from(table: ::toml::Table) -> Self {
+        Self::Object(table.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+    }
+}
+
+impl TryFrom<&Value> for ::toml::Value {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        to_toml(value, &mut Vec::new())
+    }
+}
+
+impl Value {
+    /// Formats the value, which must be an object, as a TOML document.
+    pub fn to_toml_string(&self) -> Result<String, Error> {
+        match to_toml(self, &mut Vec::new())? {
+            ::toml::Value::Table(table) => Ok(table.to_string()),
+            _ => Err(Error::new(ErrorKind::Custom(
+                "a TOML document must be a table".into(),
+            ))),
+        }
+    }
+
+    pub fn from_toml_str(toml: &str) -> Result<Value, Error> {
+        match toml.parse::<::toml::Table>() {
+            Ok(table) => Ok(Self::from(table)),
+            Err(e) => {
+                let error = Error::new(ErrorKind::Custom(e.message().into()));
+                Err(match e.span() {
+                    Some(range) => error.with_span(Span {
+                        start: position_at(toml, range.start),
+                        end: position_at(toml, range.end),
+                    }),
+                    None => error,
+                })
+            }
+        }
+    }
+}
+
+fn to_toml(value: &Value, path: &mut Vec<String>) -> Result<::toml::Value, Error> {
+    let toml = match value {
+        Value::Null => {
+            return Err(Error::new(ErrorKind::Custom(
+                "null can't be represented in TOML".into(),
+            ))
+            .with_pointer(path.iter().collect()));
+        }
+        Value::Boolean(b) => ::toml::Value::Boolean(*b),
+        Value::Integer(i) => ::toml::Value::Integer((*i).into()),
+        Value::Float(f) => ::toml::Value::Float(*f),
+        Value::String(s) => ::toml::Value::String(s.clone()),
+        Value::Array(v) => {
+            let mut array = ::toml::value::Array::with_capacity(v.len());
+            for (i, item) in v.iter().enumerate() {
+                path.push(i.to_string());
+                array.push(to_toml(item, path)?);
+                path.pop();
+            }
+            ::toml::Value::Array(array)
+        }
+        Value::Object(m) => {
+            let mut table = ::toml::Table::new();
+            for (key, item) in m {
+                if item.is_null() {
+                    continue;
+                }
+                path.push(key.clone());
+                table.insert(key.clone(), to_toml(item, path)?);
+                path.pop();
+            }
+            ::toml::Value::Table(table)
+        }
+        Value::Custom(c) => return to_toml(&c.to_value(), path),
+    };
+    Ok(toml)
+}
+
+fn position_at(s: &str, offset: usize) -> Position {
+    let mut position = Position::default();
+
+    let mut chars = s[..offset.min(s.len())].chars().peekable();
+    while let Some(c) = chars.next() {
+        position.advance(c, chars.peek().copied());
+    }
+    position
+}
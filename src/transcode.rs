@@ -0,0 +1,187 @@
+use alloc::string::{String, ToString};
+use core::cell::RefCell;
+use core::fmt;
+
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq};
+
+use crate::de::Deserializer;
+
+/// Parses `json` and feeds it straight into `serializer`, without building a
+/// `Value`. Parse errors are passed to the serializer's `Error::custom`.
+pub fn transcode<S>(json: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    let mut deserializer = Deserializer::from_str(json);
+    let failure = RefCell::new(None);
+    let result = Transcoder::new(&mut deserializer, &failure).serialize(serializer);
+
+    // An error raised deep inside the document reaches here re-wrapped by
+    // every enclosing level, so report the message it had when first raised.
+    if let Some(message) = failure.into_inner() {
+        return Err(ser::Error::custom(message));
+    }
+    let ok = result?;
+    deserializer.end().map_err(ser::Error::custom)?;
+    Ok(ok)
+}
+
+// Serializes whatever the deserializer produces. `Serialize` takes `&self`,
+// while deserializing consumes the deserializer, hence the `RefCell`.
+struct Transcoder<'a, D> {
+    deserializer: RefCell<Option<D>>,
+    failure: &'a RefCell<Option<String>>,
+}
+
+impl<'a, D> Transcoder<'a, D> {
+    fn new(deserializer: D, failure: &'a RefCell<Option<String>>) -> Self {
+        Transcoder {
+            deserializer: RefCell::new(Some(deserializer)),
+            failure,
+        }
+    }
+}
+
+impl<'de, 'a, D> Serialize for Transcoder<'a, D>
+where
+    D: de::Deserializer<'de>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let deserializer = match self.deserializer.borrow_mut().take() {
+            Some(deserializer) => deserializer,
+            None => return Err(ser::Error::custom("value serialized twice")),
+        };
+        let visitor = Visitor {
+            serializer,
+            failure: self.failure,
+        };
+
+        deserializer.deserialize_any(visitor).map_err(|error| {
+            let mut failure = self.failure.borrow_mut();
+            if failure.is_none() {
+                *failure = Some(error.to_string());
+            }
+            ser::Error::custom(error)
+        })
+    }
+}
+
+struct Visitor<'a, S> {
+    serializer: S,
+    failure: &'a RefCell<Option<String>>,
+}
+
+impl<'de, 'a, S> de::Visitor<'de> for Visitor<'a, S>
+where
+    S: ser::Serializer,
+{
+    type Value = S::Ok;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<S::Ok, E> {
+        self.serializer.serialize_unit().map_err(de::Error::custom)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<S::Ok, E> {
+        self.serializer.serialize_bool(v).map_err(de::Error::custom)
+    }
+
+    fn visit_i32<E: de::Error>(self, v: i32) -> Result<S::Ok, E> {
+        self.serializer.serialize_i32(v).map_err(de::Error::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<S::Ok, E> {
+        self.serializer.serialize_f64(v).map_err(de::Error::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<S::Ok, E> {
+        self.serializer.serialize_str(v).map_err(de::Error::custom)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<S::Ok, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut out = self
+            .serializer
+            .serialize_seq(seq.size_hint())
+            .map_err(de::Error::custom)?;
+        while let Some(()) = seq.next_element_seed(Element(&mut out, self.failure))? {}
+        out.end().map_err(de::Error::custom)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<S::Ok, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut out = self
+            .serializer
+            .serialize_map(map.size_hint())
+            .map_err(de::Error::custom)?;
+        while let Some(()) = map.next_key_seed(Key(&mut out, self.failure))? {
+            map.next_value_seed(Entry(&mut out, self.failure))?;
+        }
+        out.end().map_err(de::Error::custom)
+    }
+}
+
+struct Element<'a, S>(&'a mut S, &'a RefCell<Option<String>>);
+
+impl<'de, 'a, S> DeserializeSeed<'de> for Element<'a, S>
+where
+    S: SerializeSeq,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.0
+            .serialize_element(&Transcoder::new(deserializer, self.1))
+            .map_err(de::Error::custom)
+    }
+}
+
+struct Key<'a, S>(&'a mut S, &'a RefCell<Option<String>>);
+
+impl<'de, 'a, S> DeserializeSeed<'de> for Key<'a, S>
+where
+    S: SerializeMap,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.0
+            .serialize_key(&Transcoder::new(deserializer, self.1))
+            .map_err(de::Error::custom)
+    }
+}
+
+struct Entry<'a, S>(&'a mut S, &'a RefCell<Option<String>>);
+
+impl<'de, 'a, S> DeserializeSeed<'de> for Entry<'a, S>
+where
+    S: SerializeMap,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.0
+            .serialize_value(&Transcoder::new(deserializer, self.1))
+            .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,338 @@
+//! A lossless tree over the tokens of a valid document: writing it out
+//! gives back the text it was built from, and arrays and objects are split
+//! into entries that carry their comments along when moved or removed.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::lexer::{SyntaxKind, Token};
+use crate::options::LineEnding;
+
+pub struct Node<'a> {
+    pub kind: NodeKind<'a>,
+}
+
+pub enum NodeKind<'a> {
+    Leaf(&'a str),
+    Container {
+        open: &'a str,
+        // Whitespace and comments on the line of the opening bracket.
+        head: &'a str,
+        entries: Vec<Entry<'a>>,
+        // Whatever follows the last entry, up to the closing bracket.
+        tail: &'a str,
+        close: &'a str,
+    },
+}
+
+/// An array element or object member.
+pub struct Entry<'a> {
+    /// Whitespace before the entry's comments, or before the entry itself.
+    /// It stays in place when entries are reordered.
+    pub slot: &'a str,
+    /// Comments on the lines before the entry, and the whitespace after them.
+    pub comments: &'a str,
+    /// The key as written, and what separates it from the value.
+    pub key: Option<(&'a str, &'a str)>,
+    pub value: Node<'a>,
+    /// Anything between the value and its comma.
+    pub after_value: &'a str,
+    pub comma: bool,
+    /// Comments on the line of the entry, after its comma.
+    pub trailing: &'a str,
+}
+
+impl<'a> Entry<'a> {
+    /// The key, unquoted.
+    pub fn name(&self) -> Option<String> {
+        let (key, _) = self.key?;
+        Some(crate::parser::unquote_key(key))
+    }
+}
+
+/// A document: its value, and the comments around it.
+pub struct Tree<'a> {
+    pub before: &'a str,
+    pub root: Node<'a>,
+    pub after: &'a str,
+    /// What ends the document's lines, for line breaks that rearranging
+    /// entries adds.
+    pub line_ending: LineEnding,
+}
+
+impl<'a> Tree<'a> {
+    /// Builds the tree of a document that parses, from its tokens.
+    pub fn build(json: &'a str, tokens: &[Token]) -> Self {
+        let mut builder = Builder {
+            json,
+            tokens,
+            index: 0,
+        };
+        let before = builder.skip_trivia();
+        let root = builder.node();
+        Tree {
+            before,
+            root,
+            after: builder.skip_trivia(),
+            line_ending: crate::lexer::line_ending(json, tokens),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from(self.before);
+        self.root.write(&mut out, self.line_ending);
+        out.push_str(self.after);
+        out
+    }
+}
+
+struct Builder<'a, 't> {
+    json: &'a str,
+    tokens: &'t [Token],
+    index: usize,
+}
+
+impl<'a, 't> Builder<'a, 't> {
+    fn offset(&self) -> usize {
+        match self.tokens.get(self.index) {
+            Some(token) => token.span.start.offset,
+            None => self.json.len(),
+        }
+    }
+
+    fn kind(&self) -> Option<SyntaxKind> {
+        self.tokens.get(self.index).map(|t| t.kind)
+    }
+
+    fn text(&self, start: usize) -> &'a str {
+        &self.json[start..self.offset()]
+    }
+
+    fn bump(&mut self) -> &'a str {
+        let start = self.offset();
+        self.index += 1;
+        self.text(start)
+    }
+
+    fn skip_trivia(&mut self) -> &'a str {
+        let start = self.offset();
+        while self.tokens.get(self.index).is_some_and(Token::is_trivia) {
+            self.index += 1;
+        }
+        self.text(start)
+    }
+
+    // Whitespace and comments up to the end of the line, if there's a
+    // comment among them, leaving the whitespace after the last comment.
+    // Comments followed by another entry on the same line are left to it.
+    fn same_line_comments(&mut self) -> &'a str {
+        let (start, index) = (self.offset(), self.index);
+        let mut end = index;
+
+        while let Some(token) = self.tokens.get(self.index) {
+            let text = &self.json[token.span.start.offset..token.span.end.offset];
+            match token.kind {
+                SyntaxKind::Whitespace if !has_line_break(text) => (),
+                SyntaxKind::LineComment => end = self.index + 1,
+                SyntaxKind::BlockComment if !has_line_break(text) => end = self.index + 1,
+                SyntaxKind::Whitespace
+                | SyntaxKind::BlockComment
+                | SyntaxKind::RightBrace
+                | SyntaxKind::RightBracket => break,
+                _ => {
+                    end = index;
+                    break;
+                }
+            }
+            self.index += 1;
+        }
+        self.index = end;
+        self.text(start)
+    }
+
+    fn node(&mut self) -> Node<'a> {
+        let close = match self.kind() {
+            Some(SyntaxKind::LeftBrace) => SyntaxKind::RightBrace,
+            Some(SyntaxKind::LeftBracket) => SyntaxKind::RightBracket,
+            _ => {
+                return Node {
+                    kind: NodeKind::Leaf(self.bump()),
+                }
+            }
+        };
+        let is_object = close == SyntaxKind::RightBrace;
+        let open = self.bump();
+        let head = self.same_line_comments();
+        let mut entries = Vec::new();
+
+        loop {
+            let leading = self.skip_trivia();
+            if self.kind() == Some(close) || self.kind().is_none() {
+                return Node {
+                    kind: NodeKind::Container {
+                        open,
+                        head,
+                        entries,
+                        tail: leading,
+                        close: self.bump(),
+                    },
+                };
+            }
+            let split = leading
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(leading.len());
+            let (slot, comments) = leading.split_at(split);
+
+            let key = if is_object {
+                let key = self.bump();
+                let separator_start = self.offset();
+                self.skip_trivia();
+                self.bump();
+                self.skip_trivia();
+                Some((key, self.text(separator_start)))
+            } else {
+                None
+            };
+            let value = self.node();
+
+            let after_start = self.offset();
+            let index = self.index;
+            self.skip_trivia();
+            let (after_value, comma) = if self.kind() == Some(SyntaxKind::Comma) {
+                let after_value = self.text(after_start);
+                self.bump();
+                (after_value, true)
+            } else {
+                self.index = index;
+                ("", false)
+            };
+            let trailing = self.same_line_comments();
+
+            entries.push(Entry {
+                slot,
+                comments,
+                key,
+                value,
+                after_value,
+                comma,
+                trailing,
+            });
+        }
+    }
+}
+
+impl<'a> Node<'a> {
+    pub fn write(&self, out: &mut String, line_ending: LineEnding) {
+        match &self.kind {
+            NodeKind::Leaf(text) => out.push_str(text),
+            NodeKind::Container {
+                open,
+                head,
+                entries,
+                tail,
+                close,
+            } => {
+                out.push_str(open);
+                out.push_str(head);
+                let mut line_comment = ends_with_line_comment(head);
+
+                for entry in entries {
+                    // A line comment must stay followed by a line break.
+                    if line_comment && !has_line_break(entry.slot) {
+                        out.push_str(line_ending.as_str());
+                    }
+                    entry.write(out, line_ending);
+                    line_comment = ends_with_line_comment(entry.trailing);
+                }
+                if line_comment && !has_line_break(tail) {
+                    out.push_str(line_ending.as_str());
+                }
+                out.push_str(tail);
+                out.push_str(close);
+            }
+        }
+    }
+
+    /// The entries of an array or object.
+    pub fn entries_mut(&mut self) -> Option<&mut Vec<Entry<'a>>> {
+        match &mut self.kind {
+            NodeKind::Container { entries, .. } => Some(entries),
+            NodeKind::Leaf(_) => None,
+        }
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(&self.kind, NodeKind::Container { open, .. } if *open == "{")
+    }
+
+    /// The entry for `token` of a JSON Pointer: the last with that key,
+    /// as parsing keeps the last of duplicate keys, or the element at that
+    /// index.
+    pub fn entry(&self, token: &str) -> Option<&Entry<'a>> {
+        let entries = match &self.kind {
+            NodeKind::Container { entries, .. } => entries,
+            NodeKind::Leaf(_) => return None,
+        };
+        if self.is_object() {
+            entries
+                .iter()
+                .rev()
+                .find(|e| e.name().as_deref() == Some(token))
+        } else {
+            entries.get(crate::pointer::parse_index(token)?)
+        }
+    }
+
+    // As `entry`.
+    pub fn entry_mut(&mut self, token: &str) -> Option<&mut Entry<'a>> {
+        let is_object = self.is_object();
+        let entries = self.entries_mut()?;
+        if is_object {
+            entries
+                .iter_mut()
+                .rev()
+                .find(|e| e.name().as_deref() == Some(token))
+        } else {
+            entries.get_mut(crate::pointer::parse_index(token)?)
+        }
+    }
+}
+
+impl<'a> Entry<'a> {
+    pub fn write(&self, out: &mut String, line_ending: LineEnding) {
+        out.push_str(self.slot);
+        out.push_str(self.comments);
+        if let Some((key, separator)) = self.key {
+            out.push_str(key);
+            out.push_str(separator);
+        }
+        self.value.write(out, line_ending);
+        out.push_str(self.after_value);
+        if self.comma {
+            out.push(',');
+        }
+        out.push_str(self.trailing);
+    }
+}
+
+/// Restores the commas and the whitespace between entries, after they have
+/// been reordered or some removed: entries take the slots in order, and the
+/// last entry has a comma only if the last one had before.
+pub fn rearrange<'a>(entries: &mut Vec<Entry<'a>>, slots: &[&'a str], trailing_comma: bool) {
+    let len = entries.len();
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.slot = slots[i];
+        entry.comma = i + 1 < len || trailing_comma;
+    }
+}
+
+fn has_line_break(text: &str) -> bool {
+    text.contains(['\n', '\r'])
+}
+
+fn ends_with_line_comment(trivia: &str) -> bool {
+    trivia
+        .rsplit(['\n', '\r'])
+        .next()
+        .is_some_and(|line| line.contains("//"))
+}
@@ -0,0 +1,97 @@
+use alloc::string::String;
+use core::fmt;
+
+use crate::value::Value;
+
+/// Why `Value::try_index` found nothing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexError {
+    /// The index is past the end of the array.
+    OutOfBounds { index: usize, len: usize },
+    /// The object doesn't have the key.
+    MissingKey(String),
+    /// An index into what isn't an array, or a key into what isn't an
+    /// object, with the name of the type found, as `Value::type_name`.
+    WrongType {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { index, len } => {
+                write!(f, "index {} out of bounds for length {}", index, len)
+            }
+            Self::MissingKey(key) => write!(f, "missing key `{}`", key),
+            Self::WrongType { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IndexError {}
+
+/// What `Value::try_index` takes: `usize` indices into arrays, and string
+/// keys into objects.
+pub trait ValueIndex {
+    fn try_index_into<'v>(&self, value: &'v Value) -> Result<&'v Value, IndexError>;
+}
+
+impl ValueIndex for usize {
+    fn try_index_into<'v>(&self, value: &'v Value) -> Result<&'v Value, IndexError> {
+        match value {
+            Value::Array(v) => v.get(*self).ok_or(IndexError::OutOfBounds {
+                index: *self,
+                len: v.len(),
+            }),
+            value => Err(IndexError::WrongType {
+                expected: "array",
+                found: value.type_name(),
+            }),
+        }
+    }
+}
+
+impl ValueIndex for str {
+    fn try_index_into<'v>(&self, value: &'v Value) -> Result<&'v Value, IndexError> {
+        match value {
+            Value::Object(m) => m
+                .get(self)
+                .ok_or_else(|| IndexError::MissingKey(self.into())),
+            value => Err(IndexError::WrongType {
+                expected: "object",
+                found: value.type_name(),
+            }),
+        }
+    }
+}
+
+impl ValueIndex for String {
+    fn try_index_into<'v>(&self, value: &'v Value) -> Result<&'v Value, IndexError> {
+        self.as_str().try_index_into(value)
+    }
+}
+
+impl<T> ValueIndex for &T
+where
+    T: ValueIndex + ?Sized,
+{
+    fn try_index_into<'v>(&self, value: &'v Value) -> Result<&'v Value, IndexError> {
+        (**self).try_index_into(value)
+    }
+}
+
+impl Value {
+    /// Like indexing, but failing with what was wrong rather than giving
+    /// `Null`.
+    pub fn try_index<I>(&self, index: I) -> Result<&Value, IndexError>
+    where
+        I: ValueIndex,
+    {
+        index.try_index_into(self)
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(feature = "duration")]
+use core::time::Duration;
+
+#[cfg(feature = "duration")]
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let mut rest = s;
+    let mut total = 0.0;
+
+    if s.is_empty() {
+        return None;
+    }
+
+    // e.g. "1h30m", "1.5s", "250ms"
+    while !rest.is_empty() {
+        let (amount, unit, tail) = split_quantity(rest)?;
+        let scale = match unit {
+            "ns" => 1e-9,
+            "us" | "µs" => 1e-6,
+            "ms" => 1e-3,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return None,
+        };
+        total += amount * scale;
+        rest = tail;
+    }
+
+    Duration::try_from_secs_f64(total).ok()
+}
+
+#[cfg(feature = "byte-size")]
+pub fn parse_byte_size(s: &str) -> Option<u64> {
+    let (amount, unit, tail) = split_quantity(s.trim())?;
+
+    if !tail.is_empty() {
+        return None;
+    }
+
+    let scale: u64 = match unit {
+        "" | "B" => 1,
+        "KB" | "kB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return None,
+    };
+
+    let bytes = amount * scale as f64;
+
+    if bytes.fract() == 0.0 && bytes < u64::MAX as f64 {
+        Some(bytes as u64)
+    } else {
+        None
+    }
+}
+
+// Splits "1.5GiB..." into (1.5, "GiB", "..."), where the unit is the run of
+// letters following the number and the tail starts at the next digit.
+#[cfg(any(feature = "duration", feature = "byte-size"))]
+fn split_quantity(s: &str) -> Option<(f64, &str, &str)> {
+    let number_end = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let amount = s[..number_end].parse::<f64>().ok()?;
+    let rest = s[number_end..].trim_start();
+    let unit_end = rest
+        .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+        .unwrap_or(rest.len());
+    let (unit, tail) = rest.split_at(unit_end);
+
+    Some((amount, unit, tail.trim_start()))
+}
@@ -1,7 +1,32 @@
-use std::collections::HashMap;
-use std::ops::Index;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::ops::Index;
+#[cfg(feature = "duration")]
+use core::time::Duration;
 
-#[derive(Debug)]
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, TimeZone};
+#[cfg(feature = "url")]
+use url::Url;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+use crate::custom::Scalar;
+use crate::map::Map;
+use crate::options::{KeyMatching, StringifyOptions};
+use crate::pointer::{self, Pointer};
+#[cfg(any(feature = "duration", feature = "byte-size"))]
+use crate::units;
+
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+// 2^63, the smallest float above i64::MAX
+const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+#[derive(Clone, Debug)]
 pub enum Value {
     Null,
     Boolean(bool),
@@ -9,16 +34,16 @@ pub enum Value {
     Float(f64),
     String(String),
     Array(Vec<Value>),
-    Object(HashMap<String, Value>),
+    Object(Map<String, Value>),
+    /// A value of a type defined outside this crate, written as its
+    /// `Scalar::to_value` wherever a standard value is needed.
+    Custom(Arc<dyn Scalar>),
 }
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match self {
-            Self::Null => match other {
-                Self::Null => true,
-                _ => false,
-            },
+            Self::Null => matches!(other, Self::Null),
             Self::Boolean(b1) => match other {
                 Self::Boolean(b2) => b1 == b2,
                 _ => false,
@@ -43,10 +68,35 @@ impl PartialEq for Value {
                 Self::Object(m2) => m1 == m2,
                 _ => false,
             },
+            // Scalars of the same type, standing for the same value.
+            Self::Custom(c1) => match other {
+                Self::Custom(c2) => {
+                    (**c1).type_id() == (**c2).type_id() && c1.to_value() == c2.to_value()
+                }
+                _ => false,
+            },
         }
     }
 }
 
+#[cfg(feature = "chrono")]
+impl<Tz> From<DateTime<Tz>> for Value
+where
+    Tz: TimeZone,
+    Tz::Offset: core::fmt::Display,
+{
+    fn from(datetime: DateTime<Tz>) -> Self {
+        Self::String(datetime.to_rfc3339())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<Uuid> for Value {
+    fn from(uuid: Uuid) -> Self {
+        Self::String(uuid.hyphenated().to_string())
+    }
+}
+
 impl Index<usize> for Value {
     type Output = Value;
 
@@ -69,12 +119,215 @@ impl Index<&str> for Value {
     }
 }
 
+impl Index<String> for Value {
+    type Output = Value;
+
+    fn index(&self, key: String) -> &Self::Output {
+        &self[key.as_str()]
+    }
+}
+
+impl Index<&String> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &String) -> &Self::Output {
+        &self[key.as_str()]
+    }
+}
+
+impl Index<&Pointer> for Value {
+    type Output = Value;
+
+    fn index(&self, pointer: &Pointer) -> &Self::Output {
+        self.at(pointer.tokens())
+    }
+}
+
 impl Value {
-    pub fn is_null(&self) -> bool {
+    // Constructors usable in constants and statics, e.g. for defaults
+    // compiled into a program.
+
+    pub const fn null() -> Value {
+        Value::Null
+    }
+
+    pub const fn bool(b: bool) -> Value {
+        Value::Boolean(b)
+    }
+
+    pub const fn integer(i: i32) -> Value {
+        Value::Integer(i)
+    }
+
+    pub const fn float(f: f64) -> Value {
+        Value::Float(f)
+    }
+
+    pub const fn empty_string() -> Value {
+        Value::String(String::new())
+    }
+
+    pub const fn empty_array() -> Value {
+        Value::Array(Vec::new())
+    }
+
+    /// Follows `path` one token at a time: object keys are matched exactly,
+    /// array indices are parsed from decimal. Missing entries yield `Null`.
+    pub fn at<I>(&self, path: I) -> &Value
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        path.into_iter().fold(self, |value, token| {
+            let token = token.as_ref();
+            match value {
+                Self::Array(_) => match pointer::parse_index(token) {
+                    Some(i) => &value[i],
+                    None => &Self::Null,
+                },
+                _ => &value[token],
+            }
+        })
+    }
+
+    /// The value of `key`, if this is an object that has it. Like indexing,
+    /// this doesn't allocate.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.to_map()?.get(key)
+    }
+
+    /// Like `get`, but with the key matched as `matching` says.
+    pub fn get_with(&self, key: &str, matching: KeyMatching) -> Option<&Value> {
+        let map = self.to_map()?;
+        map.get(key).or_else(|| {
+            let entries = map.iter().map(|(key, value)| (key.as_str(), value));
+            matching.best(entries, key).map(|(_, value)| value)
+        })
+    }
+
+    /// The key as stored and its value, e.g. to borrow the key for longer
+    /// than the one looked up with.
+    pub fn get_key_value(&self, key: &str) -> Option<(&String, &Value)> {
+        self.to_map()?.get_key_value(key)
+    }
+
+    /// The value at `pointer`, if there is one. Like indexing, this doesn't
+    /// allocate.
+    pub fn pointer(&self, pointer: &Pointer) -> Option<&Value> {
+        pointer
+            .tokens()
+            .iter()
+            .try_fold(self, |value, token| match value {
+                Self::Array(v) => v.get(pointer::parse_index(token)?),
+                Self::Object(m) => m.get(token.as_str()),
+                _ => None,
+            })
+    }
+
+    /// The value at `pointer`, written as `stringify_with_options` does, if
+    /// there is one.
+    pub fn serialize_pointer(
+        &self,
+        pointer: &Pointer,
+        options: &StringifyOptions,
+    ) -> Option<String> {
+        self.pointer(pointer)
+            .map(|value| crate::stringify_with_options(value, options))
+    }
+
+    /// Like `pointer`, but with object keys matched as `matching` says.
+    pub fn pointer_with(&self, pointer: &Pointer, matching: KeyMatching) -> Option<&Value> {
+        pointer
+            .tokens()
+            .iter()
+            .try_fold(self, |value, token| match value {
+                Self::Array(v) => v.get(pointer::parse_index(token)?),
+                _ => value.get_with(token, matching),
+            })
+    }
+
+    /// The value at `pointer`, to change in place, if there is one.
+    pub fn pointer_mut(&mut self, pointer: &Pointer) -> Option<&mut Value> {
+        pointer
+            .tokens()
+            .iter()
+            .try_fold(self, |value, token| match value {
+                Self::Array(v) => v.get_mut(pointer::parse_index(token)?),
+                Self::Object(m) => m.get_mut(token.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Like `at`, but with object keys matched as `matching` says.
+    pub fn at_with<I>(&self, path: I, matching: KeyMatching) -> &Value
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        path.into_iter().fold(self, |value, token| {
+            let token = token.as_ref();
+            match value {
+                Self::Object(_) => value.get_with(token, matching).unwrap_or(&Self::Null),
+                _ => value.at([token]),
+            }
+        })
+    }
+
+    /// The values of `key` in the objects of this array, in order, `None`
+    /// for elements that aren't objects or lack the key. Empty unless this
+    /// is an array.
+    pub fn column(&self, key: &str) -> Vec<Option<&Value>> {
+        self.column_with(key, Some)
+    }
+
+    /// Like `column`, converting each value with `convert`, as in
+    /// `column_with("price", Value::to_f64)`.
+    pub fn column_with<'a, T, F>(&'a self, key: &str, convert: F) -> Vec<Option<T>>
+    where
+        F: Fn(&'a Value) -> Option<T>,
+    {
         match self {
-            Self::Null => true,
-            _ => false,
+            Self::Array(v) => v
+                .iter()
+                .map(|value| value.to_map()?.get(key).and_then(&convert))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The columns of `keys`, in order, as `column` gives them, reading the
+    /// array once.
+    pub fn columns(&self, keys: &[&str]) -> Vec<Vec<Option<&Value>>> {
+        let rows = self.to_vec().map_or(&[][..], |v| v);
+        let mut columns: Vec<_> = keys
+            .iter()
+            .map(|_| Vec::with_capacity(rows.len()))
+            .collect();
+        for row in rows {
+            let map = row.to_map();
+            for (column, key) in columns.iter_mut().zip(keys) {
+                column.push(map.and_then(|m| m.get(*key)));
+            }
         }
+        columns
+    }
+
+    /// `null`, `boolean`, `number`, `string`, `array` or `object`, for
+    /// messages. Custom values have the type of the value they stand for.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Boolean(_) => "boolean",
+            Self::Integer(_) | Self::Float(_) => "number",
+            Self::String(_) => "string",
+            Self::Array(_) => "array",
+            Self::Object(_) => "object",
+            Self::Custom(c) => c.to_value().type_name(),
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
     }
 
     pub fn to_bool(&self) -> Option<bool> {
@@ -84,6 +337,7 @@ impl Value {
         }
     }
 
+    #[deprecated(note = "silently truncates floats; use `to_i64_exact` or `to_i64_lossy`")]
     pub fn to_i32(&self) -> Option<i32> {
         match self {
             Self::Integer(i) => Some(*i),
@@ -92,6 +346,30 @@ impl Value {
         }
     }
 
+    /// Takes integers, and floats that are whole numbers within the range of
+    /// `i64`, such as `3.0` or `1e15`. Unlike `to_i64`, which only takes
+    /// integers, this suits values that may have been written or computed
+    /// as floats.
+    pub fn to_i64_exact(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(i64::from(*i)),
+            Self::Float(f) if *f >= -I64_BOUND && *f < I64_BOUND && *f as i64 as f64 == *f => {
+                Some(*f as i64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Truncates floats toward zero, saturating at the bounds of `i64`.
+    pub fn to_i64_lossy(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(i64::from(*i)),
+            Self::Float(f) if f.is_nan() => None,
+            Self::Float(f) => Some(*f as i64),
+            _ => None,
+        }
+    }
+
     pub fn to_f64(&self) -> Option<f64> {
         match self {
             Self::Integer(i) => Some(*i as f64),
@@ -100,6 +378,96 @@ impl Value {
         }
     }
 
+    pub fn to_u8(&self) -> Option<u8> {
+        match self {
+            Self::Integer(i) => u8::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn to_u16(&self) -> Option<u16> {
+        match self {
+            Self::Integer(i) => u16::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn to_u32(&self) -> Option<u32> {
+        match self {
+            Self::Integer(i) => u32::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self {
+            Self::Integer(i) => u64::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+
+    /// Takes integers only, as `to_u8` to `to_u64` do: a float is `None`
+    /// even when it is a whole number, which `to_i64_exact` takes too.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(i64::from(*i)),
+            _ => None,
+        }
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        match self {
+            Self::Integer(i) => Some(i128::from(*i)),
+            _ => None,
+        }
+    }
+
+    pub fn to_f32(&self) -> Option<f32> {
+        match self {
+            Self::Integer(i) => Some(*i as f32),
+            Self::Float(f) if f.is_finite() && f.abs() > f32::MAX as f64 => None,
+            Self::Float(f) => Some(*f as f32),
+            _ => None,
+        }
+    }
+
+    /// Like `to_bool`, but also taking `0` and `1`, and the strings `true`,
+    /// `false`, `0` and `1`, ignoring case and surrounding whitespace, as
+    /// values from the environment come.
+    pub fn coerce_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(b) => Some(*b),
+            Self::Integer(0) => Some(false),
+            Self::Integer(1) => Some(true),
+            Self::Float(f) if *f == 0.0 => Some(false),
+            Self::Float(f) if *f == 1.0 => Some(true),
+            Self::String(s) => match s.trim() {
+                s if s.eq_ignore_ascii_case("true") || s == "1" => Some(true),
+                s if s.eq_ignore_ascii_case("false") || s == "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Like `to_i64_exact`, but also taking strings of decimal integers,
+    /// such as `"42"`, ignoring surrounding whitespace.
+    pub fn coerce_i64(&self) -> Option<i64> {
+        match self {
+            Self::String(s) => s.trim().parse().ok(),
+            _ => self.to_i64_exact(),
+        }
+    }
+
+    /// Like `to_f64`, but also taking strings of numbers, such as `"1.5"`,
+    /// ignoring surrounding whitespace.
+    pub fn coerce_f64(&self) -> Option<f64> {
+        match self {
+            Self::String(s) => s.trim().parse().ok(),
+            _ => self.to_f64(),
+        }
+    }
+
     pub fn to_string(&self) -> Option<&String> {
         match self {
             Self::String(s) => Some(s),
@@ -114,10 +482,177 @@ impl Value {
         }
     }
 
-    pub fn to_map(&self) -> Option<&HashMap<String, Self>> {
+    pub fn to_map(&self) -> Option<&Map<String, Self>> {
         match self {
             Self::Object(m) => Some(m),
             _ => None,
         }
     }
+
+    #[cfg(feature = "duration")]
+    pub fn to_duration(&self) -> Option<Duration> {
+        match self {
+            Self::String(s) => units::parse_duration(s),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "byte-size")]
+    pub fn to_byte_size(&self) -> Option<u64> {
+        match self {
+            Self::Integer(i) => u64::try_from(*i).ok(),
+            Self::String(s) => units::parse_byte_size(s),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "url")]
+    pub fn to_url(&self) -> Option<Url> {
+        match self {
+            Self::String(s) => Url::parse(s).ok(),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn to_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::String(s) => DateTime::parse_from_rfc3339(s).ok(),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "uuid")]
+    pub fn to_uuid(&self) -> Option<Uuid> {
+        match self {
+            Self::String(s) => Uuid::parse_str(s).ok(),
+            _ => None,
+        }
+    }
+
+    /// The number of elements in an array, entries in an object, or chars in
+    /// a string. Other values have length 0.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::String(s) => s.chars().count(),
+            Self::Array(v) => v.len(),
+            Self::Object(m) => m.len(),
+            _ => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        match self {
+            Self::Object(m) => m.contains_key(key),
+            _ => false,
+        }
+    }
+
+    pub fn contains(&self, value: &Value) -> bool {
+        match self {
+            Self::Array(v) => v.contains(value),
+            _ => false,
+        }
+    }
+
+    /// Appends to an array. Returns false, leaving `self` untouched, if
+    /// `self` is not an array; likewise for the other array helpers below.
+    pub fn push(&mut self, value: Value) -> bool {
+        match self {
+            Self::Array(v) => {
+                v.push(value);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Also returns false if `index` is past the end of the array.
+    pub fn insert_at(&mut self, index: usize, value: Value) -> bool {
+        match self {
+            Self::Array(v) if index <= v.len() => {
+                v.insert(index, value);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn remove_at(&mut self, index: usize) -> Option<Value> {
+        match self {
+            Self::Array(v) if index < v.len() => Some(v.remove(index)),
+            _ => None,
+        }
+    }
+
+    pub fn extend<I>(&mut self, values: I) -> bool
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        match self {
+            Self::Array(v) => {
+                v.extend(values);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn sort_by<F>(&mut self, compare: F) -> bool
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        match self {
+            Self::Array(v) => {
+                v.sort_by(compare);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes consecutive equal elements.
+    pub fn dedup(&mut self) -> bool {
+        match self {
+            Self::Array(v) => {
+                v.dedup();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn redact<F>(&mut self, is_secret: F)
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.redact_with(&is_secret);
+    }
+
+    fn redact_with<F>(&mut self, is_secret: &F)
+    where
+        F: Fn(&str) -> bool,
+    {
+        match self {
+            Self::Array(v) => {
+                for value in v.iter_mut() {
+                    value.redact_with(is_secret);
+                }
+            }
+            Self::Object(m) => {
+                for (key, value) in m.iter_mut() {
+                    if is_secret(key) {
+                        *value = Self::String(REDACTED.to_string());
+                    } else {
+                        value.redact_with(is_secret);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
 }
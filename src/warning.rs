@@ -0,0 +1,51 @@
+use alloc::string::String;
+use core::fmt;
+
+use crate::pointer::Pointer;
+use crate::span::Span;
+
+/// A finding that doesn't prevent parsing but may point at a mistake.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub span: Span,
+}
+
+/// A key repeated in an object, by `parse_with_duplicates`: the spans of
+/// the member kept and of one dropped, each from the key to the end of the
+/// value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Duplicate {
+    /// The JSON Pointer of the member.
+    pub pointer: Pointer,
+    pub kept: Span,
+    pub dropped: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WarningKind {
+    DuplicateKey(String),
+    LoneSurrogate,
+    /// An unescaped control character in a string.
+    ControlCharacter(char),
+    /// A `NaN` literal, which is rarely intended in configuration data.
+    NaN,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            WarningKind::DuplicateKey(key) => write!(f, "duplicate key `{}`", key)?,
+            WarningKind::LoneSurrogate => f.write_str("lone surrogate replaced with U+FFFD")?,
+            WarningKind::ControlCharacter(c) => {
+                write!(f, "unescaped control character `{}`", c.escape_debug())?
+            }
+            WarningKind::NaN => f.write_str("unquoted NaN")?,
+        }
+        write!(
+            f,
+            ", line {} column {}",
+            self.span.start.line, self.span.start.column
+        )
+    }
+}
@@ -0,0 +1,85 @@
+use core::convert::TryFrom;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::map::Map;
+use crate::value::Value;
+
+#[wasm_bindgen(js_name = parse)]
+pub fn parse_js(json: &str) -> Result<JsValue, JsValue> {
+    match crate::parse(json) {
+        Ok(value) => Ok(JsValue::from(value)),
+        Err(e) => Err(js_sys::SyntaxError::new(&e.to_string()).into()),
+    }
+}
+
+#[wasm_bindgen(js_name = stringify)]
+pub fn stringify_js(value: JsValue) -> Result<String, JsValue> {
+    let value = Value::try_from(value)?;
+    Ok(crate::stringify(&value))
+}
+
+impl From<Value> for JsValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => JsValue::NULL,
+            Value::Boolean(b) => JsValue::from_bool(b),
+            Value::Integer(i) => JsValue::from_f64(i.into()),
+            Value::Float(f) => JsValue::from_f64(f),
+            Value::String(s) => JsValue::from_str(&s),
+            Value::Array(v) => v.into_iter().map(JsValue::from).collect::<Array>().into(),
+            Value::Object(m) => {
+                let object = Object::new();
+                for (key, value) in m {
+                    // Setting a property on a plain object cannot fail.
+                    let _ = Reflect::set(&object, &JsValue::from_str(&key), &value.into());
+                }
+                object.into()
+            }
+            Value::Custom(c) => c.to_value().into(),
+        }
+    }
+}
+
+impl TryFrom<JsValue> for Value {
+    type Error = JsValue;
+
+    fn try_from(value: JsValue) -> Result<Self, Self::Error> {
+        if value.is_null() || value.is_undefined() {
+            return Ok(Value::Null);
+        }
+        if let Some(b) = value.as_bool() {
+            return Ok(Value::Boolean(b));
+        }
+        if let Some(f) = value.as_f64() {
+            let i = f as i32;
+            if f64::from(i) == f && !(f == 0.0 && f.is_sign_negative()) {
+                return Ok(Value::Integer(i));
+            }
+            return Ok(Value::Float(f));
+        }
+        if let Some(s) = value.as_string() {
+            return Ok(Value::String(s));
+        }
+        if Array::is_array(&value) {
+            let array: Array = value.unchecked_into();
+            let mut v = Vec::with_capacity(array.length() as usize);
+            for element in array.iter() {
+                v.push(Value::try_from(element)?);
+            }
+            return Ok(Value::Array(v));
+        }
+        if value.is_object() && !value.is_function() {
+            let mut m = Map::new();
+            for entry in Object::entries(value.unchecked_ref()).iter() {
+                let entry: Array = entry.unchecked_into();
+                let key = entry.get(0).as_string().unwrap_or_default();
+                m.insert(key, Value::try_from(entry.get(1))?);
+            }
+            return Ok(Value::Object(m));
+        }
+        Err(js_sys::TypeError::new("value cannot be represented in JSON5").into())
+    }
+}
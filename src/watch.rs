@@ -0,0 +1,130 @@
+//! Reloading a document whenever its file changes.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+
+use crate::error::Error;
+use crate::value::Value;
+
+// Editors often save a file in several steps (truncate, write, rename), so
+// events are coalesced until the file has been quiet this long.
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+pub enum WatchError {
+    Io(io::Error),
+    Parse(Error),
+    Notify(notify::Error),
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read file: {}", e),
+            Self::Parse(e) => e.fmt(f),
+            Self::Notify(e) => write!(f, "failed to watch file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {}
+
+/// Stops watching when dropped.
+pub struct Watcher {
+    current: Arc<Mutex<Option<Value>>>,
+    _inner: RecommendedWatcher,
+}
+
+impl Watcher {
+    /// The last successfully parsed document, if any.
+    pub fn current(&self) -> Option<Value> {
+        self.current.lock().ok()?.clone()
+    }
+}
+
+/// Parses the file at `path` now and again after every change, passing each
+/// outcome to `callback`. After a failed reload, `Watcher::current` keeps
+/// returning the last good document.
+pub fn watch<P, F>(path: P, mut callback: F) -> Result<Watcher, WatchError>
+where
+    P: AsRef<Path>,
+    F: FnMut(Result<&Value, &WatchError>) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let current = Arc::new(Mutex::new(None));
+    reload(&path, &current, &mut callback);
+
+    // Watching the parent directory rather than the file itself survives the
+    // file being replaced by a rename.
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut inner = notify::recommended_watcher(tx).map_err(WatchError::Notify)?;
+    inner
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(WatchError::Notify)?;
+
+    let shared = Arc::clone(&current);
+    thread::spawn(move || {
+        let affects = |event: &notify::Event| {
+            event
+                .paths
+                .iter()
+                .any(|p| p.file_name() == path.file_name())
+        };
+
+        // The channel disconnects once the watcher has been dropped.
+        while let Ok(event) = rx.recv() {
+            match event {
+                Ok(event) if affects(&event) => (),
+                Ok(_) => continue,
+                Err(e) => {
+                    callback(Err(&WatchError::Notify(e)));
+                    continue;
+                }
+            }
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(_) => (),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            reload(&path, &shared, &mut callback);
+        }
+    });
+
+    Ok(Watcher {
+        current,
+        _inner: inner,
+    })
+}
+
+fn reload<F>(path: &Path, current: &Mutex<Option<Value>>, callback: &mut F)
+where
+    F: FnMut(Result<&Value, &WatchError>),
+{
+    let result = fs::read_to_string(path)
+        .map_err(WatchError::Io)
+        .and_then(|json| crate::parse(&json).map_err(WatchError::Parse));
+
+    match result {
+        Ok(value) => {
+            callback(Ok(&value));
+            if let Ok(mut current) = current.lock() {
+                *current = Some(value);
+            }
+        }
+        Err(e) => callback(Err(&e)),
+    }
+}
@@ -0,0 +1,212 @@
+use std::io::{self, Write};
+
+use crate::options::{StringifyOptions, TrailingCommas};
+use crate::value::Value;
+
+/// Writes a document piece by piece, as it is generated, rather than from a
+/// whole `Value`. Arrays and objects have one entry per line, indented by
+/// two spaces per level, and comments are line comments on lines of their
+/// own. Lines end as `StringifyOptions::line_ending` says, and arrays and
+/// objects, being written over several lines, have trailing commas unless
+/// `StringifyOptions::trailing_commas` is `Never`. Scalars and the values given to `value` are written as
+/// `stringify_with_options` writes them.
+///
+/// Calls out of order, such as a value where an object expects a key, fail
+/// with `io::ErrorKind::InvalidInput`.
+pub struct Json5Writer<W> {
+    out: W,
+    options: StringifyOptions,
+    frames: Vec<Frame>,
+    // A key has been written, and its value is next.
+    after_key: bool,
+    has_root: bool,
+}
+
+// An array or object being written.
+struct Frame {
+    object: bool,
+    empty: bool,
+    // A comma is due before whatever comes next.
+    comma: bool,
+}
+
+impl<W> Json5Writer<W>
+where
+    W: Write,
+{
+    pub fn new(out: W) -> Self {
+        Self::with_options(out, StringifyOptions::default())
+    }
+
+    pub fn with_options(out: W, options: StringifyOptions) -> Self {
+        Json5Writer {
+            out,
+            options,
+            frames: Vec::new(),
+            after_key: false,
+            has_root: false,
+        }
+    }
+
+    pub fn begin_object(&mut self) -> io::Result<()> {
+        self.begin('{', true)
+    }
+
+    pub fn end_object(&mut self) -> io::Result<()> {
+        self.end('}', true)
+    }
+
+    pub fn begin_array(&mut self) -> io::Result<()> {
+        self.begin('[', false)
+    }
+
+    pub fn end_array(&mut self) -> io::Result<()> {
+        self.end(']', false)
+    }
+
+    /// Writes the key of the next member of the object being written.
+    pub fn key(&mut self, key: &str) -> io::Result<()> {
+        match self.frames.last() {
+            Some(frame) if frame.object && !self.after_key => (),
+            _ => return Err(invalid("a key outside an object, or after a key")),
+        }
+        self.new_entry()?;
+        let key = crate::stringify(&Value::String(key.into()));
+        write!(self.out, "{}: ", key)?;
+        self.after_key = true;
+        Ok(())
+    }
+
+    /// Writes a whole value: an array element, the value of the last key,
+    /// or the document.
+    pub fn value(&mut self, value: &Value) -> io::Result<()> {
+        self.before_value()?;
+        let json = crate::stringify_with_options(value, &self.options);
+        self.out.write_all(json.as_bytes())
+    }
+
+    /// Writes `text` as line comments before whatever comes next, or after
+    /// the document.
+    pub fn comment(&mut self, text: &str) -> io::Result<()> {
+        if self.after_key {
+            return Err(invalid("a comment between a key and its value"));
+        }
+        let indent = self.frames.len();
+        match self.frames.last_mut() {
+            Some(frame) => {
+                if frame.comma {
+                    self.out.write_all(b",")?;
+                    frame.comma = false;
+                }
+                frame.empty = false;
+                let newline = self.options.line_ending.as_str();
+                for line in comment_lines(text) {
+                    write!(
+                        self.out,
+                        "{}{:indent$}// {}",
+                        newline,
+                        "",
+                        line,
+                        indent = indent * 2
+                    )?;
+                }
+            }
+            None => {
+                let newline = self.options.line_ending.as_str();
+                for line in comment_lines(text) {
+                    if self.has_root {
+                        self.out.write_all(newline.as_bytes())?;
+                    }
+                    write!(self.out, "// {}", line)?;
+                    if !self.has_root {
+                        self.out.write_all(newline.as_bytes())?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ends the document with a line break, and gives back the writer.
+    /// Fails if the document is incomplete.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.has_root || !self.frames.is_empty() || self.after_key {
+            return Err(invalid("an incomplete document"));
+        }
+        self.out
+            .write_all(self.options.line_ending.as_str().as_bytes())?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn begin(&mut self, open: char, object: bool) -> io::Result<()> {
+        self.before_value()?;
+        write!(self.out, "{}", open)?;
+        self.frames.push(Frame {
+            object,
+            empty: true,
+            comma: false,
+        });
+        Ok(())
+    }
+
+    fn end(&mut self, close: char, object: bool) -> io::Result<()> {
+        let frame = match self.frames.last() {
+            Some(frame) if frame.object == object && !self.after_key => frame,
+            _ => return Err(invalid("an end that doesn't match the beginning")),
+        };
+        if frame.comma && self.options.trailing_commas != TrailingCommas::Never {
+            self.out.write_all(b",")?;
+        }
+        if !frame.empty {
+            let indent = (self.frames.len() - 1) * 2;
+            self.new_line(indent)?;
+        }
+        self.frames.pop();
+        write!(self.out, "{}", close)
+    }
+
+    // Checks that a value may come next, and starts its line in an array.
+    fn before_value(&mut self) -> io::Result<()> {
+        if self.after_key {
+            self.after_key = false;
+            return Ok(());
+        }
+        match self.frames.last() {
+            Some(frame) if frame.object => Err(invalid("a value without a key")),
+            Some(_) => self.new_entry(),
+            None if self.has_root => Err(invalid("a second value after the document")),
+            None => {
+                self.has_root = true;
+                Ok(())
+            }
+        }
+    }
+
+    // Writes the comma after the last entry, if any, and starts a line.
+    fn new_entry(&mut self) -> io::Result<()> {
+        let indent = self.frames.len() * 2;
+        if let Some(frame) = self.frames.last_mut() {
+            if frame.comma {
+                self.out.write_all(b",")?;
+            }
+            frame.comma = true;
+            frame.empty = false;
+        }
+        self.new_line(indent)
+    }
+
+    fn new_line(&mut self, indent: usize) -> io::Result<()> {
+        let newline = self.options.line_ending.as_str();
+        write!(self.out, "{}{:indent$}", newline, "", indent = indent)
+    }
+}
+
+// The lines of a comment, which may end with a lone "\r" too.
+fn comment_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().flat_map(|line| line.split('\r'))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
@@ -0,0 +1,1061 @@
+//! Conversion to and from YAML.
+//!
+//! Only the parts of YAML that have a JSON5 equivalent are supported, and
+//! anything that would have to be guessed at is rejected. Aliases are
+//! expanded into copies of the anchored node, of at most a million values
+//! in all, so that a few nested aliases can't use up the memory. Of the
+//! tags, only those of the core schema (`!!str`, `!!int`, `!!float`,
+//! `!!bool`, `!!null`, `!!seq` and `!!map`) and the non-specific `!` are
+//! accepted. Merge keys (`<<`), complex keys (`?`), directives and multiple
+//! documents are errors.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::error::{Error, ErrorKind};
+use crate::map::Map;
+use crate::parser::MAX_DEPTH;
+use crate::span::{Position, Span};
+use crate::value::Value;
+
+impl Value {
+    /// Formats the value as a block-style YAML document that
+    /// `Value::from_yaml_str` reads back unchanged.
+    pub fn to_yaml_string(&self) -> String {
+        let mut out = String::new();
+
+        if is_block(self) {
+            write_block(&mut out, self, 0);
+        } else {
+            write_scalar(&mut out, self);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parses a single YAML document.
+    pub fn from_yaml_str(yaml: &str) -> Result<Value, Error> {
+        YamlParser::new(yaml).parse_document()
+    }
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(v) => !v.is_empty(),
+        Value::Object(m) => !m.is_empty(),
+        _ => false,
+    }
+}
+
+fn write_block(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Array(v) => {
+            for item in v {
+                push_indent(out, indent);
+                out.push('-');
+                if is_block(item) {
+                    // Start the item on the same line as its `-`.
+                    let mut block = String::new();
+                    write_block(&mut block, item, indent + 2);
+                    out.push(' ');
+                    out.push_str(&block[indent + 2..]);
+                } else {
+                    out.push(' ');
+                    write_scalar(out, item);
+                    out.push('\n');
+                }
+            }
+        }
+        Value::Object(m) => {
+            for (key, item) in m {
+                push_indent(out, indent);
+                write_string(out, key);
+                out.push(':');
+                if is_block(item) {
+                    out.push('\n');
+                    write_block(out, item, indent + 2);
+                } else {
+                    out.push(' ');
+                    write_scalar(out, item);
+                    out.push('\n');
+                }
+            }
+        }
+        _ => write_scalar(out, value),
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.extend(core::iter::repeat_n(' ', indent));
+}
+
+fn write_scalar(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Boolean(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Integer(i) => {
+            let _ = write!(out, "{}", i);
+        }
+        Value::Float(f) if f.is_nan() => out.push_str(".nan"),
+        Value::Float(f) if f.is_infinite() => out.push_str(if *f > 0.0 { ".inf" } else { "-.inf" }),
+        Value::Float(f) => {
+            let _ = write!(out, "{:?}", f);
+        }
+        Value::String(s) => write_string(out, s),
+        Value::Array(_) => out.push_str("[]"),
+        Value::Object(_) => out.push_str("{}"),
+        Value::Custom(c) => write_scalar(out, &c.to_value()),
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    if is_plain_safe(s) {
+        out.push_str(s);
+        return;
+    }
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() || (c.is_whitespace() && c != ' ') => {
+                let _ = write!(out, "\\u{:04X}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// Whether `s` can be written without quotes and still read back as the same
+// string.
+fn is_plain_safe(s: &str) -> bool {
+    let first = match s.chars().next() {
+        Some(c) => c,
+        None => return false,
+    };
+
+    !"-?:,[]{}#&*!|>'\"%@`".contains(first)
+        && !first.is_whitespace()
+        && !s.starts_with("...")
+        && !s.ends_with(|c: char| c.is_whitespace() || c == ':')
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s
+            .chars()
+            .any(|c| c.is_control() || (c.is_whitespace() && c != ' '))
+        && matches!(resolve_plain(s), Ok(Value::String(_)))
+}
+
+// The core schema's interpretation of an untagged plain scalar.
+fn resolve_plain(s: &str) -> Result<Value, ErrorKind> {
+    let value = match s {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Boolean(true),
+        "false" | "False" | "FALSE" => Value::Boolean(false),
+        ".nan" | ".NaN" | ".NAN" => Value::Float(f64::NAN),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => Value::Float(f64::INFINITY),
+        "-.inf" | "-.Inf" | "-.INF" => Value::Float(f64::NEG_INFINITY),
+        _ if is_int(s) => Value::Integer(s.parse().map_err(|_| ErrorKind::NumberOverflow)?),
+        _ if is_radix_int(s, "0x", 16) => {
+            Value::Integer(i32::from_str_radix(&s[2..], 16).map_err(|_| ErrorKind::NumberOverflow)?)
+        }
+        _ if is_radix_int(s, "0o", 8) => {
+            Value::Integer(i32::from_str_radix(&s[2..], 8).map_err(|_| ErrorKind::NumberOverflow)?)
+        }
+        _ if is_float(s) => Value::Float(s.parse().map_err(|_| ErrorKind::InvalidNumber)?),
+        _ => Value::String(s.into()),
+    };
+    Ok(value)
+}
+
+fn strip_sign(s: &str) -> &str {
+    s.strip_prefix(|c| c == '-' || c == '+').unwrap_or(s)
+}
+
+fn is_digits(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_int(s: &str) -> bool {
+    let digits = strip_sign(s);
+    !digits.is_empty() && is_digits(digits)
+}
+
+fn is_radix_int(s: &str, prefix: &str, radix: u32) -> bool {
+    match s.strip_prefix(prefix) {
+        Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix)),
+        None => false,
+    }
+}
+
+fn is_float(s: &str) -> bool {
+    let s = strip_sign(s);
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], Some(strip_sign(&s[i + 1..]))),
+        None => (s, None),
+    };
+    let (int, frac) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None => (mantissa, ""),
+    };
+
+    is_digits(int)
+        && is_digits(frac)
+        && !(int.is_empty() && frac.is_empty())
+        && exponent.is_none_or(|e| !e.is_empty() && is_digits(e))
+}
+
+// How many values the copies made for aliases may have in all.
+const MAX_ALIAS_VALUES: usize = 1_000_000;
+
+const TAGS: &[&str] = &[
+    "!", "!!str", "!!int", "!!float", "!!bool", "!!null", "!!seq", "!!map",
+];
+
+#[derive(Clone, Copy)]
+struct Checkpoint {
+    index: usize,
+    position: Position,
+}
+
+struct YamlParser {
+    chars: Vec<char>,
+    index: usize,
+    position: Position,
+    // The anchored nodes, with the number of values in each.
+    anchors: Vec<(String, Value, usize)>,
+    // The number of values copied for aliases so far.
+    alias_values: usize,
+    depth: usize,
+}
+
+impl YamlParser {
+    fn new(yaml: &str) -> Self {
+        YamlParser {
+            chars: yaml.chars().collect(),
+            index: 0,
+            position: Position::default(),
+            anchors: Vec::new(),
+            alias_values: 0,
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.chars.get(self.index + n).copied()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.position.advance(c, self.peek_at(1));
+            self.index += 1;
+        }
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            index: self.index,
+            position: self.position,
+        }
+    }
+
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        self.index = checkpoint.index;
+        self.position = checkpoint.position;
+    }
+
+    fn error_at(&self, start: Position, kind: ErrorKind) -> Error {
+        Error::new(kind).with_span(Span {
+            start,
+            end: self.position,
+        })
+    }
+
+    fn custom(&self, start: Position, message: &str) -> Error {
+        self.error_at(start, ErrorKind::Custom(message.into()))
+    }
+
+    fn unexpected(&self) -> Error {
+        let kind = match self.peek() {
+            Some(found) => ErrorKind::UnexpectedCharacter {
+                found,
+                expected: Vec::new(),
+            },
+            None => ErrorKind::UnexpectedEof {
+                expected: Vec::new(),
+            },
+        };
+        let mut end = self.position;
+        if let Some(c) = self.peek() {
+            end.advance(c, self.peek_at(1));
+        }
+        Error::new(kind).with_span(Span {
+            start: self.position,
+            end,
+        })
+    }
+
+    // Errors abandon the whole document, so only successfully parsed
+    // collections need to leave.
+    fn enter(&mut self) -> Result<(), Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error_at(self.position, ErrorKind::ExceededMaxDepth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    // The indentation of the current column; -1 stands for the (virtual)
+    // parent of the document's root node.
+    fn indent(&self) -> isize {
+        self.position.column as isize - 1
+    }
+
+    fn is_space_at(&self, n: usize) -> bool {
+        matches!(
+            self.peek_at(n),
+            None | Some(' ') | Some('\t') | Some('\n') | Some('\r')
+        )
+    }
+
+    fn at_line_end(&self) -> bool {
+        matches!(self.peek(), None | Some('\n') | Some('\r'))
+    }
+
+    fn at_entry(&self) -> bool {
+        self.peek() == Some('-') && self.is_space_at(1)
+    }
+
+    fn at_marker(&self) -> bool {
+        let marker = |c| (0..3).all(|i| self.peek_at(i) == Some(c));
+        self.position.column == 1 && (marker('-') || marker('.')) && self.is_space_at(3)
+    }
+
+    fn at_end(&self) -> bool {
+        self.peek().is_none() || self.at_marker()
+    }
+
+    fn skip_inline_space(&mut self) {
+        while let Some(' ') | Some('\t') = self.peek() {
+            self.bump();
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !self.at_line_end() {
+                self.bump();
+            }
+        }
+    }
+
+    fn skip_break(&mut self) {
+        if self.peek() == Some('\r') {
+            self.bump();
+        }
+        if self.peek() == Some('\n') {
+            self.bump();
+        }
+    }
+
+    // Moves to the next character that isn't whitespace or a comment.
+    fn skip_to_content(&mut self) {
+        loop {
+            self.skip_inline_space();
+            self.skip_comment();
+            if self.peek().is_none() || !self.at_line_end() {
+                return;
+            }
+            self.skip_break();
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Value, Error> {
+        self.skip_to_content();
+        if self.peek() == Some('%') {
+            return Err(self.custom(self.position, "YAML directives are not supported"));
+        }
+        if self.at_marker() && self.peek() == Some('-') {
+            (0..3).for_each(|_| self.bump());
+        }
+
+        let value = self.parse_value_after_indicator(-1, false)?;
+
+        self.skip_to_content();
+        if self.at_marker() && self.peek() == Some('.') {
+            (0..3).for_each(|_| self.bump());
+            self.skip_to_content();
+        }
+        match self.peek() {
+            None => Ok(value),
+            Some('-') if self.at_marker() => {
+                Err(self.custom(self.position, "multiple documents are not supported"))
+            }
+            Some(_) => Err(self.unexpected()),
+        }
+    }
+
+    // Parses the node after a `-`, a `:` or the document start, which may be
+    // on the same line or, more indented than `parent`, on the lines below.
+    fn parse_value_after_indicator(
+        &mut self,
+        parent: isize,
+        after_key: bool,
+    ) -> Result<Value, Error> {
+        self.skip_inline_space();
+        self.skip_comment();
+        if !self.at_line_end() {
+            return self.parse_node(parent, after_key);
+        }
+
+        self.skip_to_content();
+        if self.at_end() {
+            return Ok(Value::Null);
+        }
+        // A sequence may be indented as much as the key it's the value of.
+        let indent = self.indent();
+        if indent > parent || (after_key && indent == parent && self.at_entry()) {
+            self.parse_node(parent, false)
+        } else {
+            Ok(Value::Null)
+        }
+    }
+
+    // `inline` is set for values on the same line as their key, which can't
+    // be block collections.
+    fn parse_node(&mut self, parent: isize, inline: bool) -> Result<Value, Error> {
+        let start = self.position;
+        let (anchor, tag) = self.parse_properties()?;
+        let has_properties = anchor.is_some() || tag.is_some();
+
+        self.skip_comment();
+        let value = if has_properties && self.at_line_end() {
+            // The properties belong to the node on the lines below.
+            self.skip_to_content();
+            if self.at_end() || self.indent() <= parent {
+                self.resolve(String::new(), false, tag.as_deref(), start)?
+            } else {
+                self.parse_bare(parent, false, tag.as_deref(), false)?
+            }
+        } else {
+            self.parse_bare(parent, inline, tag.as_deref(), has_properties)?
+        };
+        self.finish_node(anchor, tag, value, start)
+    }
+
+    fn finish_node(
+        &mut self,
+        anchor: Option<String>,
+        tag: Option<String>,
+        value: Value,
+        start: Position,
+    ) -> Result<Value, Error> {
+        match (tag.as_deref(), &value) {
+            (Some("!"), _)
+            | (Some("!!seq"), Value::Array(_))
+            | (Some("!!map"), Value::Object(_)) => {}
+            (Some(tag), Value::Array(_)) | (Some(tag), Value::Object(_)) => {
+                return Err(self.custom(start, &format!("unsupported tag `{}`", tag)));
+            }
+            _ => {}
+        }
+        if let Some(name) = anchor {
+            self.anchors
+                .push((name, value.clone(), count_values(&value)));
+        }
+        Ok(value)
+    }
+
+    fn parse_properties(&mut self) -> Result<(Option<String>, Option<String>), Error> {
+        let (mut anchor, mut tag) = (None, None);
+
+        loop {
+            match self.peek() {
+                Some('&') if anchor.is_none() => {
+                    self.bump();
+                    anchor = Some(self.parse_name()?);
+                }
+                Some('!') if tag.is_none() => {
+                    let start = self.position;
+                    let name = self.parse_name()?;
+                    if !TAGS.contains(&name.as_str()) {
+                        return Err(self.custom(start, &format!("unsupported tag `{}`", name)));
+                    }
+                    tag = Some(name);
+                }
+                _ => return Ok((anchor, tag)),
+            }
+            self.skip_inline_space();
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String, Error> {
+        let mut name = String::new();
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || ",[]{}".contains(c) {
+                break;
+            }
+            name.push(c);
+            self.bump();
+        }
+        if name.is_empty() {
+            return Err(self.unexpected());
+        }
+        Ok(name)
+    }
+
+    fn parse_alias(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        self.bump();
+        let name = self.parse_name()?;
+
+        let (value, count) = match self
+            .anchors
+            .iter()
+            .rev()
+            .find(|(anchor, _, _)| *anchor == name)
+        {
+            Some((_, value, count)) => (value, *count),
+            None => return Err(self.custom(start, &format!("unknown anchor `{}`", name))),
+        };
+        self.alias_values += count;
+        if self.alias_values > MAX_ALIAS_VALUES {
+            return Err(self.error_at(start, ErrorKind::BudgetExceeded));
+        }
+        Ok(value.clone())
+    }
+
+    fn parse_bare(
+        &mut self,
+        parent: isize,
+        inline: bool,
+        tag: Option<&str>,
+        has_properties: bool,
+    ) -> Result<Value, Error> {
+        let start = self.position;
+        let indent = self.indent();
+
+        match self.peek() {
+            Some('*') => return self.parse_alias(),
+            Some('[') | Some('{') => return self.parse_flow_collection(),
+            Some('|') | Some('>') => return self.parse_block_scalar(parent, tag, start),
+            Some('-') if self.is_space_at(1) => {
+                if inline {
+                    return Err(self.custom(start, "sequence entries are not allowed here"));
+                }
+                return self.parse_block_sequence(indent);
+            }
+            Some('?') if self.is_space_at(1) => {
+                return Err(self.custom(start, "complex keys are not supported"));
+            }
+            _ => {}
+        }
+
+        let (mut text, quoted) = self.parse_scalar_text(false)?;
+
+        self.skip_inline_space();
+        if self.peek() == Some(':') && self.is_space_at(1) {
+            if inline {
+                return Err(self.custom(start, "mapping values are not allowed here"));
+            }
+            if has_properties {
+                return Err(self.custom(start, "properties on mapping keys are not supported"));
+            }
+            return self.parse_block_mapping(indent, text, quoted, start);
+        }
+        if !quoted {
+            self.continue_plain(&mut text, parent);
+        }
+        self.resolve(text, quoted, tag, start)
+    }
+
+    fn parse_block_sequence(&mut self, indent: isize) -> Result<Value, Error> {
+        let mut items = Vec::new();
+        self.enter()?;
+
+        loop {
+            self.bump();
+            items.push(self.parse_value_after_indicator(indent, false)?);
+
+            self.skip_to_content();
+            if self.at_end() || self.indent() != indent || !self.at_entry() {
+                self.depth -= 1;
+                return Ok(Value::Array(items));
+            }
+        }
+    }
+
+    fn parse_block_mapping(
+        &mut self,
+        indent: isize,
+        mut key: String,
+        mut quoted: bool,
+        mut key_start: Position,
+    ) -> Result<Value, Error> {
+        let mut map = Map::new();
+        self.enter()?;
+
+        loop {
+            if !quoted && key == "<<" {
+                return Err(self.custom(key_start, "merge keys are not supported"));
+            }
+            self.bump();
+            let value = self.parse_value_after_indicator(indent, true)?;
+
+            if map.contains_key(&key) {
+                return Err(self.error_at(key_start, ErrorKind::DuplicateKey(key)));
+            }
+            map.insert(key, value);
+
+            self.skip_to_content();
+            if self.at_end() || self.indent() < indent {
+                self.depth -= 1;
+                return Ok(Value::Object(map));
+            }
+            if self.indent() > indent || self.at_entry() {
+                return Err(self.unexpected());
+            }
+            key_start = self.position;
+            let (next, next_quoted) = self.parse_key()?;
+            key = next;
+            quoted = next_quoted;
+        }
+    }
+
+    fn parse_key(&mut self) -> Result<(String, bool), Error> {
+        match self.peek() {
+            Some('?') if self.is_space_at(1) => {
+                return Err(self.custom(self.position, "complex keys are not supported"));
+            }
+            Some(c) if "&!*[{|>".contains(c) => return Err(self.unexpected()),
+            _ => {}
+        }
+
+        let key = self.parse_scalar_text(false)?;
+
+        self.skip_inline_space();
+        if self.peek() == Some(':') && self.is_space_at(1) {
+            Ok(key)
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    // Returns the scalar's text and whether it was quoted.
+    fn parse_scalar_text(&mut self, flow: bool) -> Result<(String, bool), Error> {
+        match self.peek() {
+            Some('"') => Ok((self.parse_double_quoted()?, true)),
+            Some('\'') => Ok((self.parse_single_quoted()?, true)),
+            Some(',') | Some(']') | Some('}') | Some('@') | Some('`') => Err(self.unexpected()),
+            _ => Ok((self.scan_plain(flow), false)),
+        }
+    }
+
+    // Reads the rest of a plain scalar's line.
+    fn scan_plain(&mut self, flow: bool) -> String {
+        let is_flow_indicator = |c: Option<char>| matches!(c, Some(',' | '[' | ']' | '{' | '}'));
+        let mut text = String::new();
+
+        while let Some(c) = self.peek() {
+            let ends = match c {
+                '\n' | '\r' => true,
+                ':' => self.is_space_at(1) || (flow && is_flow_indicator(self.peek_at(1))),
+                '#' => text.ends_with([' ', '\t']),
+                _ => flow && is_flow_indicator(Some(c)),
+            };
+            if ends {
+                break;
+            }
+            text.push(c);
+            self.bump();
+        }
+        text.truncate(text.trim_end().len());
+        text
+    }
+
+    // Folds the lines of a multi-line plain scalar into `text`.
+    fn continue_plain(&mut self, text: &mut String, parent: isize) {
+        loop {
+            let checkpoint = self.checkpoint();
+            let mut breaks = 0;
+
+            self.skip_inline_space();
+            while self.peek().is_some() && self.at_line_end() {
+                self.skip_break();
+                self.skip_inline_space();
+                breaks += 1;
+            }
+            if breaks == 0 || self.at_end() || self.peek() == Some('#') || self.indent() <= parent {
+                self.restore(checkpoint);
+                return;
+            }
+
+            let line = self.scan_plain(false);
+            if self.peek() == Some(':') {
+                self.restore(checkpoint);
+                return;
+            }
+            fold_breaks(text, breaks);
+            text.push_str(&line);
+        }
+    }
+
+    fn parse_double_quoted(&mut self) -> Result<String, Error> {
+        let mut s = String::new();
+        self.bump();
+
+        loop {
+            match self.peek() {
+                None => return Err(self.unexpected()),
+                Some('"') => {
+                    self.bump();
+                    return Ok(s);
+                }
+                Some('\\') => {
+                    let start = self.position;
+                    self.bump();
+                    let c = match self.peek() {
+                        Some(c) => c,
+                        None => return Err(self.unexpected()),
+                    };
+                    self.bump();
+                    let unescaped = match c {
+                        '0' => '\0',
+                        'a' => '\u{7}',
+                        'b' => '\u{8}',
+                        't' | '\t' => '\t',
+                        'n' => '\n',
+                        'v' => '\u{B}',
+                        'f' => '\u{C}',
+                        'r' => '\r',
+                        'e' => '\u{1B}',
+                        ' ' | '"' | '/' | '\\' => c,
+                        'N' => '\u{85}',
+                        '_' => '\u{A0}',
+                        'L' => '\u{2028}',
+                        'P' => '\u{2029}',
+                        'x' => self.parse_hex(2, start)?,
+                        'u' => self.parse_hex(4, start)?,
+                        'U' => self.parse_hex(8, start)?,
+                        '\n' | '\r' => {
+                            // An escaped line break joins the lines.
+                            if c == '\r' && self.peek() == Some('\n') {
+                                self.bump();
+                            }
+                            self.skip_inline_space();
+                            continue;
+                        }
+                        _ => return Err(self.error_at(start, ErrorKind::InvalidEscape)),
+                    };
+                    s.push(unescaped);
+                }
+                Some('\n') | Some('\r') => self.fold_quoted_break(&mut s),
+                Some(c) => {
+                    s.push(c);
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn parse_hex(&mut self, count: usize, start: Position) -> Result<char, Error> {
+        let mut code = 0;
+
+        for _ in 0..count {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(self.error_at(start, ErrorKind::InvalidEscape)),
+            }
+            self.bump();
+        }
+        char::from_u32(code).ok_or_else(|| self.error_at(start, ErrorKind::InvalidEscape))
+    }
+
+    fn parse_single_quoted(&mut self) -> Result<String, Error> {
+        let mut s = String::new();
+        self.bump();
+
+        loop {
+            match self.peek() {
+                None => return Err(self.unexpected()),
+                Some('\'') if self.peek_at(1) == Some('\'') => {
+                    s.push('\'');
+                    self.bump();
+                    self.bump();
+                }
+                Some('\'') => {
+                    self.bump();
+                    return Ok(s);
+                }
+                Some('\n') | Some('\r') => self.fold_quoted_break(&mut s),
+                Some(c) => {
+                    s.push(c);
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn fold_quoted_break(&mut self, s: &mut String) {
+        let mut breaks = 0;
+
+        s.truncate(s.trim_end_matches(&[' ', '\t'][..]).len());
+        while self.peek().is_some() && self.at_line_end() {
+            self.skip_break();
+            self.skip_inline_space();
+            breaks += 1;
+        }
+        fold_breaks(s, breaks);
+    }
+
+    fn parse_block_scalar(
+        &mut self,
+        parent: isize,
+        tag: Option<&str>,
+        start: Position,
+    ) -> Result<Value, Error> {
+        let literal = self.peek() == Some('|');
+        let (mut chomp, mut explicit) = (None, None);
+        self.bump();
+
+        loop {
+            match self.peek() {
+                Some(c @ '-') | Some(c @ '+') if chomp.is_none() => chomp = Some(c),
+                Some(c @ '1'..='9') if explicit.is_none() => explicit = c.to_digit(10),
+                _ => break,
+            }
+            self.bump();
+        }
+        self.skip_inline_space();
+        self.skip_comment();
+        if !self.at_line_end() {
+            return Err(self.unexpected());
+        }
+        self.skip_break();
+
+        let mut indent = explicit.map(|m| parent.max(0) as usize + m as usize);
+        let mut lines = Vec::new();
+
+        while self.peek().is_some() && !self.at_marker() {
+            let spaces = (0..).take_while(|&i| self.peek_at(i) == Some(' ')).count();
+
+            if matches!(self.peek_at(spaces), None | Some('\n') | Some('\r')) {
+                (0..spaces).for_each(|_| self.bump());
+                self.skip_break();
+                lines.push(String::new());
+                continue;
+            }
+            let content = match indent {
+                Some(indent) => indent,
+                None if spaces as isize > parent => spaces,
+                None => break,
+            };
+            indent = Some(content);
+            if spaces < content {
+                break;
+            }
+
+            (0..content).for_each(|_| self.bump());
+            let mut line = String::new();
+            while !self.at_line_end() {
+                line.extend(self.peek());
+                self.bump();
+            }
+            self.skip_break();
+            lines.push(line);
+        }
+
+        let trailing = lines.iter().rev().take_while(|l| l.is_empty()).count();
+        let body = &lines[..lines.len() - trailing];
+        let mut text = if literal {
+            body.join("\n")
+        } else {
+            fold_lines(body)
+        };
+        match chomp {
+            Some('-') => {}
+            Some(_) => {
+                if !body.is_empty() {
+                    text.push('\n');
+                }
+                text.extend(core::iter::repeat_n('\n', trailing));
+            }
+            None if !body.is_empty() => text.push('\n'),
+            None => {}
+        }
+        self.resolve(text, true, tag, start)
+    }
+
+    fn parse_flow_node(&mut self) -> Result<Value, Error> {
+        self.skip_to_content();
+        let start = self.position;
+        let (anchor, tag) = self.parse_properties()?;
+        self.skip_to_content();
+
+        let value = match self.peek() {
+            Some('[') | Some('{') => self.parse_flow_collection()?,
+            Some('*') => self.parse_alias()?,
+            Some(',') | Some(']') | Some('}') if anchor.is_some() || tag.is_some() => {
+                self.resolve(String::new(), false, tag.as_deref(), start)?
+            }
+            None => return Err(self.unexpected()),
+            _ => {
+                let (text, quoted) = self.parse_scalar_text(true)?;
+                self.resolve(text, quoted, tag.as_deref(), start)?
+            }
+        };
+        self.finish_node(anchor, tag, value, start)
+    }
+
+    fn parse_flow_collection(&mut self) -> Result<Value, Error> {
+        let close = if self.peek() == Some('[') { ']' } else { '}' };
+        let mut items = Vec::new();
+        let mut map = Map::new();
+        self.enter()?;
+        self.bump();
+
+        loop {
+            self.skip_to_content();
+            if self.peek() == Some(close) {
+                self.bump();
+                break;
+            }
+
+            if close == ']' {
+                items.push(self.parse_flow_node()?);
+            } else {
+                let key_start = self.position;
+                if self.peek() == Some('?') && self.is_space_at(1) {
+                    return Err(self.custom(key_start, "complex keys are not supported"));
+                }
+                let (key, _) = self.parse_scalar_text(true)?;
+
+                self.skip_to_content();
+                // Entries without a `:` have null values, as in `{ a, b }`.
+                let value = if self.peek() == Some(':') {
+                    self.bump();
+                    self.skip_to_content();
+                    match self.peek() {
+                        Some(',') | Some('}') => Value::Null,
+                        _ => self.parse_flow_node()?,
+                    }
+                } else {
+                    Value::Null
+                };
+                if map.contains_key(&key) {
+                    return Err(self.error_at(key_start, ErrorKind::DuplicateKey(key)));
+                }
+                map.insert(key, value);
+            }
+
+            self.skip_to_content();
+            match self.peek() {
+                Some(',') => self.bump(),
+                Some(c) if c == close => {
+                    self.bump();
+                    break;
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+
+        self.depth -= 1;
+        if close == ']' {
+            Ok(Value::Array(items))
+        } else {
+            Ok(Value::Object(map))
+        }
+    }
+
+    fn resolve(
+        &self,
+        text: String,
+        quoted: bool,
+        tag: Option<&str>,
+        start: Position,
+    ) -> Result<Value, Error> {
+        let value = match tag {
+            None if quoted => return Ok(Value::String(text)),
+            Some("!") | Some("!!str") => return Ok(Value::String(text)),
+            None | Some("!!null") | Some("!!bool") | Some("!!int") | Some("!!float") => {
+                resolve_plain(&text).map_err(|kind| self.error_at(start, kind))?
+            }
+            Some(tag) => return Err(self.custom(start, &format!("unsupported tag `{}`", tag))),
+        };
+
+        match (tag, value) {
+            (Some("!!float"), Value::Integer(i)) => Ok(Value::Float(i.into())),
+            (None, value)
+            | (Some("!!null"), value @ Value::Null)
+            | (Some("!!bool"), value @ Value::Boolean(_))
+            | (Some("!!int"), value @ Value::Integer(_))
+            | (Some("!!float"), value @ Value::Float(_)) => Ok(value),
+            (Some(tag), _) => {
+                Err(self.custom(start, &format!("`{}` is not a valid {}", text, tag)))
+            }
+        }
+    }
+}
+
+// Lines of a folded scalar are joined with spaces, except around blank lines
+// (which each stand for a line break) and more-indented lines (which keep
+// their line breaks).
+fn count_values(value: &Value) -> usize {
+    match value {
+        Value::Array(v) => 1 + v.iter().map(count_values).sum::<usize>(),
+        Value::Object(m) => 1 + m.values().map(count_values).sum::<usize>(),
+        _ => 1,
+    }
+}
+
+fn fold_lines(lines: &[String]) -> String {
+    let is_indented = |line: &str| line.starts_with(' ') || line.starts_with('\t');
+    let mut text = String::new();
+    let mut folded = false;
+
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            let previous = &lines[i - 1];
+            if previous.is_empty() {
+                if folded && is_indented(line) {
+                    text.push('\n');
+                }
+                text.push('\n');
+                folded = false;
+            } else if line.is_empty() {
+                if is_indented(previous) {
+                    text.push('\n');
+                } else {
+                    folded = true;
+                }
+            } else if is_indented(previous) || is_indented(line) {
+                text.push('\n');
+            } else {
+                text.push(' ');
+            }
+        }
+        text.push_str(line);
+    }
+    text
+}
+
+// A single line break becomes a space; otherwise, each of the blank lines
+// that follow it becomes a line break.
+fn fold_breaks(text: &mut String, breaks: usize) {
+    if breaks == 1 {
+        text.push(' ');
+    } else {
+        text.extend(core::iter::repeat_n('\n', breaks.saturating_sub(1)));
+    }
+}
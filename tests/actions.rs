@@ -0,0 +1,99 @@
+#![cfg(feature = "cst")]
+
+extern crate json5;
+
+use json5::{parse, remove_duplicate_keys, sort_keys, to_double_quotes, Pointer};
+
+#[test]
+fn sorting_keys() {
+    let root = Pointer::root();
+    let nested = Pointer::root().key("nested");
+    let input = "// config
+{
+  // the port
+  port: 80, // default
+  'host': 'a', /* inline */
+  nested: {z: 1, y: [2, 1], x: null,},
+
+  alpha: true // last
+}
+";
+    assert_eq!(
+        sort_keys(input, &root).unwrap(),
+        "// config
+{
+  alpha: true, // last
+  'host': 'a', /* inline */
+  nested: {z: 1, y: [2, 1], x: null,},
+
+  // the port
+  port: 80 // default
+}
+"
+    );
+    assert_eq!(
+        sort_keys(input, &nested).unwrap(),
+        input.replace("{z: 1, y: [2, 1], x: null,}", "{x: null, y: [2, 1], z: 1,}")
+    );
+
+    // Already sorted, the document is left exactly as it was.
+    let sorted = sort_keys(input, &root).unwrap();
+    assert_eq!(sort_keys(&sorted, &root).unwrap(), sorted);
+    assert_eq!(sort_keys("{b: 1, a: 2}", &root).unwrap(), "{a: 2, b: 1}");
+    assert_eq!(
+        sort_keys("{b: 1, // one\na: 2}", &root).unwrap(),
+        "{a: 2,\nb: 1 // one\n}"
+    );
+    assert_eq!(
+        sort_keys("{b: 1, // one\r\na: 2}", &root).unwrap(),
+        "{a: 2,\r\nb: 1 // one\r\n}"
+    );
+    let crlf = input.replace('\n', "\r\n");
+    assert_eq!(
+        sort_keys(&crlf, &root).unwrap(),
+        sorted.replace('\n', "\r\n")
+    );
+
+    assert_eq!(
+        sort_keys(r"{\u0063: 1, b: 2, a: 3}", &root).unwrap(),
+        r"{a: 3, b: 2, \u0063: 1}"
+    );
+
+    assert!(sort_keys(input, &nested.clone().key("y")).is_err());
+    assert!(sort_keys(input, &root.clone().key("missing")).is_err());
+    assert!(sort_keys("{a: }", &root).is_err());
+}
+
+#[test]
+fn double_quotes() {
+    let input = r#"{'key': 'it\'s "quoted"', "same": "x", 'e': '\nA'}"#;
+    let output = to_double_quotes(input).unwrap();
+
+    assert_eq!(
+        output,
+        r#"{"key": "it's \"quoted\"", "same": "x", "e": "\nA"}"#
+    );
+    assert_eq!(parse(&output).unwrap(), parse(input).unwrap());
+}
+
+#[test]
+fn duplicate_keys() {
+    let input = "{
+  a: 1, // first
+  b: {c: 1, c: 2},
+  // the one kept
+  a: 3,
+}";
+    let output = remove_duplicate_keys(input).unwrap();
+
+    assert_eq!(
+        output,
+        "{
+  b: {c: 2},
+  // the one kept
+  a: 3,
+}"
+    );
+    assert_eq!(parse(&output).unwrap(), parse(input).unwrap());
+    assert_eq!(remove_duplicate_keys("[1, 2]").unwrap(), "[1, 2]");
+}
@@ -0,0 +1,72 @@
+extern crate json5;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use json5::{Pointer, Value};
+
+// Counts the allocations of each thread, so that those of the test harness
+// don't count.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn lookups() {
+    let value = json5::parse("{ servers: [{ host: 'a', 'x/y~': 1 }], name: 'n' }").unwrap();
+    let pointer = Pointer::parse("/servers/0/host").unwrap();
+    let escaped = Pointer::parse("/servers/0/x~1y~0").unwrap();
+
+    assert_eq!(allocations(|| &value["name"]), (&value["name"], 0));
+    assert_eq!(allocations(|| value.get("name")).1, 0);
+    assert_eq!(allocations(|| value.get_key_value("name")).1, 0);
+    assert_eq!(allocations(|| value.at(["servers", "0", "host"])).1, 0);
+    assert_eq!(allocations(|| &value[&pointer]).1, 0);
+    assert_eq!(allocations(|| value.pointer(&pointer)).1, 0);
+    assert_eq!(allocations(|| value.pointer(&escaped)).1, 0);
+}
+
+#[test]
+fn get_and_pointer() {
+    let value =
+        json5::parse("{ servers: [{ host: 'a', 'x/y~': 1, '~2': 2 }], name: 'n' }").unwrap();
+
+    let (key, name) = value.get_key_value("name").unwrap();
+    assert_eq!((key.as_str(), name), ("name", &Value::String("n".into())));
+    assert_eq!(value.get("name"), Some(&Value::String("n".into())));
+    assert_eq!(value.get("missing"), None);
+    assert_eq!(value["servers"].get("0"), None);
+
+    let pointer = |s| value.pointer(&Pointer::parse(s).unwrap());
+    assert_eq!(pointer(""), Some(&value));
+    assert_eq!(pointer("/servers/0/host"), Some(&Value::String("a".into())));
+    assert_eq!(pointer("/servers/0/x~1y~0"), Some(&Value::Integer(1)));
+    assert_eq!(pointer("/servers/0/~2"), Some(&Value::Integer(2)));
+    assert_eq!(pointer("/servers/0/x~1y"), None);
+    assert_eq!(pointer("/servers/01/host"), None);
+    assert_eq!(pointer("/servers/1"), None);
+    assert_eq!(pointer("/name/x"), None);
+    assert_eq!(Pointer::parse("servers"), None);
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "values")]
+
+extern crate json5;
+
+use std::thread;
+
+use json5::{ArcValue, Value};
+
+#[test]
+fn it_works() {
+    let value = json5::parse("{ 'hosts': ['a', 'b'], 'port': 80, 'name': 'api' }").unwrap();
+    let shared = ArcValue::from(value.clone());
+
+    assert_eq!(shared["hosts"][1], ArcValue::String("b".to_string().into()));
+    assert_eq!(shared["port"], ArcValue::Integer(80));
+    assert_eq!(shared["missing"][0], ArcValue::Null);
+    assert_eq!(Value::from(&shared), value);
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            thread::spawn(move || shared["hosts"].clone())
+        })
+        .collect();
+    for handle in handles {
+        assert!(handle.join().unwrap().ptr_eq(&shared["hosts"]));
+    }
+}
+
+#[test]
+fn copy_on_write() {
+    let original = ArcValue::from(json5::parse("{ 'hosts': ['a'], 'name': 'api' }").unwrap());
+    let mut copy = original.clone();
+
+    assert!(copy.ptr_eq(&original));
+
+    let hosts = copy.make_object_mut().unwrap().get_mut("hosts").unwrap();
+    hosts
+        .make_array_mut()
+        .unwrap()
+        .push(ArcValue::String("b".to_string().into()));
+
+    assert!(!copy.ptr_eq(&original));
+    assert!(copy["name"].ptr_eq(&original["name"]));
+    assert_eq!(original["hosts"][1], ArcValue::Null);
+    assert_eq!(copy["hosts"][1], ArcValue::String("b".to_string().into()));
+
+    assert!(ArcValue::Integer(1).ptr_eq(&ArcValue::Integer(1)));
+    assert!(ArcValue::Integer(1).make_string_mut().is_none());
+    assert!(ArcValue::Null.make_array_mut().is_none());
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "bench")]
+
+extern crate json5;
+
+use json5::bench::{inputs, steps};
+
+#[test]
+fn linear() {
+    for (small, large) in inputs(1_000).into_iter().zip(inputs(100_000)) {
+        // Each character is read at most twice, once more after backtracking.
+        for input in [&small, &large] {
+            let steps = steps(&input.json, &input.options);
+            assert!(
+                steps <= 2 * input.json.chars().count() + 2,
+                "{}: {} steps for {} characters",
+                input.name,
+                steps,
+                input.json.len()
+            );
+        }
+        assert!(large.json.len() >= 50 * small.json.len(), "{}", small.name);
+    }
+}
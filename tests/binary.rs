@@ -0,0 +1,112 @@
+#![cfg(all(feature = "msgpack", feature = "cbor"))]
+
+extern crate json5;
+
+use json5::{parse, ErrorKind, Value};
+
+fn sample() -> Value {
+    parse(
+        r#"{
+            name: 'app',
+            ints: [0, 127, 128, 255, 256, 65535, 65536, -1, -32, -33, -128, -129, -32768, -32769,
+                   2147483647, -2147483648],
+            floats: [0.5, -0.0, 1e300, Infinity, -Infinity],
+            long: 'a string long enough to need more than a fixstr or a single-byte length..',
+            nested: [[], {}, { a: [null, true, false] }],
+        }"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn msgpack() {
+    let value = sample();
+    assert_eq!(Value::from_msgpack(&value.to_msgpack()), Ok(value));
+
+    let nan = Value::from_msgpack(&Value::Float(f64::NAN).to_msgpack()).unwrap();
+    assert!(nan.to_f64().unwrap().is_nan());
+
+    assert_eq!(Value::Integer(-1).to_msgpack(), [0xff]);
+    assert_eq!(Value::Integer(200).to_msgpack(), [0xcc, 200]);
+    assert_eq!(
+        parse("{ a: [1] }").unwrap().to_msgpack(),
+        [0x81, 0xa1, b'a', 0x91, 0x01]
+    );
+
+    // uint64 and float32, which are never written
+    assert_eq!(
+        Value::from_msgpack(&[0xcf, 0, 0, 0, 1, 0, 0, 0, 0]),
+        Ok(Value::Float(4294967296.0))
+    );
+    assert_eq!(
+        Value::from_msgpack(&[0xca, 0x3f, 0xc0, 0, 0]),
+        Ok(Value::Float(1.5))
+    );
+    assert_eq!(
+        Value::from_msgpack(&[0xd1, 0xff, 0x00]),
+        Ok(Value::Integer(-256))
+    );
+
+    let error = |bytes: &[u8]| Value::from_msgpack(bytes).unwrap_err().to_string();
+    assert_eq!(error(&[0x92, 0x01]), "unexpected end of input at byte 2");
+    assert_eq!(error(&[0xc1]), "unsupported MessagePack type at byte 1");
+    assert_eq!(
+        error(&[0x81, 0x01, 0x01]),
+        "map key is not a string at byte 2"
+    );
+    assert_eq!(error(&[0x01, 0x02]), "trailing bytes at byte 1");
+    assert_eq!(
+        Value::from_msgpack(&[0x91; 200]).unwrap_err().kind(),
+        &ErrorKind::ExceededMaxDepth
+    );
+}
+
+#[test]
+fn cbor() {
+    let value = sample();
+    assert_eq!(Value::from_cbor(&value.to_cbor()), Ok(value));
+
+    let nan = Value::from_cbor(&Value::Float(f64::NAN).to_cbor()).unwrap();
+    assert!(nan.to_f64().unwrap().is_nan());
+
+    assert_eq!(Value::Integer(-500).to_cbor(), [0x39, 0x01, 0xf3]);
+    assert_eq!(
+        parse("{ a: [1] }").unwrap().to_cbor(),
+        [0xa1, 0x61, b'a', 0x81, 0x01]
+    );
+
+    // Half floats, tags and undefined, which are never written
+    assert_eq!(Value::from_cbor(&[0xf9, 0x3e, 0x00]), Ok(Value::Float(1.5)));
+    assert_eq!(
+        Value::from_cbor(&[0xf9, 0x00, 0x01]),
+        Ok(Value::Float(5.960464477539063e-8))
+    );
+    assert_eq!(
+        Value::from_cbor(&[0xf9, 0xfc, 0x00]),
+        Ok(Value::Float(f64::NEG_INFINITY))
+    );
+    assert_eq!(
+        Value::from_cbor(&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]),
+        Ok(Value::Integer(1363896240))
+    );
+    assert_eq!(Value::from_cbor(&[0xf7]), Ok(Value::Null));
+    assert_eq!(
+        Value::from_cbor(&[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        Ok(Value::Float(-18446744073709551616.0))
+    );
+
+    let error = |bytes: &[u8]| Value::from_cbor(bytes).unwrap_err().to_string();
+    assert_eq!(
+        error(&[0x9f, 0xff]),
+        "unsupported indefinite-length CBOR item at byte 1"
+    );
+    assert_eq!(
+        error(&[0x41, 0x00]),
+        "unsupported CBOR byte string at byte 1"
+    );
+    assert_eq!(error(&[0x62, 0xff, 0xfe]), "invalid UTF-8 at byte 1");
+    assert_eq!(
+        error(&[0x7a, 0xff, 0xff, 0xff, 0xff]),
+        "unexpected end of input at byte 5"
+    );
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "std")]
+
+extern crate json5;
+
+use std::sync::Arc;
+
+use json5::{cached, Value};
+
+// One test, as the cache is shared by the whole process.
+#[test]
+fn cached() {
+    let a = cached::parse("{a: 1}").unwrap();
+    let b = cached::parse("{a: 1}").unwrap();
+    assert!(Arc::ptr_eq(&a, &b));
+    assert_eq!(*a, json5::parse("{a: 1}").unwrap());
+    assert!(!Arc::ptr_eq(&a, &cached::parse("{a: 1} ").unwrap()));
+    assert_eq!(cached::len(), 2);
+
+    assert!(cached::parse("{a:").is_err());
+    assert_eq!(cached::len(), 2);
+
+    // The least recently used goes first.
+    cached::set_capacity(2);
+    cached::parse("{a: 1}").unwrap();
+    cached::parse("[1]").unwrap();
+    assert_eq!(cached::len(), 2);
+    assert!(Arc::ptr_eq(&a, &cached::parse("{a: 1}").unwrap()));
+    assert_eq!(
+        *cached::parse("[1]").unwrap(),
+        Value::Array(vec![Value::Integer(1)])
+    );
+
+    cached::set_capacity(1);
+    assert_eq!(cached::len(), 1);
+    assert!(!Arc::ptr_eq(&a, &cached::parse("{a: 1}").unwrap()));
+
+    cached::set_capacity(0);
+    assert_eq!(cached::len(), 0);
+    cached::parse("{a: 1}").unwrap();
+    assert_eq!(cached::len(), 0);
+
+    cached::set_capacity(8);
+    cached::parse("{a: 1}").unwrap();
+    cached::clear();
+    assert_eq!(cached::len(), 0);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "cli")]
+
+extern crate json5;
+
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_json5"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Usage errors exit before reading the input.
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn rewrites() {
+    let input = "{b: 1, 'a': 'x', // last\nb: 2}";
+
+    let output = run(&["--dedupe", "--sort-keys", "--double-quotes"], input);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "{\"a\": \"x\", // last\n b: 2}"
+    );
+
+    let output = run(&["--sort-keys=/c"], "{c: {z: 1, y: 2}, a: 3}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "{c: {y: 2, z: 1}, a: 3}"
+    );
+
+    // Without flags, documents are only checked.
+    let output = run(&[], input);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn errors() {
+    let output = run(&["--sort-keys"], "{a: }");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "-: unexpected character `}`, expected a value, line 1 column 5\n"
+    );
+
+    assert_eq!(run(&["--sort-keys=/x"], "[]").status.code(), Some(1));
+    assert_eq!(run(&["--sort-keys=x"], "{}").status.code(), Some(2));
+    assert_eq!(run(&["--unknown"], "{}").status.code(), Some(2));
+    assert_eq!(run(&["--in-place"], "{}").status.code(), Some(2));
+}
+
+#[test]
+fn in_place() {
+    let path = std::env::temp_dir().join(format!("json5-cli-{}.json5", std::process::id()));
+    fs::write(&path, "{b: 1, a: 2}").unwrap();
+
+    let output = run(&["--sort-keys", "--in-place", path.to_str().unwrap()], "");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{a: 2, b: 1}");
+    fs::remove_file(&path).unwrap();
+}
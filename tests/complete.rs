@@ -0,0 +1,79 @@
+#![cfg(feature = "schema")]
+
+extern crate json5;
+
+use json5::{complete, parse, CompletionKind};
+
+const SCHEMA: &str = r##"{
+    type: "object",
+    properties: {
+        name: { type: "string", description: "What to call it" },
+        mode: { enum: ["fast", "safe"], default: "safe" },
+        server: { $ref: "#/$defs/server" },
+        tags: { type: "array", items: { type: ["boolean", "null"] } },
+    },
+    $defs: {
+        server: {
+            allOf: [
+                { properties: { host: { title: "Host name" } } },
+                { properties: { port: { type: "integer" } } },
+            ],
+        },
+    },
+}"##;
+
+fn labels(json: &str) -> Vec<(CompletionKind, String)> {
+    let schema = parse(SCHEMA).unwrap();
+    let offset = json.find('|').unwrap();
+    let json = json.replace('|', "");
+    let mut labels: Vec<_> = complete(&json, offset, &schema)
+        .into_iter()
+        .map(|c| (c.kind, c.label))
+        .collect();
+    labels.sort_by(|a, b| a.1.cmp(&b.1));
+    labels
+}
+
+fn keys(names: &[&str]) -> Vec<(CompletionKind, String)> {
+    names
+        .iter()
+        .map(|n| (CompletionKind::Key, n.to_string()))
+        .collect()
+}
+
+fn values(labels: &[&str]) -> Vec<(CompletionKind, String)> {
+    labels
+        .iter()
+        .map(|l| (CompletionKind::Value, l.to_string()))
+        .collect()
+}
+
+#[test]
+fn keys_in_objects() {
+    assert_eq!(labels("{|}"), keys(&["mode", "name", "server", "tags"]));
+    assert_eq!(
+        labels("{ name: 'x', | mode: 'fast' }"),
+        keys(&["server", "tags"])
+    );
+    assert_eq!(labels("{server: {po|"), keys(&["host", "port"]));
+    assert_eq!(labels("{server: {'host': 'a', |}}"), keys(&["port"]));
+
+    let schema = parse(SCHEMA).unwrap();
+    let completions = complete("{\n  na\n}", 6, &schema);
+    let name = completions.iter().find(|c| c.label == "name").unwrap();
+    assert_eq!(name.insert_text, "\"name\": ");
+    assert_eq!(name.documentation.as_deref(), Some("What to call it"));
+    assert_eq!(name.replace.unwrap().start.offset, 4);
+}
+
+#[test]
+fn values_at_keys() {
+    assert_eq!(labels("{mode: |}"), values(&["\"fast\"", "\"safe\""]));
+    assert_eq!(
+        labels("{tags: [true, |"),
+        values(&["false", "null", "true"])
+    );
+    assert_eq!(labels("|"), values(&["{}"]));
+    assert_eq!(labels("{name: |}"), values(&[]));
+    assert_eq!(labels("{/* | */}"), values(&[]));
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "std")]
+
+extern crate json5;
+
+use std::fs;
+
+use json5::{run_conformance, Expectation, ParseOptions};
+
+#[test]
+fn run_conformance_() {
+    let dir = std::env::temp_dir().join(format!("json5-conformance-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for (file, json) in [
+        ("arrays/empty-array.json", "[]"),
+        ("arrays/trailing-comma-array.json5", "[1,]"),
+        ("arrays/leading-comma-array.js", "[,1]"),
+        ("comments/top-level-block-comment.txt", "/* */"),
+        ("strings/single-quoted-string.json5", "'a'"),
+        ("README.md", "# not a test"),
+    ] {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, json).unwrap();
+    }
+
+    let report = run_conformance(&dir, &ParseOptions::default()).unwrap();
+    assert!(report.is_success(), "{:?}", report.failures);
+    assert_eq!(report.passed, 5);
+
+    let report = run_conformance(&dir, &ParseOptions::strict()).unwrap();
+    assert_eq!(report.passed, 3);
+    let failures: Vec<_> = report
+        .failures
+        .iter()
+        .map(|f| {
+            (
+                f.path.strip_prefix(&dir).unwrap().to_str().unwrap(),
+                f.expected,
+                f.error.is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        failures,
+        [
+            (
+                "arrays/trailing-comma-array.json5",
+                Expectation::Valid,
+                true
+            ),
+            (
+                "strings/single-quoted-string.json5",
+                Expectation::Valid,
+                true
+            ),
+        ]
+    );
+
+    assert!(run_conformance(dir.join("missing"), &ParseOptions::default()).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
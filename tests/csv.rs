@@ -0,0 +1,118 @@
+#![cfg(feature = "csv")]
+
+extern crate json5;
+
+use json5::{parse, to_csv, CsvOptions, Pointer};
+
+fn csv(json: &str, options: &CsvOptions) -> String {
+    to_csv(&parse(json).unwrap(), options).unwrap()
+}
+
+#[test]
+fn to_csv_() {
+    // Rows whose keys are new one at a time, for the header not to depend
+    // on the order of maps.
+    let json = r#"[
+        { name: 'plain' },
+        { price: 1.5 },
+        { name: 'a, "b"' },
+        { price: null },
+        { name: '' },
+        { tags: ['x', 'y'] },
+        { name: 'two\nlines' },
+        { price: true },
+    ]"#;
+    assert_eq!(
+        csv(json, &CsvOptions::default()),
+        "name,price,tags\n\
+         plain,,\n\
+         ,1.5,\n\
+         \"a, \"\"b\"\"\",,\n\
+         ,,\n\
+         \"\",,\n\
+         ,,\"[\"\"x\"\",\"\"y\"\"]\"\n\
+         \"two\nlines\",,\n\
+         ,true,\n"
+    );
+
+    let options = CsvOptions {
+        null: "NULL".into(),
+        ..CsvOptions::tsv()
+    };
+    assert_eq!(
+        csv(
+            "[{ a: 'x,y' }, { a: null }, { a: 'NULL' }, { b: 1 }]",
+            &options
+        ),
+        "a\tb\nx,y\tNULL\nNULL\tNULL\n\"NULL\"\tNULL\nNULL\t1\n"
+    );
+    assert_eq!(csv("[]", &CsvOptions::default()), "\n");
+
+    let error = to_csv(&parse("[{}, 1]").unwrap(), &CsvOptions::default()).unwrap_err();
+    assert_eq!(error.to_string(), "a row must be an object at /1");
+    assert_eq!(error.pointer(), Some(&Pointer::root().index(1)));
+    assert!(to_csv(&parse("{}").unwrap(), &CsvOptions::default()).is_err());
+}
+
+#[test]
+fn from_csv() {
+    let table = "name,price,code,note\r\n\
+                 a,1.5,007,\"x, \"\"y\"\"\"\r\n\
+                 \n\
+                 \"true\",-2,1e3,\n\
+                 true,99999999999,\"\",\"two\nlines\"\n";
+    assert_eq!(
+        json5::from_csv(table.as_bytes(), &CsvOptions::default()),
+        parse(
+            r#"[
+                { name: 'a', price: 1.5, code: '007', note: 'x, "y"' },
+                { name: 'true', price: -2, code: 1000.0, note: null },
+                { name: true, price: 99999999999.0, code: '', note: 'two\nlines' },
+            ]"#
+        )
+    );
+    assert_eq!(
+        json5::from_csv(
+            "a\tb\nNULL\tx\n".as_bytes(),
+            &CsvOptions {
+                null: "NULL".into(),
+                ..CsvOptions::tsv()
+            }
+        ),
+        parse("[{ a: null, b: 'x' }]")
+    );
+    assert_eq!(
+        json5::from_csv(&b""[..], &CsvOptions::default()),
+        parse("[]")
+    );
+
+    let json = "[{ a: 'NULL' }, { a: null }, { a: 1 }, { a: '1' }, { a: 'x\"' }]";
+    let options = CsvOptions {
+        null: "NULL".into(),
+        ..CsvOptions::default()
+    };
+    let table = csv(json, &options);
+    assert_eq!(
+        json5::from_csv(table.as_bytes(), &options),
+        parse("[{ a: 'NULL' }, { a: null }, { a: 1 }, { a: 1 }, { a: 'x\"' }]")
+    );
+
+    let error = |table: &str| {
+        json5::from_csv(table.as_bytes(), &CsvOptions::default())
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        error("a,b\n1\n"),
+        "expected 2 fields, found 1, line 2 column 1"
+    );
+    assert_eq!(error("a,a\n"), "duplicate key `a`, line 1 column 3");
+    assert_eq!(
+        error("a\n\"x\"y\n"),
+        "unexpected character `y`, expected `,`, line 2 column 4"
+    );
+    assert_eq!(
+        error("a\n\"x\n"),
+        "unexpected end of JSON, expected `\"`, line 2 column 1"
+    );
+}
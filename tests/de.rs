@@ -0,0 +1,644 @@
+#![cfg(feature = "serde")]
+
+extern crate json5;
+
+use json5::{from_str, from_str_with_unused, ErrorKind, Pointer, Position, Span, UnusedKey};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Config {
+    name: String,
+    server: Server,
+    tags: Vec<String>,
+    limits: HashMap<String, f64>,
+    debug: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn it_works() {
+    let config: Config = from_str(
+        r#"
+        // service configuration
+        {
+            "name": 'api',
+            "server": { "host": "0.0.0.0", "port": 8080, },
+            "tags": ["a", "b"],
+            "limits": { "cpu": 1.5, "memory": 512 },
+            "debug": null,
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            name: "api".to_string(),
+            server: Server {
+                host: "0.0.0.0".to_string(),
+                port: 8080,
+            },
+            tags: vec!["a".to_string(), "b".to_string()],
+            limits: vec![("cpu".to_string(), 1.5), ("memory".to_string(), 512.0)]
+                .into_iter()
+                .collect(),
+            debug: None,
+        }
+    );
+}
+
+#[test]
+fn invalid_type() {
+    let error = from_str::<Config>(
+        "{
+  'name': 'api',
+  'server': {
+    'host': 'localhost',
+    'port': 'http',
+  },
+}",
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.kind(),
+        &ErrorKind::Custom("invalid type: string \"http\", expected u16".to_string())
+    );
+    assert_eq!(error.pointer(), Pointer::parse("/server/port").as_ref());
+    assert_eq!(
+        error.span(),
+        Some(Span {
+            start: Position {
+                offset: 70,
+                line: 5,
+                column: 13,
+            },
+            end: Position {
+                offset: 76,
+                line: 5,
+                column: 19,
+            },
+        })
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid type: string \"http\", expected u16 at /server/port, line 5 column 13"
+    );
+}
+
+#[test]
+fn out_of_range() {
+    let error =
+        from_str::<Vec<Server>>("[{ 'host': 'a', 'port': 1 }, { 'host': 'b', 'port': 70000 }]")
+            .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "invalid value: integer `70000`, expected u16 at /1/port, line 1 column 53"
+    );
+}
+
+#[test]
+fn missing_field() {
+    let error = from_str::<Server>("\n\n  { 'host': 'a/b~c' }").unwrap_err();
+
+    assert_eq!(error.to_string(), "missing field `port`, line 3 column 3");
+
+    let error = from_str::<HashMap<String, Server>>("{ 'a/b~c': { 'host': 'x' } }").unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "missing field `port` at /a~1b~0c, line 1 column 12"
+    );
+}
+
+#[test]
+fn syntax_error() {
+    assert_eq!(
+        from_str::<Vec<i32>>("[1, 2").unwrap_err().to_string(),
+        "unexpected end of JSON, expected `,` or `]`, line 1 column 6"
+    );
+    assert_eq!(
+        from_str::<Vec<i32>>("[1, 2] 3").unwrap_err().to_string(),
+        "unexpected character `3`, expected end of input, line 1 column 8"
+    );
+    assert_eq!(
+        from_str::<Vec<Server>>("[{ 'host': 'a', 'port': 1 }, { 'host': 'b', 'port': 08 }]")
+            .unwrap_err()
+            .to_string(),
+        "invalid number at /1/port, line 1 column 53"
+    );
+}
+
+#[test]
+fn unused_keys() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Retry {
+        #[serde(default)]
+        timeout: u32,
+        attempts: u32,
+    }
+
+    let (retry, unused) = from_str_with_unused::<Retry>(
+        "{
+  'timout': 30,
+  'attempts': 3,
+  'extra': { 'nested': [1, 2] },
+}",
+    )
+    .unwrap();
+
+    assert_eq!(
+        retry,
+        Retry {
+            timeout: 0,
+            attempts: 3
+        }
+    );
+    assert_eq!(
+        unused,
+        vec![
+            UnusedKey {
+                pointer: Pointer::root().key("timout"),
+                span: Span {
+                    start: Position {
+                        offset: 4,
+                        line: 2,
+                        column: 3,
+                    },
+                    end: Position {
+                        offset: 12,
+                        line: 2,
+                        column: 11,
+                    },
+                },
+            },
+            UnusedKey {
+                pointer: Pointer::root().key("extra"),
+                span: Span {
+                    start: Position {
+                        offset: 37,
+                        line: 4,
+                        column: 3,
+                    },
+                    end: Position {
+                        offset: 44,
+                        line: 4,
+                        column: 10,
+                    },
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn transcode() {
+    let json = "// comment\n{ name: 'app', ports: [80, 443,], ratio: .5, nested: { ok: true, none: null } }";
+    let mut out = Vec::new();
+    json5::transcode(json, &mut serde_json::Serializer::new(&mut out)).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        r#"{"name":"app","ports":[80,443],"ratio":0.5,"nested":{"ok":true,"none":null}}"#
+    );
+
+    let mut out = Vec::new();
+    let error =
+        json5::transcode("[1, 2,, 3]", &mut serde_json::Serializer::new(&mut out)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unexpected character `,`, expected a value at /2, line 1 column 7"
+    );
+
+    let mut out = Vec::new();
+    let error = json5::transcode("1 2", &mut serde_json::Serializer::new(&mut out)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unexpected character `2`, expected end of input, line 1 column 3"
+    );
+}
+
+#[test]
+fn enums() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum External {
+        Unit,
+        Newtype(u32),
+        Tuple(i32, String),
+        Struct { port: u16 },
+    }
+
+    assert_eq!(
+        from_str::<Vec<External>>(
+            "['Unit', { Unit: null }, { Newtype: 1 }, { Tuple: [-2, 'b'] }, { 'Struct': { port: 80 } }]"
+        )
+        .unwrap(),
+        vec![
+            External::Unit,
+            External::Unit,
+            External::Newtype(1),
+            External::Tuple(-2, "b".to_string()),
+            External::Struct { port: 80 },
+        ]
+    );
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    enum Internal {
+        File { path: String },
+        Socket { port: u16, tls: bool },
+        Stdout,
+    }
+
+    assert_eq!(
+        from_str::<Vec<Internal>>(
+            "[
+  // the type may come after the fields
+  { path: '/tmp/log', type: 'file' },
+  { type: 'socket', port: 514, tls: false, },
+  { type: \"stdout\" },
+]"
+        )
+        .unwrap(),
+        vec![
+            Internal::File {
+                path: "/tmp/log".to_string()
+            },
+            Internal::Socket {
+                port: 514,
+                tls: false
+            },
+            Internal::Stdout,
+        ]
+    );
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(tag = "t", content = "c")]
+    enum Adjacent {
+        Unit,
+        Newtype(f64),
+        Tuple(u8, u8),
+        Struct { name: String },
+    }
+
+    assert_eq!(
+        from_str::<Vec<Adjacent>>(
+            "[{ t: 'Unit' }, { c: .5, t: 'Newtype' }, { t: 'Tuple', c: [1, 2] }, { t: 'Struct', c: { name: 'x' } }]"
+        )
+        .unwrap(),
+        vec![
+            Adjacent::Unit,
+            Adjacent::Newtype(0.5),
+            Adjacent::Tuple(1, 2),
+            Adjacent::Struct {
+                name: "x".to_string()
+            },
+        ]
+    );
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(untagged)]
+    enum Untagged {
+        Integer(i64),
+        Float(f64),
+        Text(String),
+        List(Vec<Untagged>),
+        Server { host: String, port: u16 },
+        Nothing,
+    }
+
+    assert_eq!(
+        from_str::<Vec<Untagged>>("[1, -Infinity, 'two', [0x10], { host: 'h', port: 1 }, null]")
+            .unwrap(),
+        vec![
+            Untagged::Integer(1),
+            Untagged::Float(f64::NEG_INFINITY),
+            Untagged::Text("two".to_string()),
+            Untagged::List(vec![Untagged::Integer(16)]),
+            Untagged::Server {
+                host: "h".to_string(),
+                port: 1
+            },
+            Untagged::Nothing,
+        ]
+    );
+}
+
+#[test]
+fn enum_errors() {
+    #[derive(Debug, Deserialize)]
+    #[serde(tag = "type")]
+    enum Output {
+        #[allow(dead_code)]
+        File { path: String },
+    }
+
+    assert_eq!(
+        from_str::<Vec<Output>>("[{ type: 'File', path: '/' }, { type: 'Pipe' }]")
+            .unwrap_err()
+            .to_string(),
+        "unknown variant `Pipe`, expected `File` at /1/type, line 1 column 39"
+    );
+    assert_eq!(
+        from_str::<Output>("{ type: 'File' }")
+            .unwrap_err()
+            .to_string(),
+        "missing field `path`, line 1 column 1"
+    );
+
+    #[derive(Debug, Deserialize)]
+    enum Mode {
+        Fast,
+    }
+
+    assert_eq!(
+        from_str::<Mode>("{ Fast: null, Slow: null }")
+            .unwrap_err()
+            .to_string(),
+        "unexpected character `S`, expected `}`, line 1 column 15"
+    );
+}
+
+#[test]
+fn borrowed() {
+    use std::borrow::Cow;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Entry<'a> {
+        name: &'a str,
+        #[serde(borrow)]
+        note: Cow<'a, str>,
+        #[serde(borrow)]
+        labels: HashMap<&'a str, &'a str>,
+        data: &'a [u8],
+    }
+
+    let json =
+        r#"{ name: 'café', note: "line\nbreak", labels: { env: 'prod', "a b": "" }, data: 'ab' }"#;
+    let entry: Entry = from_str(json).unwrap();
+
+    assert_eq!(entry.name, "café");
+    assert_eq!(entry.note, "line\nbreak");
+    assert!(matches!(entry.note, Cow::Owned(_)));
+    assert_eq!(entry.labels["env"], "prod");
+    assert_eq!(entry.labels["a b"], "");
+    assert_eq!(entry.data, b"ab");
+
+    // Only strings without escapes can be borrowed.
+    assert_eq!(
+        from_str::<&str>(r#""tab\t""#).unwrap_err().to_string(),
+        "invalid type: string \"tab\\t\", expected a borrowed string, line 1 column 1"
+    );
+
+    let json = "{ name: '', note: 'plain', labels: {}, data: '' }";
+    let entry: Entry = from_str(json).unwrap();
+    assert!(matches!(entry.note, Cow::Borrowed("plain")));
+}
+
+#[test]
+fn array_iter() {
+    let json = "// export\n[{ host: 'a', port: 1 }, { host: 'b', port: 2 },]\n";
+    let servers = json5::Deserializer::from_str(json)
+        .into_iter::<Server>()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        servers,
+        vec![
+            Server {
+                host: "a".to_string(),
+                port: 1
+            },
+            Server {
+                host: "b".to_string(),
+                port: 2
+            },
+        ]
+    );
+
+    assert_eq!(
+        json5::Deserializer::from_str("[]")
+            .into_iter::<u8>()
+            .count(),
+        0
+    );
+
+    let results: Vec<_> = json5::Deserializer::from_str("[1, 300, 3]")
+        .into_iter::<u8>()
+        .map(|r| r.map_err(|e| e.to_string()))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            Ok(1),
+            Err("invalid value: integer `300`, expected u8 at /1, line 1 column 5".to_string())
+        ]
+    );
+
+    let results: Vec<_> = json5::Deserializer::from_str("[1] 2")
+        .into_iter::<u8>()
+        .map(|r| r.map_err(|e| e.to_string()))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            Ok(1),
+            Err("unexpected character `2`, expected end of input, line 1 column 5".to_string())
+        ]
+    );
+
+    let mut iter = json5::Deserializer::from_str("{}").into_iter::<u8>();
+    assert_eq!(
+        iter.next().unwrap().unwrap_err().to_string(),
+        "unexpected character `{`, expected `[`, line 1 column 1"
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn error_path() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Deployment {
+        services: Vec<Service>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Service {
+        name: String,
+        backoff: Option<Backoff>,
+        labels: Option<HashMap<String, u8>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Backoff {
+        retries: u32,
+    }
+
+    let error = from_str::<Deployment>(
+        "{ services: [{ name: 'a' }, { name: 'b' }, { name: 'c', backoff: {} }] }",
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.pointer(),
+        Pointer::parse("/services/2/backoff").as_ref()
+    );
+    assert_eq!(error.path().as_deref(), Some("services[2].backoff"));
+    assert_eq!(
+        format!("{} at {}", error.kind(), error.path().unwrap()),
+        "missing field `retries` at services[2].backoff"
+    );
+
+    let error =
+        from_str::<Deployment>("{ services: [{ name: 'a', labels: { 'app/name': 'x' } }] }")
+            .unwrap_err();
+    assert_eq!(
+        error.path().as_deref(),
+        Some(r#"services[0].labels["app/name"]"#)
+    );
+
+    let error = from_str::<Deployment>("[]").unwrap_err();
+    assert_eq!(error.path().as_deref(), Some(""));
+    assert_eq!(json5::parse("[").unwrap_err().path(), None);
+}
+
+#[test]
+fn optional_field() {
+    use json5::OptionalField;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Port(u16);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Overrides {
+        #[serde(default)]
+        port: OptionalField<Port>,
+        #[serde(default)]
+        host: OptionalField<String>,
+        #[serde(default)]
+        proxy: OptionalField<String>,
+    }
+
+    let overrides: Overrides = from_str("{ port: 8080, host: null }").unwrap();
+    assert_eq!(
+        overrides,
+        Overrides {
+            port: OptionalField::Present(Port(8080)),
+            host: OptionalField::Null,
+            proxy: OptionalField::Absent,
+        }
+    );
+
+    let base = OptionalField::Present("localhost".to_string());
+    assert_eq!(overrides.host.clone().or(base.clone()), OptionalField::Null);
+    assert_eq!(overrides.proxy.clone().or(base.clone()), base);
+    assert_eq!(overrides.port.present(), Some(Port(8080)));
+
+    let error = from_str::<Overrides>("{ host: 1 }").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid type: integer `1`, expected a string at /host, line 1 column 9"
+    );
+
+    #[derive(serde::Serialize)]
+    struct Patch {
+        #[serde(skip_serializing_if = "OptionalField::is_absent")]
+        host: OptionalField<&'static str>,
+        #[serde(skip_serializing_if = "OptionalField::is_absent")]
+        proxy: OptionalField<&'static str>,
+        #[serde(skip_serializing_if = "OptionalField::is_absent")]
+        user: OptionalField<&'static str>,
+    }
+
+    let patch = Patch {
+        host: OptionalField::Present("h"),
+        proxy: OptionalField::Null,
+        user: OptionalField::Absent,
+    };
+    assert_eq!(
+        serde_json::to_string(&patch).unwrap(),
+        r#"{"host":"h","proxy":null}"#
+    );
+}
+
+#[test]
+fn key_matching() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Retry {
+        max_retries: u32,
+        #[serde(default)]
+        backoff_ms: u32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Policy {
+        Retry { max_retries: u32 },
+    }
+
+    let json = "{ 'Max-Retries': 3, BACKOFF_MS: 100, extra: 1 }";
+    let mut de = json5::Deserializer::from_str(json).with_key_matching(json5::KeyMatching::loose());
+    assert_eq!(
+        Retry::deserialize(&mut de).unwrap(),
+        Retry {
+            max_retries: 3,
+            backoff_ms: 100
+        }
+    );
+    de.end().unwrap();
+    assert_eq!(de.unused_keys().len(), 1);
+    assert_eq!(de.unused_keys()[0].pointer, Pointer::root().key("extra"));
+
+    // Keys that both match a field are duplicates.
+    let json = "{ 'max-retries': 3, max_retries: 4 }";
+    let mut de = json5::Deserializer::from_str(json).with_key_matching(json5::KeyMatching::loose());
+    assert_eq!(
+        Retry::deserialize(&mut de).unwrap_err().to_string(),
+        "duplicate field `max_retries`, line 1 column 1"
+    );
+
+    let json = "[{ Retry: { MaxRetries: 1 } }]";
+    let mut de = json5::Deserializer::from_str(json).with_key_matching(json5::KeyMatching {
+        ignore_case: true,
+        ignore_separators: false,
+    });
+    let error = Vec::<Policy>::deserialize(&mut de).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "missing field `max_retries` at /0/Retry, line 1 column 11"
+    );
+    let json = "[{ Retry: { Max_Retries: 1 } }]";
+    let mut de = json5::Deserializer::from_str(json).with_key_matching(json5::KeyMatching {
+        ignore_case: true,
+        ignore_separators: false,
+    });
+    assert_eq!(
+        Vec::<Policy>::deserialize(&mut de).unwrap(),
+        vec![Policy::Retry { max_retries: 1 }]
+    );
+
+    // Keys are matched exactly by default.
+    assert!(from_str::<Retry>("{ 'Max-Retries': 3 }").is_err());
+}
+
+#[test]
+fn serialize_error() {
+    let error = from_str::<Config>("{ name: 'app', server: { port: 'x' } }").unwrap_err();
+    let json = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(json["kind"], error.kind().code());
+    assert_eq!(json["message"], error.kind().to_string());
+    assert_eq!(json["pointer"], "/server/port");
+    assert_eq!(json["path"], "server.port");
+    assert!(json["name"].is_null());
+    assert_eq!(json["line"], error.span().unwrap().start.line);
+    assert_eq!(json.as_object().unwrap().len(), 7);
+}
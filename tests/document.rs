@@ -0,0 +1,122 @@
+#![cfg(feature = "cst")]
+
+extern crate json5;
+
+use std::collections::HashMap;
+
+use json5::{
+    parse_with_key_spans, parse_with_spans, parse_with_warnings, Document, ParseOptions, Pointer,
+};
+
+fn pointer(s: &str) -> Pointer {
+    Pointer::parse(s).unwrap()
+}
+
+// A document edited step by step must end up as if parsed afresh.
+fn assert_fresh(document: &Document) {
+    let text = document.text();
+    match parse_with_warnings(text, &ParseOptions::default()) {
+        Ok((value, warnings)) => {
+            assert_eq!(document.value(), Some(&value), "{}", text);
+            assert_eq!(document.warnings(), &warnings[..], "{}", text);
+            // With duplicate keys, the last span is the one kept.
+            let spans: HashMap<_, _> = parse_with_spans(text).unwrap().1.into_iter().collect();
+            for (pointer, span) in spans {
+                assert_eq!(
+                    document.span(&pointer),
+                    Some(span),
+                    "{} in {}",
+                    pointer,
+                    text
+                );
+            }
+            let key_spans: HashMap<_, _> =
+                parse_with_key_spans(text).unwrap().1.into_iter().collect();
+            for (pointer, span) in key_spans {
+                assert_eq!(
+                    document.key_span(&pointer),
+                    Some(span),
+                    "{} in {}",
+                    pointer,
+                    text
+                );
+            }
+        }
+        Err(error) => assert_eq!(document.error(), Some(&error)),
+    }
+}
+
+#[test]
+fn it_works() {
+    let mut document = Document::new("{\n  a: [1, 2],\n  b: {c: true},\n  d: 'x',\n}".into());
+    assert!(document.error().is_none());
+
+    let edits: &[(&str, &str)] = &[
+        ("2", "2, 3,\n    4"),
+        ("true", "false"),
+        ("c:", "c: 1, c:"),
+        ("{c: 1, c: false}", "[]"),
+        ("'x'", "'x' /* comment */"),
+        ("[]", "[}"),
+        ("[}", "{e: null}"),
+        ("a: ", ""),
+        ("\n  d", " d"),
+    ];
+    for (from, to) in edits {
+        let start = document.text().find(from).unwrap();
+        let result = document
+            .apply_edit(start..start + from.len(), to)
+            .map(<[_]>::len)
+            .map_err(Clone::clone);
+        assert_fresh(&document);
+        assert_eq!(result.is_err(), document.error().is_some());
+    }
+    assert_eq!(
+        document.text(),
+        "{\n  [1, 2, 3,\n    4],\n  b: {e: null}, d: 'x' /* comment */,\n}"
+    );
+    assert!(document.error().is_some());
+
+    let start = document.text().find("[1").unwrap();
+    document.apply_edit(start..start, "a: ").unwrap();
+    assert_fresh(&document);
+    assert_eq!(
+        document.value_at(start + 4).unwrap().0,
+        &Pointer::root().key("a").index(0)
+    );
+}
+
+#[test]
+fn extract() {
+    let text = "// settings
+{
+  name: 'x',
+  // where to listen
+  /* the server */ server: {
+    port: 80, // default
+  }, // done
+  list: [1, /* two */ 2],
+}
+";
+    let document = Document::new(text.into());
+
+    assert_eq!(
+        document.extract(&pointer("/server")).unwrap(),
+        "// where to listen
+  /* the server */ server: {
+    port: 80, // default
+  } // done"
+    );
+    assert_eq!(
+        document.extract(&pointer("/server/port")).unwrap(),
+        "port: 80 // default"
+    );
+    assert_eq!(document.extract(&pointer("/name")).unwrap(), "name: 'x'");
+    assert_eq!(
+        document.extract(&pointer("/list/1")).unwrap(),
+        "/* two */ 2"
+    );
+    assert_eq!(document.extract(&pointer("")).unwrap(), text);
+    assert_eq!(document.extract(&pointer("/missing")), None);
+    assert_eq!(Document::new("{a: }".into()).extract(&pointer("/a")), None);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "ffi")]
+
+extern crate json5;
+
+use json5::ffi::*;
+use std::ffi::CString;
+use std::ptr;
+use std::slice;
+
+#[test]
+fn parse_and_read() {
+    let input = CString::new("{ 'name': 'engine', 'sizes': [1, 2.5], 'debug': true }").unwrap();
+
+    unsafe {
+        let mut error = Json5Error::NullPointer;
+        let root = json5_parse(input.as_ptr(), &mut error);
+
+        assert!(!root.is_null());
+        assert_eq!(error, Json5Error::Ok);
+        assert_eq!(json5_type_of(root), Json5Type::Object);
+        assert_eq!(json5_len(root), 3);
+
+        let key = CString::new("name").unwrap();
+        let name = json5_object_get(root, key.as_ptr());
+        let mut len = 0;
+        let bytes = json5_get_string(name, &mut len) as *const u8;
+        assert_eq!(slice::from_raw_parts(bytes, len), b"engine");
+
+        let key = CString::new("sizes").unwrap();
+        let sizes = json5_object_get(root, key.as_ptr());
+        let mut i = 0;
+        let mut f = 0.0;
+        assert!(json5_get_int(json5_array_get(sizes, 0), &mut i));
+        assert!(json5_get_double(json5_array_get(sizes, 1), &mut f));
+        assert_eq!((i, f), (1, 2.5));
+        assert!(json5_array_get(sizes, 2).is_null());
+
+        let key = CString::new("debug").unwrap();
+        let mut b = false;
+        assert!(json5_get_bool(json5_object_get(root, key.as_ptr()), &mut b));
+        assert!(b);
+
+        let mut key = ptr::null();
+        let mut key_len = 0;
+        let mut seen = Vec::new();
+        for index in 0..json5_len(root) {
+            assert!(!json5_object_entry(root, index, &mut key, &mut key_len).is_null());
+            seen.push(slice::from_raw_parts(key as *const u8, key_len).to_vec());
+        }
+        seen.sort();
+        assert_eq!(
+            seen,
+            vec![b"debug".to_vec(), b"name".to_vec(), b"sizes".to_vec()]
+        );
+
+        json5_free(root);
+    }
+}
+
+#[test]
+fn parse_error() {
+    let input = CString::new("[1, 2").unwrap();
+
+    unsafe {
+        let mut error = Json5Error::Ok;
+
+        assert!(json5_parse(input.as_ptr(), &mut error).is_null());
+        assert_eq!(error, Json5Error::UnexpectedEndOfJson);
+        assert!(json5_parse(ptr::null(), &mut error).is_null());
+        assert_eq!(error, Json5Error::NullPointer);
+    }
+}
@@ -0,0 +1,250 @@
+#![cfg(feature = "format")]
+
+extern crate json5;
+
+use json5::{
+    detect_style, reformat, reformat_with_options, tokenize, FormatOptions, Indent, LineEnding,
+    SyntaxKind, TrailingCommas,
+};
+
+#[test]
+fn tokens() {
+    let input = "{a: +.5e-3, 'b\\'': [true, /* c */ NaN], // d\n}";
+    let tokens = tokenize(input);
+
+    assert_eq!(
+        tokens.iter().map(|t| t.text(input)).collect::<String>(),
+        input
+    );
+    let significant: Vec<(SyntaxKind, &str)> = tokens
+        .iter()
+        .filter(|t| t.kind != SyntaxKind::Whitespace)
+        .map(|t| (t.kind, t.text(input)))
+        .collect();
+    assert_eq!(
+        significant,
+        vec![
+            (SyntaxKind::LeftBrace, "{"),
+            (SyntaxKind::Identifier, "a"),
+            (SyntaxKind::Colon, ":"),
+            (SyntaxKind::Number, "+.5e-3"),
+            (SyntaxKind::Comma, ","),
+            (SyntaxKind::String, "'b\\''"),
+            (SyntaxKind::Colon, ":"),
+            (SyntaxKind::LeftBracket, "["),
+            (SyntaxKind::Identifier, "true"),
+            (SyntaxKind::Comma, ","),
+            (SyntaxKind::BlockComment, "/* c */"),
+            (SyntaxKind::Number, "NaN"),
+            (SyntaxKind::RightBracket, "]"),
+            (SyntaxKind::Comma, ","),
+            (SyntaxKind::LineComment, "// d"),
+            (SyntaxKind::RightBrace, "}"),
+        ]
+    );
+    assert_eq!(tokens[4].span.start.column, 5);
+}
+
+#[test]
+fn bad_tokens() {
+    let input = "[#, 'open\n/* never closed";
+    let kinds: Vec<SyntaxKind> = tokenize(input).iter().map(|t| t.kind).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            SyntaxKind::LeftBracket,
+            SyntaxKind::Error,
+            SyntaxKind::Comma,
+            SyntaxKind::Whitespace,
+            SyntaxKind::Error,
+            SyntaxKind::Whitespace,
+            SyntaxKind::Error,
+        ]
+    );
+}
+
+#[test]
+fn it_works() {
+    let input =
+        "// settings\n{a:1,b:[ ],\n\n\n  c: [0x10, 'x',], // last\n  d: {e: null /* none */}}";
+    assert_eq!(
+        reformat(input).unwrap(),
+        "// settings
+{
+  a: 1,
+  b: [],
+
+  c: [
+    0x10,
+    'x',
+  ], // last
+  d: {
+    e: null /* none */
+  }
+}
+"
+    );
+
+    let formatted = reformat(input).unwrap();
+    assert_eq!(reformat(&formatted).unwrap(), formatted);
+    assert_eq!(reformat("  42 ").unwrap(), "42\n");
+    assert!(reformat("{a: }").is_err());
+}
+
+#[test]
+fn detected_style() {
+    let input = "{\r\n    a: [\r\n        1,\r\n    ],\r\n    b: {c: 2},\r\n}";
+    assert_eq!(
+        detect_style(input),
+        FormatOptions {
+            indent: Some(Indent::Spaces(4)),
+            line_ending: Some(LineEnding::CrLf),
+            ..FormatOptions::default()
+        }
+    );
+    assert_eq!(
+        reformat(input).unwrap(),
+        "{\r\n    a: [\r\n        1,\r\n    ],\r\n    b: {\r\n        c: 2\r\n    },\r\n}\r\n"
+    );
+
+    let tabs = "{\n\ta: 1,\n\t/*\n   * b\n   */\n\tb: [2],\n}";
+    assert_eq!(detect_style(tabs).indent, Some(Indent::Tab));
+    assert_eq!(
+        reformat(tabs).unwrap(),
+        "{\n\ta: 1,\n\t/*\n   * b\n   */\n\tb: [\n\t\t2\n\t],\n}\n"
+    );
+    assert_eq!(
+        reformat("// a\r[1,\r\r2]").unwrap(),
+        "// a\r[\r  1,\r\r  2\r]\r"
+    );
+    assert_eq!(
+        detect_style("[1, 2]"),
+        FormatOptions {
+            indent: Some(Indent::Spaces(2)),
+            line_ending: Some(LineEnding::Lf),
+            ..FormatOptions::default()
+        }
+    );
+
+    let options = FormatOptions {
+        indent: Some(Indent::Spaces(3)),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        reformat_with_options(input, &options).unwrap(),
+        "{\r\n   a: [\r\n      1,\r\n   ],\r\n   b: {\r\n      c: 2\r\n   },\r\n}\r\n"
+    );
+}
+
+#[test]
+fn comment_style() {
+    let input = "//////////////
+//settings
+//////////////
+{
+  /* The host
+   * to bind. */
+  host: 'a', //trailing
+  port: 80, /* inline */
+  /* ==== ports ==== */
+  ports: [], ///doc
+}";
+    let options = FormatOptions {
+        block_to_line_comments: true,
+        space_after_line_comment: true,
+        trailing_comment_column: Some(16),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        reformat_with_options(input, &options).unwrap(),
+        "//////////////
+// settings
+//////////////
+{
+  // The host
+  // to bind.
+  host: 'a',   // trailing
+  port: 80,    /* inline */
+  /* ==== ports ==== */
+  ports: [],   /// doc
+}
+"
+    );
+    assert_eq!(reformat(input).unwrap(), format!("{}\n", input));
+
+    let options = FormatOptions {
+        block_to_line_comments: true,
+        trailing_comment_column: Some(4),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        reformat_with_options("[\n  /*\n    a\n\n    b\n  */\n  1, //c\n]", &options).unwrap(),
+        "[\n  // a\n  //\n  // b\n  1, //c\n]\n"
+    );
+}
+
+#[test]
+fn trailing_commas() {
+    let input = "{a: [1, [], {b: 2,}], c: 'x' // last\n}";
+    let format = |policy| {
+        let options = FormatOptions {
+            trailing_commas: Some(policy),
+            ..FormatOptions::default()
+        };
+        reformat_with_options(input, &options).unwrap()
+    };
+
+    assert_eq!(
+        format(TrailingCommas::Always),
+        "{\n  a: [\n    1,\n    [],\n    {\n      b: 2,\n    },\n  ],\n  c: 'x', // last\n}\n"
+    );
+    assert_eq!(
+        format(TrailingCommas::Multiline),
+        format(TrailingCommas::Always)
+    );
+    assert_eq!(
+        format(TrailingCommas::Never),
+        "{\n  a: [\n    1,\n    [],\n    {\n      b: 2\n    }\n  ],\n  c: 'x' // last\n}\n"
+    );
+}
+
+#[test]
+fn numbers_per_line() {
+    let input = "{m: [1, -20, 3.5, 400, 5, 0x6, 7,], short: [1, 2], mixed: [1, 2, 'x', 4]}";
+    let options = FormatOptions {
+        numbers_per_line: Some(3),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        reformat_with_options(input, &options).unwrap(),
+        "{
+  m: [
+      1, -20, 3.5,
+    400,   5, 0x6,
+      7,
+  ],
+  short: [
+    1,
+    2
+  ],
+  mixed: [
+    1,
+    2,
+    'x',
+    4
+  ]
+}
+"
+    );
+
+    let options = FormatOptions {
+        numbers_per_line: Some(4),
+        trailing_commas: Some(TrailingCommas::Never),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        reformat_with_options("[[1, 2, 3, 4, 5], [6, /* c */ 7, 8, 9, 10]]", &options).unwrap(),
+        "[\n  [\n    1, 2, 3, 4,\n    5\n  ],\n  [\n    6, /* c */\n    7,\n    8,\n    9,\n    10\n  ]\n]\n"
+    );
+}
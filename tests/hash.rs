@@ -0,0 +1,64 @@
+#![cfg(feature = "hash")]
+
+extern crate json5;
+
+use std::cell::Cell;
+
+use json5::{parse, ContentCache, Value};
+
+#[test]
+fn structural_hash() {
+    let a = parse("{ 'a': 1, 'b': [true, null, 'x'], 'c': { 'd': 0.0 } }").unwrap();
+    let b = parse("// reordered\n{ 'c': { 'd': -0.0 }, 'b': [true, null, 'x'], 'a': 1, }").unwrap();
+
+    assert_eq!(a.structural_hash(), b.structural_hash());
+    assert_eq!(
+        Value::Null.structural_hash(),
+        parse("null").unwrap().structural_hash()
+    );
+
+    for other in &[
+        "{ 'a': 1, 'b': [null, true, 'x'], 'c': { 'd': 0.0 } }",
+        "{ 'a': 1.0, 'b': [true, null, 'x'], 'c': { 'd': 0.0 } }",
+        "{ 'a': 1, 'b': [true, null, 'x'], 'c': { 'e': 0.0 } }",
+        "{ 'a': 1, 'b': [true, null, 'x'] }",
+    ] {
+        assert_ne!(a.structural_hash(), parse(other).unwrap().structural_hash());
+    }
+    assert_ne!(
+        parse("['ab', 'c']").unwrap().structural_hash(),
+        parse("['a', 'bc']").unwrap().structural_hash()
+    );
+}
+
+#[test]
+fn content_cache() {
+    let calls = Cell::new(0);
+    let load = |value: &Value| {
+        calls.set(calls.get() + 1);
+        value["port"].to_i64_exact()
+    };
+    let mut cache = ContentCache::new(2);
+
+    let a = parse("{ 'port': 80, 'host': 'a' }").unwrap();
+    let b = parse("{ 'host': 'a', 'port': 80 }").unwrap();
+    let c = parse("{ 'port': 81, 'host': 'a' }").unwrap();
+    let d = parse("{ 'port': 82, 'host': 'a' }").unwrap();
+
+    assert_eq!(cache.get_or_insert_with(&a, load), &Some(80));
+    assert_eq!(cache.get_or_insert_with(&b, load), &Some(80));
+    assert_eq!(calls.get(), 1);
+
+    assert_eq!(cache.get_or_insert_with(&c, load), &Some(81));
+    assert_eq!(cache.get(&a), Some(&Some(80)));
+    assert_eq!(cache.get_or_insert_with(&d, load), &Some(82));
+    assert_eq!(calls.get(), 3);
+    assert_eq!(cache.len(), 2);
+
+    // `c` was least recently used
+    assert_eq!(cache.get(&c), None);
+    assert_eq!(cache.get(&b), Some(&Some(80)));
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
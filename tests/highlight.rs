@@ -0,0 +1,40 @@
+#![cfg(feature = "cst")]
+
+extern crate json5;
+
+use json5::{semantic_tokens, SemanticKind};
+
+#[test]
+fn it_works() {
+    let input = "{a: 'x', \"b\" : [1, null, bogus], /* c */ 'd': 'open\n}";
+    let tokens: Vec<(SemanticKind, &str)> = semantic_tokens(input)
+        .iter()
+        .map(|t| (t.kind, &input[t.span.start.offset..t.span.end.offset]))
+        .collect();
+
+    assert_eq!(
+        tokens,
+        vec![
+            (SemanticKind::Punctuation, "{"),
+            (SemanticKind::Key, "a"),
+            (SemanticKind::Punctuation, ":"),
+            (SemanticKind::String, "'x'"),
+            (SemanticKind::Punctuation, ","),
+            (SemanticKind::Key, "\"b\""),
+            (SemanticKind::Punctuation, ":"),
+            (SemanticKind::Punctuation, "["),
+            (SemanticKind::Number, "1"),
+            (SemanticKind::Punctuation, ","),
+            (SemanticKind::Keyword, "null"),
+            (SemanticKind::Punctuation, ","),
+            (SemanticKind::Error, "bogus"),
+            (SemanticKind::Punctuation, "]"),
+            (SemanticKind::Punctuation, ","),
+            (SemanticKind::Comment, "/* c */"),
+            (SemanticKind::Key, "'d'"),
+            (SemanticKind::Punctuation, ":"),
+            (SemanticKind::Error, "'open"),
+            (SemanticKind::Punctuation, "}"),
+        ]
+    );
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "config")]
+
+extern crate json5;
+
+use json5::{parse, Layered, Pointer, Position, Provenance, Span};
+
+fn pointer(s: &str) -> Pointer {
+    Pointer::parse(s).unwrap()
+}
+
+#[test]
+fn it_works() {
+    let mut config = Layered::new();
+    config.add_value(
+        "defaults",
+        parse("{ 'server': { 'host': 'localhost', 'port': 80 }, 'tags': ['a'] }").unwrap(),
+    );
+    config
+        .add_str(
+            "app.json5",
+            "{\n  // listen everywhere\n  'server': { 'host': '0.0.0.0' },\n  'tags': ['b', 'c'],\n}",
+        )
+        .unwrap();
+    config.add_value("env", parse("{ 'server': { 'port': 8080 } }").unwrap());
+
+    assert_eq!(
+        config.value(),
+        &parse("{ 'server': { 'host': '0.0.0.0', 'port': 8080 }, 'tags': ['b', 'c'] }").unwrap()
+    );
+    assert_eq!(
+        config.provenance(&pointer("/server/host")),
+        Some(&Provenance {
+            source: "app.json5".to_string(),
+            span: Some(Span {
+                start: Position {
+                    offset: 47,
+                    line: 3,
+                    column: 23
+                },
+                end: Position {
+                    offset: 56,
+                    line: 3,
+                    column: 32
+                },
+            }),
+        })
+    );
+    assert_eq!(
+        config
+            .provenance(&pointer("/server/port"))
+            .unwrap()
+            .to_string(),
+        "env"
+    );
+    assert_eq!(
+        config.provenance(&pointer("/server")).unwrap().source,
+        "defaults"
+    );
+    assert_eq!(
+        config.provenance(&pointer("/tags/1")).unwrap().to_string(),
+        "app.json5, line 4"
+    );
+    assert_eq!(config.provenance(&pointer("/missing")), None);
+
+    let mut config = Layered::new();
+    config.add_str("a.json5", "{ 'x': { 'y': 1 } }").unwrap();
+    config.add_str("b.json5", "{ 'x': 2 }").unwrap();
+    assert_eq!(config.provenance(&pointer("/x/y")), None);
+    assert_eq!(
+        config.to_annotated_string(),
+        "{\n  \"x\": 2, // b.json5, line 1\n}\n"
+    );
+
+    let error = Layered::new().add_str("bad.json5", "{").unwrap_err();
+    assert_eq!(error.name(), Some("bad.json5"));
+}
+
+#[test]
+fn annotated_string() {
+    let mut config = Layered::new();
+    config.add_value(
+        "defaults",
+        parse("{ 'list': [1, {}], 'empty': [] }").unwrap(),
+    );
+    config
+        .add_str("app.json5", "{\n  'nested': { 'on': true },\n}")
+        .unwrap();
+
+    let dump = config.to_annotated_string();
+    assert_eq!(parse(&dump).unwrap(), *config.value());
+    for line in [
+        "  \"list\": [",
+        "    1, // defaults",
+        "    {}, // defaults",
+        "  ], ",
+        "  \"empty\": [], // defaults",
+        "    \"on\": true, // app.json5, line 2",
+    ] {
+        assert!(
+            dump.lines().any(|l| l == line.trim_end()),
+            "{}\n{}",
+            line,
+            dump
+        );
+    }
+}
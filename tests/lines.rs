@@ -0,0 +1,53 @@
+#![cfg(feature = "lines")]
+
+extern crate json5;
+
+use json5::{lines, stringify_lines, ErrorKind, Value};
+
+#[test]
+fn it_works() {
+    let input = "{ 'id': 1, } // first\n\n  \r\n[2, 3]\r\n'four'";
+    let values: Vec<Value> = lines(input).collect::<Result<_, _>>().unwrap();
+
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0]["id"], Value::Integer(1));
+    assert_eq!(values[1][1], Value::Integer(3));
+    assert_eq!(values[2], Value::String("four".to_string()));
+
+    assert_eq!(stringify_lines(&values), "{\"id\":1}\n[2,3]\n\"four\"\n");
+    assert_eq!(
+        lines(&stringify_lines(&values))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        values
+    );
+    assert_eq!(lines("").count(), 0);
+}
+
+#[test]
+fn errors() {
+    let input = "1\n{ 'a': }\n\n[1, 2\n3";
+    let results: Vec<_> = lines(input).collect();
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0], Ok(Value::Integer(1)));
+    assert_eq!(
+        results[1].as_ref().unwrap_err().to_string(),
+        "unexpected character `}`, expected a value, line 2 column 8"
+    );
+    assert_eq!(
+        results[1]
+            .as_ref()
+            .unwrap_err()
+            .span()
+            .unwrap()
+            .start
+            .offset,
+        9
+    );
+
+    let error = results[2].as_ref().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedEof { .. }));
+    assert_eq!(error.span().unwrap().start.line, 4);
+    assert_eq!(results[3], Ok(Value::Integer(3)));
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "std")]
+
+extern crate json5;
+
+use std::fs;
+use std::path::PathBuf;
+
+use json5::{load_dir, load_dir_all, ErrorKind, LoadError, Value};
+
+fn fixture(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("json5-load-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("nested.json5")).unwrap();
+    for (file, json) in files {
+        fs::write(dir.join(file), json).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn it_works() {
+    let dir = fixture(
+        "ok",
+        &[
+            ("app.json5", "{ 'port': 80 }"),
+            ("db.json5", "// database\n{ 'host': 'db' }"),
+            ("notes.txt", "not json"),
+            ("app.json5.bak", "{"),
+        ],
+    );
+
+    let values = load_dir(&dir, "*.json5").unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values["app.json5"]["port"], Value::Integer(80));
+    assert_eq!(values["db.json5"]["host"], Value::String("db".to_string()));
+
+    let values = load_dir_all(&dir, "d?.*").unwrap();
+    assert_eq!(values.len(), 1);
+    assert!(values.contains_key("db.json5"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn errors() {
+    let dir = fixture(
+        "errors",
+        &[
+            ("a.json5", "{ 'port': }"),
+            ("b.json5", "{}"),
+            ("c.json5", "[1, 2"),
+        ],
+    );
+
+    let error = load_dir(&dir, "*.json5").unwrap_err();
+    assert_eq!(error.path(), dir.join("a.json5"));
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "{}: unexpected character `}}`, expected a value, line 1 column 11",
+            dir.join("a.json5").display()
+        )
+    );
+
+    let errors = load_dir_all(&dir, "*").unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        &errors[1],
+        LoadError::Parse { path, error } if *path == dir.join("c.json5") && matches!(error.kind(), ErrorKind::UnexpectedEof { .. })
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    let error = load_dir(&dir, "*").unwrap_err();
+    assert!(matches!(error, LoadError::Io { ref path, .. } if *path == dir));
+}
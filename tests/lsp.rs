@@ -0,0 +1,187 @@
+#![cfg(feature = "lsp")]
+
+extern crate json5;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+fn frame(message: Value) -> String {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+fn read_messages(output: &[u8]) -> Vec<Value> {
+    let mut reader = BufReader::new(output);
+    let mut messages = Vec::new();
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).unwrap() == 0 {
+            return messages;
+        }
+        let length: usize = header["Content-Length: ".len()..].trim().parse().unwrap();
+        reader.read_line(&mut String::new()).unwrap();
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        messages.push(serde_json::from_slice(&body).unwrap());
+    }
+}
+
+// Runs the server through a session, returning what it sent back.
+fn run(input: &[Value]) -> Vec<Value> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_json5-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stdin = child.stdin.as_mut().unwrap();
+    for message in input {
+        stdin.write_all(frame(message.clone()).as_bytes()).unwrap();
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    read_messages(&output.stdout)
+}
+
+#[test]
+fn session() {
+    let uri = "file:///config.json5";
+    let input = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+            "textDocument": {"uri": uri, "languageId": "json5", "version": 1, "text": "{a: [1, }"},
+        }}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+            "textDocument": {"uri": uri, "version": 2},
+            "contentChanges": [
+                {"text": "{\"é\": [1, 2], // c\n}"},
+                {"range": {"start": {"line": 0, "character": 10}, "end": {"line": 0, "character": 11}}, "text": "'two'"},
+            ],
+        }}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/hover", "params": {
+            "textDocument": {"uri": uri},
+            "position": {"line": 0, "character": 11},
+        }}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/formatting", "params": {
+            "textDocument": {"uri": uri},
+            "options": {"tabSize": 2, "insertSpaces": true},
+        }}),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "textDocument/rename", "params": {}}),
+        json!({"jsonrpc": "2.0", "id": 6, "method": "textDocument/semanticTokens/full", "params": {
+            "textDocument": {"uri": uri},
+        }}),
+        json!({"jsonrpc": "2.0", "id": 7, "method": "textDocument/codeAction", "params": {
+            "textDocument": {"uri": uri},
+            "range": {"start": {"line": 0, "character": 11}, "end": {"line": 0, "character": 11}},
+            "context": {"diagnostics": []},
+        }}),
+        json!({"jsonrpc": "2.0", "id": 5, "method": "shutdown"}),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+    ];
+
+    let messages = run(&input);
+    assert_eq!(messages.len(), 9);
+
+    assert_eq!(messages[0]["result"]["capabilities"]["hoverProvider"], true);
+
+    let diagnostics = &messages[1]["params"]["diagnostics"];
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(
+        diagnostics[0]["range"]["start"],
+        json!({"line": 0, "character": 8})
+    );
+    assert_eq!(messages[2]["params"]["diagnostics"], json!([]));
+
+    assert_eq!(messages[3]["id"], 2);
+    assert_eq!(
+        messages[3]["result"]["range"],
+        json!({"start": {"line": 0, "character": 10}, "end": {"line": 0, "character": 15}})
+    );
+    let contents = messages[3]["result"]["contents"]["value"].as_str().unwrap();
+    assert!(contents.starts_with("`/é/1` (string)"));
+
+    let edits = messages[4]["result"].as_array().unwrap();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(
+        edits[0]["newText"],
+        "{\n  \"é\": [\n    1,\n    'two'\n  ], // c\n}\n"
+    );
+    assert_eq!(edits[0]["range"]["end"], json!({"line": 1, "character": 1}));
+
+    assert_eq!(messages[5]["error"]["code"], -32601);
+    // {"é": [1, 'two'], // c
+    // }
+    assert_eq!(
+        messages[6]["result"]["data"],
+        json!([
+            0, 0, 1, 5, 0, // {
+            0, 1, 3, 0, 0, // "é"
+            0, 3, 1, 5, 0, // :
+            0, 2, 1, 5, 0, // [
+            0, 1, 1, 2, 0, // 1
+            0, 1, 1, 5, 0, // ,
+            0, 2, 5, 1, 0, // 'two'
+            0, 5, 1, 5, 0, // ]
+            0, 1, 1, 5, 0, // ,
+            0, 2, 4, 4, 0, // // c
+            1, 0, 1, 5, 0, // }
+        ])
+    );
+    let actions = messages[7]["result"].as_array().unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0]["title"], "Convert to double quotes");
+    assert_eq!(
+        actions[0]["edit"]["changes"][uri][0]["newText"],
+        "{\"é\": [1, \"two\"], // c\n}"
+    );
+
+    assert_eq!(messages[8]["result"], Value::Null);
+}
+
+#[test]
+fn completion() {
+    let dir = std::env::temp_dir().join(format!("json5-lsp-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("schema.json"),
+        r#"{"properties": {"port": {"type": "integer", "description": "Port to listen on"}}}"#,
+    )
+    .unwrap();
+    let uri = format!("file://{}/config.json5", dir.display());
+
+    let input = [
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+            "textDocument": {"uri": uri, "languageId": "json5", "version": 1,
+                "text": "{$schema: 'schema.json'}"},
+        }}),
+        // Mid-edit, the document doesn't parse, but the schema is kept.
+        json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+            "textDocument": {"uri": uri, "version": 2},
+            "contentChanges": [{"text": "{$schema: 'schema.json', p\n"}],
+        }}),
+        json!({"jsonrpc": "2.0", "id": 1, "method": "textDocument/completion", "params": {
+            "textDocument": {"uri": uri},
+            "position": {"line": 0, "character": 26},
+        }}),
+    ];
+    let messages = run(&input);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        messages[2]["result"],
+        json!([{
+            "label": "port",
+            "kind": 10,
+            "insertText": "\"port\": ",
+            "documentation": "Port to listen on",
+            "textEdit": {
+                "range": {"start": {"line": 0, "character": 25}, "end": {"line": 0, "character": 26}},
+                "newText": "\"port\": ",
+            },
+        }])
+    );
+}
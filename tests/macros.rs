@@ -0,0 +1,31 @@
+#![cfg(feature = "macros")]
+
+extern crate json5;
+
+use json5::{json5_static, StaticValue, Value};
+
+static CONFIG: StaticValue = json5_static!("tests/data/config.json5");
+const RAW: StaticValue = json5_static!(r#"tests/data/config.json5"#);
+
+macro_rules! load {
+    ($path:expr) => {
+        json5_static!($path)
+    };
+}
+
+#[test]
+fn it_works() {
+    let text = include_str!("data/config.json5");
+
+    assert_eq!(CONFIG.to_value(), json5::parse(text).unwrap());
+    assert_eq!(CONFIG["port"], StaticValue::Integer(8080));
+    assert_eq!(CONFIG["tags"][1], StaticValue::String("b"));
+    assert_eq!(CONFIG.get("missing"), None);
+    assert_eq!(CONFIG["missing"][0], StaticValue::Null);
+    assert_eq!(RAW, CONFIG);
+    assert_eq!(load!("tests/data/config.json5"), CONFIG);
+    assert_eq!(
+        StaticValue::Array(&[StaticValue::Float(0.5)]).to_value(),
+        Value::Array(vec![Value::Float(0.5)])
+    );
+}
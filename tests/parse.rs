@@ -1,9 +1,16 @@
 extern crate json5;
 
-use json5::{parse, Error, Value};
-use std::collections::HashMap;
+use json5::{
+    parse, parse_with_warnings, DuplicateKeys, ErrorKind, Map, ParseOptions, Position, Span,
+    TokenKind, Value, WarningKind,
+};
+
+fn error_kind(json: &str) -> ErrorKind {
+    parse(json).unwrap_err().kind().clone()
+}
 
 #[test]
+#[allow(clippy::bool_assert_comparison, clippy::legacy_numeric_constants)]
 fn it_works() {
     assert_eq!(parse("null"), Ok(Value::Null));
 
@@ -11,10 +18,16 @@ fn it_works() {
     assert_eq!(parse("false"), Ok(Value::Boolean(false)));
 
     assert_eq!(parse("0"), Ok(Value::Integer(0)));
-    assert_eq!(parse("00"), Err(Error::UnparseableNumber));
+    assert_eq!(error_kind("00"), ErrorKind::InvalidNumber);
     assert_eq!(parse("42"), Ok(Value::Integer(42)));
     assert_eq!(parse("+42"), Ok(Value::Integer(42)));
-    assert_eq!(parse("++42"), Err(Error::UnparseableNumber));
+    assert_eq!(
+        error_kind("++42"),
+        ErrorKind::UnexpectedCharacter {
+            found: '+',
+            expected: vec![TokenKind::Number]
+        }
+    );
     assert_eq!(parse("-999"), Ok(Value::Integer(-999)));
     assert_eq!(parse("0x1a"), Ok(Value::Integer(26)));
     assert_eq!(parse("0X1A"), Ok(Value::Integer(26)));
@@ -89,10 +102,1020 @@ fn it_works() {
             "#
         ),
         Ok(Value::Object({
-            let mut m = HashMap::new();
+            let mut m = Map::new();
             m.insert("foo".to_string(), Value::Integer(1));
             m.insert("bar".to_string(), Value::Boolean(true));
             m
         })),
     );
 }
+
+#[test]
+fn max_depth() {
+    let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+
+    assert!(parse(&nested(128)).is_ok());
+    assert_eq!(error_kind(&nested(129)), ErrorKind::ExceededMaxDepth);
+    assert_eq!(
+        error_kind(&"{'a':".repeat(100_000)),
+        ErrorKind::ExceededMaxDepth
+    );
+}
+
+#[test]
+fn malformed_input() {
+    for json in &[
+        "",
+        "t",
+        "nul",
+        "+",
+        "-",
+        "0x",
+        "'\\",
+        "'\\u12",
+        "'\\uD800'",
+        "'\\x4'",
+        "[1,",
+        "{'a'",
+        "{'a':",
+        "{1:2}",
+        "/",
+        "/*",
+        "Infinit",
+        "\u{2028}",
+    ] {
+        assert!(parse(json).is_err(), "{:?} should not parse", json);
+    }
+}
+
+#[test]
+fn parse_named() {
+    assert_eq!(json5::parse_named("[1]", "a.json5"), parse("[1]"));
+
+    let error = json5::parse_named("[1,", "config/prod.json5").unwrap_err();
+
+    assert_eq!(error.name(), Some("config/prod.json5"));
+    assert_eq!(
+        error.to_string(),
+        "config/prod.json5: unexpected end of JSON, expected a value or `]`, line 1 column 4"
+    );
+    assert_eq!(parse("[1,").unwrap_err().name(), None);
+}
+
+#[test]
+fn error_to_value() {
+    let error = json5::parse_named("{\n  a: tru }", "app.json5").unwrap_err();
+    assert_eq!(error.kind().code(), "unexpected_character");
+    assert_eq!(
+        error.to_value(),
+        parse(
+            "{
+                kind: 'unexpected_character',
+                message: 'unexpected character ` `, expected `true`',
+                line: 2,
+                column: 9,
+                pointer: null,
+                path: null,
+                name: 'app.json5',
+            }"
+        )
+        .unwrap()
+    );
+
+    #[cfg(feature = "config")]
+    {
+        let mut value = parse("{ servers: [{ '@if(x)': 1 }] }").unwrap();
+        let error = value.resolve_conditions(|_| true).unwrap_err();
+        let error = error.to_value();
+        assert_eq!(error["pointer"], Value::String("/servers/0/@if(x)".into()));
+        assert_eq!(
+            error["path"],
+            Value::String("servers[0][\"@if(x)\"]".into())
+        );
+        assert!(error["line"].is_null());
+    }
+}
+
+#[test]
+fn error_kinds() {
+    use ErrorKind::*;
+    use TokenKind::{Char, EndOfInput, HexDigit, Key, Literal};
+
+    let cases = vec![
+        (
+            "[1 2]",
+            UnexpectedCharacter {
+                found: '2',
+                expected: vec![Char(','), Char(']')],
+            },
+            "unexpected character `2`, expected `,` or `]`, line 1 column 4",
+        ),
+        (
+            "{'a': 1,",
+            UnexpectedEof {
+                expected: vec![Key, Char('}')],
+            },
+            "unexpected end of JSON, expected an object key or `}`, line 1 column 9",
+        ),
+        (
+            "{'a' 1}",
+            UnexpectedCharacter {
+                found: '1',
+                expected: vec![Char(':')],
+            },
+            "unexpected character `1`, expected `:`, line 1 column 6",
+        ),
+        (
+            "\n  nul",
+            UnexpectedEof {
+                expected: vec![Literal("null")],
+            },
+            "unexpected end of JSON, expected `null`, line 2 column 6",
+        ),
+        (
+            "'a\nb'",
+            UnexpectedCharacter {
+                found: '\n',
+                expected: vec![Char('\'')],
+            },
+            "unexpected character `\\n`, expected `'`, line 1 column 3",
+        ),
+        (
+            "0xg",
+            UnexpectedCharacter {
+                found: 'g',
+                expected: vec![HexDigit],
+            },
+            "unexpected character `g`, expected a hexadecimal digit, line 1 column 3",
+        ),
+        (
+            "1 1",
+            UnexpectedCharacter {
+                found: '1',
+                expected: vec![EndOfInput],
+            },
+            "unexpected character `1`, expected end of input, line 1 column 3",
+        ),
+        (
+            "/* a",
+            UnexpectedEof {
+                expected: vec![Literal("*/")],
+            },
+            "unexpected end of JSON, expected `*/`, line 1 column 5",
+        ),
+        ("1.2.3", InvalidNumber, "invalid number, line 1 column 1"),
+        (
+            "3000000000",
+            NumberOverflow,
+            "number out of range, line 1 column 1",
+        ),
+        (
+            "-0x80000001",
+            NumberOverflow,
+            "number out of range, line 1 column 1",
+        ),
+        (
+            "['\\x4']",
+            InvalidEscape,
+            "invalid escape sequence, line 1 column 3",
+        ),
+        (
+            "'\\uD800'",
+            InvalidEscape,
+            "invalid escape sequence, line 1 column 2",
+        ),
+    ];
+
+    for (json, kind, message) in cases {
+        let error = parse(json).unwrap_err();
+        assert_eq!(error.kind(), &kind, "{:?}", json);
+        assert_eq!(error.to_string(), message, "{:?}", json);
+    }
+
+    assert_eq!(parse("-0x80000000"), Ok(Value::Integer(i32::MIN)));
+    assert_eq!(
+        parse("[1, 00]").unwrap_err().span(),
+        Some(Span {
+            start: Position {
+                offset: 4,
+                line: 1,
+                column: 5,
+            },
+            end: Position {
+                offset: 6,
+                line: 1,
+                column: 7,
+            },
+        })
+    );
+    assert_eq!(parse("[").unwrap_err().pointer(), None);
+}
+
+#[test]
+fn warnings() {
+    let json = "{ 'a': 1, 'b': NaN, 'a': 2, 's': '\\uD800x' }";
+
+    let error = parse(json).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::InvalidEscape);
+
+    let mut options = ParseOptions {
+        replace_lone_surrogates: true,
+        ..ParseOptions::default()
+    };
+    let (value, warnings) = parse_with_warnings(json, &options).unwrap();
+
+    assert_eq!(value["a"], Value::Integer(2));
+    assert_eq!(value["s"], Value::String("\u{FFFD}x".to_string()));
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            WarningKind::NaN,
+            WarningKind::DuplicateKey("a".to_string()),
+            WarningKind::LoneSurrogate,
+        ]
+    );
+    assert_eq!(
+        warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+        vec![
+            "unquoted NaN, line 1 column 16",
+            "duplicate key `a`, line 1 column 21",
+            "lone surrogate replaced with U+FFFD, line 1 column 35",
+        ]
+    );
+
+    options.duplicate_keys = DuplicateKeys::FirstWins;
+    let value = json5::parse_with_options(json, &options).unwrap();
+    assert_eq!(value["a"], Value::Integer(1));
+
+    options.duplicate_keys = DuplicateKeys::Error;
+    let error = json5::parse_with_options(json, &options).unwrap_err();
+    assert_eq!(error.to_string(), "duplicate key `a`, line 1 column 21");
+
+    let (_, warnings) = parse_with_warnings("'\\uD83D\\uDE00'", &options).unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn feature_toggles() {
+    let strict = ParseOptions::strict();
+    let parse_strict = |json| json5::parse_with_options(json, &strict);
+
+    assert!(parse_strict(r#"{"a": [1, -2.5e3, "x", true, null]}"#).is_ok());
+    assert_eq!(
+        parse_strict(r#""\"\\\/\b\f\n\r\t\u0041""#),
+        Ok(Value::String("\"\\/\u{8}\u{c}\n\r\tA".to_string()))
+    );
+    assert_eq!(parse_strict("1.5e3"), Ok(Value::Float(1500.0)));
+    assert_eq!(
+        parse_strict("1.e3").unwrap_err().kind(),
+        &ErrorKind::InvalidNumber
+    );
+    for json in &[
+        "// comment\n1",
+        "'a'",
+        "[1,]",
+        "{\"a\": 1,}",
+        "{a: 1}",
+        "Infinity",
+        "-NaN",
+        "0x1F",
+        "+1",
+        ".5",
+        "-.5",
+        "1.",
+        "-1.e5",
+        r#""\x41""#,
+        r#""\v""#,
+        r#""\0""#,
+        r#""\'""#,
+        r#""\a""#,
+        "\"a\\\nb\"",
+    ] {
+        assert!(parse(json).is_ok(), "{:?} should parse", json);
+        assert!(parse_strict(json).is_err(), "{:?} should not parse", json);
+    }
+
+    let error = parse_strict("{\"a\": 1,}").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unexpected character `}`, expected an object key, line 1 column 9"
+    );
+
+    let options = ParseOptions {
+        allow_unquoted_keys: true,
+        ..strict
+    };
+    let value = json5::parse_with_options("{ $_a1: 1, ünï: 2 }", &options).unwrap();
+    assert_eq!(value["$_a1"], Value::Integer(1));
+    assert_eq!(value["ünï"], Value::Integer(2));
+    assert!(json5::parse_with_options("{ 1a: 1 }", &options).is_err());
+    assert!(json5::parse_with_options("{ 'a': 1 }", &options).is_err());
+}
+
+#[test]
+fn hjson() {
+    let json = "{
+  # service settings
+  name: my service, v2
+  port: 8080
+  ratio: 0.5 // inline comment
+  enabled: true
+  note: true story
+  url-path: /api/{id}
+  hosts: [
+    alpha
+    beta, gamma
+    3
+  ]
+  script:
+    '''
+    echo 'hi'
+      indented
+    '''
+  inline: '''one line'''
+}";
+    let value = json5::parse_with_options(json, &ParseOptions::hjson()).unwrap();
+    let string = |s: &str| Value::String(s.to_string());
+
+    assert_eq!(value["name"], string("my service, v2"));
+    assert_eq!(value["port"], Value::Integer(8080));
+    assert_eq!(value["ratio"], Value::Float(0.5));
+    assert_eq!(value["enabled"], Value::Boolean(true));
+    assert_eq!(value["note"], string("true story"));
+    assert_eq!(value["url-path"], string("/api/{id}"));
+    assert_eq!(
+        value["hosts"],
+        Value::Array(vec![
+            string("alpha"),
+            string("beta, gamma"),
+            Value::Integer(3)
+        ])
+    );
+    assert_eq!(value["script"], string("echo 'hi'\n  indented"));
+    assert_eq!(value["inline"], string("one line"));
+
+    assert!(parse(json).is_err());
+    assert!(json5::parse_with_options("[1 2]", &ParseOptions::hjson()).is_err());
+    assert_eq!(
+        json5::parse_with_options("'''abc", &ParseOptions::hjson())
+            .unwrap_err()
+            .to_string(),
+        "unexpected end of JSON, expected `'''`, line 1 column 7"
+    );
+}
+
+#[test]
+fn backtick_strings() {
+    let json = "{ 'query': `SELECT *
+  FROM t
+  WHERE name = 'a\\b'` }";
+    let options = ParseOptions {
+        allow_backtick_strings: true,
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        json5::parse_with_options(json, &options).unwrap()["query"],
+        Value::String("SELECT *\n  FROM t\n  WHERE name = 'a\\b'".to_string())
+    );
+    assert!(parse(json).is_err());
+    assert!(json5::parse_with_options("`abc", &options).is_err());
+}
+
+#[test]
+fn control_characters() {
+    let json = "['a\tb', 'c\u{0001}']";
+
+    assert_eq!(
+        parse(json).unwrap_err().to_string(),
+        "unexpected character `\\t`, line 1 column 4"
+    );
+
+    let options = ParseOptions {
+        allow_control_characters: true,
+        ..ParseOptions::default()
+    };
+    let (value, warnings) = parse_with_warnings(json, &options).unwrap();
+
+    assert_eq!(value[0], Value::String("a\tb".to_string()));
+    assert_eq!(value[1], Value::String("c\u{0001}".to_string()));
+    assert_eq!(
+        warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+        vec![
+            "unescaped control character `\\t`, line 1 column 4",
+            "unescaped control character `\\u{1}`, line 1 column 11",
+        ]
+    );
+    assert!(json5::parse_with_options("'a\nb'", &options).is_err());
+}
+
+#[test]
+#[cfg(feature = "cst")]
+fn comments() {
+    let json = "// Service settings.
+{
+  /* The host
+     to bind. */
+  host: 'a', // trailing
+  ports: [
+    // HTTP
+    80,
+  ],
+}
+// end";
+    let (value, comments) = json5::parse_with_comments(json).unwrap();
+
+    assert_eq!(value, parse(json).unwrap());
+    assert_eq!(
+        comments
+            .iter()
+            .map(|c| (c.text.as_str(), c.pointer.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (" Service settings.", json5::Pointer::parse("")),
+            (" The host\n     to bind. ", json5::Pointer::parse("/host")),
+            (" trailing", json5::Pointer::parse("/ports")),
+            (" HTTP", json5::Pointer::parse("/ports/0")),
+            (" end", None),
+        ]
+    );
+    assert_eq!(
+        comments[0].span,
+        Span {
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            end: Position {
+                offset: 20,
+                line: 1,
+                column: 21,
+            },
+        }
+    );
+    assert!(json5::parse_with_comments("{ // open").is_err());
+}
+
+#[test]
+fn line_endings() {
+    for newline in &["\n", "\r\n", "\r"] {
+        let json = "{ // first\n  a: 'x\\\ny',\n  /* b\n */ b: 1,\n}".replace('\n', newline);
+        let mut expected = Map::new();
+        expected.insert("a".to_string(), Value::String("xy".to_string()));
+        expected.insert("b".to_string(), Value::Integer(1));
+        assert_eq!(parse(&json), Ok(Value::Object(expected)), "{:?}", json);
+
+        let json = "[\n  1,\n  @,\n]".replace('\n', newline);
+        let start = parse(&json).unwrap_err().span().unwrap().start;
+        assert_eq!((start.line, start.column), (3, 3), "{:?}", json);
+    }
+
+    let options = ParseOptions {
+        allow_hash_comments: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        json5::parse_with_options("# a\r1", &options),
+        Ok(Value::Integer(1))
+    );
+}
+
+#[test]
+fn parse_buffer() {
+    let mut buffer = json5::ParseBuffer::new();
+
+    assert_eq!(
+        buffer.parse("{ a: 1.5, a: -0x10 }").unwrap(),
+        parse("{ a: -0x10 }").unwrap()
+    );
+    assert_eq!(buffer.warnings().len(), 1);
+
+    assert_eq!(
+        buffer.parse("[1e3, 'x', 12345]").unwrap(),
+        parse("[1000.0, 'x', 12345]").unwrap()
+    );
+    assert!(buffer.warnings().is_empty());
+
+    assert_eq!(
+        buffer.parse("[1, 2").unwrap_err().to_string(),
+        "unexpected end of JSON, expected `,` or `]`, line 1 column 6"
+    );
+    assert_eq!(buffer.parse("7").unwrap(), Value::Integer(7));
+
+    let mut strict = json5::ParseBuffer::with_options(ParseOptions::strict());
+    assert!(strict.parse("{ a: 1 }").is_err());
+    assert!(strict.parse(r#"{ "a": 1 }"#).is_ok());
+}
+
+#[test]
+fn limits() {
+    use json5::{parse_with_limits, Limits};
+    use std::sync::atomic::AtomicBool;
+
+    let options = ParseOptions::default();
+    let json = "{ a: [1, 2, 3], b: 'x' }";
+    assert_eq!(
+        parse_with_limits(json, &options, &Limits::default()).unwrap(),
+        parse(json).unwrap()
+    );
+
+    let limits = Limits {
+        max_values: Some(6),
+        ..Limits::default()
+    };
+    assert!(parse_with_limits(json, &options, &limits).is_ok());
+    let limits = Limits {
+        max_values: Some(5),
+        ..Limits::default()
+    };
+    let error = parse_with_limits(json, &options, &limits).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::BudgetExceeded);
+    assert_eq!(
+        error.to_string(),
+        "exceeded parsing budget, line 1 column 20"
+    );
+
+    let limits = Limits {
+        max_bytes: Some(json.len() - 1),
+        ..Limits::default()
+    };
+    let error = parse_with_limits(json, &options, &limits).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::BudgetExceeded);
+    assert_eq!(error.span(), None);
+
+    let cancel = AtomicBool::new(true);
+    let limits = Limits {
+        cancel: Some(&cancel),
+        ..Limits::default()
+    };
+    let error = parse_with_limits(json, &options, &limits).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::Cancelled);
+    assert_eq!(error.to_string(), "parsing cancelled, line 1 column 1");
+}
+
+#[test]
+fn progress() {
+    let json = format!("[{}0]", "1234567,".repeat(100));
+    let mut offsets = Vec::new();
+    let value = json5::parse_with_progress(&json, &ParseOptions::default(), 200, |offset| {
+        offsets.push(offset)
+    })
+    .unwrap();
+
+    assert_eq!(value, parse(&json).unwrap());
+    assert_eq!(offsets, vec![201, 401, 601, 801, json.len()]);
+
+    let mut offsets = Vec::new();
+    assert!(
+        json5::parse_with_progress("[1, 2", &ParseOptions::default(), 1, |offset| {
+            offsets.push(offset)
+        })
+        .is_err()
+    );
+    assert_eq!(offsets, vec![1, 4]);
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn variables() {
+    let mut variables = Map::new();
+    variables.insert("instance".to_string(), Value::Integer(3));
+    variables.insert("region".to_string(), Value::String("eu".into()));
+    variables.insert("ratio".to_string(), Value::Float(0.5));
+    variables.insert("tags".to_string(), parse("['a']").unwrap());
+    let eval = |json: &str| json5::parse_with_variables(json, &variables);
+
+    assert_eq!(
+        eval(
+            "{
+                port: 8000 + instance, // comment
+                workers: (instance + 1) * 2 /* comment */ - 1,
+                share: 1 / 4 + ratio,
+                half: 6 / 2,
+                third: 7 % 3 - -instance,
+                host: 'db-' + region + '-' + instance,
+                url: 'https://${region}.example.com/$${path}',
+                tags: tags,
+                literals: [true, null, -Infinity, 2 * 0x10],
+            }"
+        ),
+        parse(
+            "{
+                port: 8003,
+                workers: 7,
+                share: 0.75,
+                half: 3,
+                third: 4,
+                host: 'db-eu-3',
+                url: 'https://eu.example.com/${path}',
+                tags: ['a'],
+                literals: [true, null, -Infinity, 32],
+            }"
+        )
+    );
+    assert_eq!(eval("2147483647 + 1"), Ok(Value::Float(2147483648.0)));
+    assert_eq!(eval("7 / 2"), Ok(Value::Float(3.5)));
+    assert_eq!(eval("1+1"), Ok(Value::Integer(2)));
+    assert_eq!(eval("[1+1, 5-2, 1e-2*100]"), parse("[2, 3, 1.0]"));
+    assert_eq!(eval("{a: 1+2}"), parse("{a: 3}"));
+    assert_eq!(eval("1+instance"), Ok(Value::Integer(4)));
+    assert_eq!(eval("instance-1"), Ok(Value::Integer(2)));
+    assert_eq!(eval("-2147483648 % -1"), Ok(Value::Integer(0)));
+
+    let error = |json: &str| eval(json).unwrap_err().to_string();
+    assert_eq!(
+        error("port + 1"),
+        "unknown variable `port`, line 1 column 1"
+    );
+    assert_eq!(
+        error("'${port}'"),
+        "unknown variable `port`, line 1 column 1"
+    );
+    assert_eq!(error("[1 / 0]"), "division by zero, line 1 column 2");
+    assert_eq!(error("tags * 2"), "`*` needs numbers, line 1 column 1");
+    assert_eq!(
+        error("'a' + tags"),
+        "arrays and objects can't be joined with strings, line 1 column 1"
+    );
+    assert_eq!(
+        error(&"(".repeat(200)),
+        "exceeded maximum nesting depth, line 1 column 129"
+    );
+
+    // Plain parsing is unaffected.
+    assert!(parse("8000 + 1").is_err());
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn render() {
+    let bindings =
+        parse("{ region: 'eu', port: 8080, db: { hosts: ['a', 'b'] }, tls: null }").unwrap();
+
+    assert_eq!(
+        json5::render(
+            "{
+                name: 'api-{{region}}',
+                port: '{{port}}',
+                host: '{{ db.hosts.1 }}',
+                url: 'https://{{db.hosts.0}}:{{port}}/',
+                hosts: '{{db.hosts}}',
+                tls: '{{tls}}',
+                plain: ['{ {region} }', 1],
+            }",
+            &bindings
+        ),
+        parse(
+            "{
+                name: 'api-eu',
+                port: 8080,
+                host: 'b',
+                url: 'https://a:8080/',
+                hosts: ['a', 'b'],
+                tls: null,
+                plain: ['{ {region} }', 1],
+            }"
+        )
+    );
+
+    let error = |template: &str| json5::render(template, &bindings).unwrap_err().to_string();
+    assert_eq!(
+        error("{ a: [1, 'x-{{zone}}'] }"),
+        "unknown placeholder `zone`, line 1 column 10"
+    );
+    assert_eq!(
+        error("'{{db.hosts.2}}'"),
+        "unknown placeholder `db.hosts.2`, line 1 column 1"
+    );
+    assert_eq!(
+        error("['{{region']"),
+        "unterminated placeholder, line 1 column 2"
+    );
+    assert_eq!(
+        error("'hosts: {{db.hosts}}'"),
+        "arrays and objects can't be joined with strings, line 1 column 1"
+    );
+    assert!(json5::render("[", &bindings).is_err());
+}
+
+#[test]
+fn units() {
+    let mut units = Map::new();
+    for (unit, factor) in [
+        ("ms", 0.001),
+        ("s", 1.0),
+        ("m", 60.0),
+        ("d", 86400.0),
+        ("MiB", 1048576.0),
+        ("eV", 1.0),
+        ("%", 0.01),
+    ] {
+        units.insert(unit.to_string(), factor);
+    }
+    let parse_units = |json: &str| json5::parse_with_units(json, &ParseOptions::default(), &units);
+
+    assert_eq!(
+        parse_units("{ timeout: 10s, retry: 1.5m, ttl: 3d, cache: 5MiB, tick: 250ms, ratio: 50%, energy: 2eV, raw: 1e3, hex: 0x10 }"),
+        parse("{ timeout: 10, retry: 90, ttl: 259200, cache: 5242880, tick: 0.25, ratio: 0.5, energy: 2, raw: 1000.0, hex: 16 }")
+    );
+    assert_eq!(parse_units("-2m"), Ok(Value::Integer(-120)));
+    assert_eq!(parse_units("3000MiB"), Ok(Value::Float(3145728000.0)));
+    assert_eq!(
+        parse_units("[1, 10h]").unwrap_err().to_string(),
+        "unknown unit `h`, line 1 column 5"
+    );
+    assert!(parse_units("10 s").is_err());
+
+    // Without units, they remain syntax errors.
+    assert!(parse("10s").is_err());
+}
+
+#[test]
+fn date_literals() {
+    let options = ParseOptions {
+        allow_date_literals: true,
+        ..ParseOptions::default()
+    };
+    let parse_dates = |json: &str| json5::parse_with_options(json, &options);
+
+    assert_eq!(
+        parse_dates(
+            "{ day: 2024-05-01, at: @2024-05-01T00:00:00Z, local: 2024-05-01t09:30, \
+             offset: 2024-05-01T09:30:00.250+02:00, year: 2024, negative: -2024 }"
+        ),
+        parse(
+            "{ day: '2024-05-01', at: '2024-05-01T00:00:00Z', local: '2024-05-01t09:30', \
+             offset: '2024-05-01T09:30:00.250+02:00', year: 2024, negative: -2024 }"
+        )
+    );
+    for invalid in [
+        "2024-13-01",
+        "2024-05-00",
+        "2024-02-31T00:00:00Z",
+        "2023-02-29",
+        "2024-04-31",
+        "@2024-05",
+        "2024-05-01T24:00",
+        "2024-05-01T09:30:00.",
+        "@x",
+    ] {
+        assert_eq!(
+            parse_dates(invalid).unwrap_err().to_string(),
+            "invalid date or time, line 1 column 1",
+            "{}",
+            invalid
+        );
+    }
+    assert_eq!(
+        parse_dates("2024-02-29"),
+        Ok(Value::String("2024-02-29".into()))
+    );
+    assert!(parse("2024-05-01").is_err());
+    assert!(parse("@2024-05-01").is_err());
+
+    // Timestamps are strings, as those converted from `chrono` are.
+    #[cfg(feature = "chrono")]
+    {
+        use chrono::{TimeZone, Utc};
+
+        let value = parse_dates("@2024-05-01T02:00:00+02:00").unwrap();
+        let from = Value::from(Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap());
+        assert!(matches!(from, Value::String(_)));
+        assert_eq!(value.to_datetime(), from.to_datetime());
+    }
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn literals() {
+    let literals = json5::Literals::new()
+        .with("upper", |s| Ok(Value::String(s.to_uppercase())))
+        .with("len", |s| Ok(Value::Integer(s.len() as i32)))
+        .with("port", |s| match s.parse::<u16>() {
+            Ok(port) => Ok(Value::Integer(port.into())),
+            Err(_) => Err(format!("invalid port `{}`", s)),
+        });
+    let parse_literals =
+        |json: &str| json5::parse_with_literals(json, &ParseOptions::default(), &literals);
+
+    assert_eq!(
+        parse_literals("{ name: @upper(api), n: @len(f(x) + 1), port: @port(8080), '@x': 1 }"),
+        parse("{ name: 'API', n: 8, port: 8080, '@x': 1 }")
+    );
+
+    let error = |json: &str| parse_literals(json).unwrap_err().to_string();
+    assert_eq!(
+        error("[@port(99999)]"),
+        "invalid port `99999`, line 1 column 2"
+    );
+    assert_eq!(
+        error("@uuid(1)"),
+        "unknown literal `@uuid`, line 1 column 1"
+    );
+    assert_eq!(
+        error("@upper(a"),
+        "unexpected end of JSON, expected `)`, line 1 column 9"
+    );
+    assert!(parse("@upper(a)").is_err());
+
+    // Dates are still read after `@` where they are allowed.
+    let options = ParseOptions {
+        allow_date_literals: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        json5::parse_with_literals("[@2024-05-01, @upper(a)]", &options, &literals),
+        parse("['2024-05-01', 'A']")
+    );
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn multi() {
+    use json5::MultiValue;
+
+    let json = "{ server: 'a', port: 1, server: 'b', nested: [{ x: 1, x: 2 }], server: 'c', }";
+    let multi = json5::parse_multi(json, &ParseOptions::default()).unwrap();
+
+    let scalar = |json: &str| MultiValue::Scalar(parse(json).unwrap());
+    assert_eq!(
+        multi.get_all("server").collect::<Vec<_>>(),
+        [&scalar("'a'"), &scalar("'b'"), &scalar("'c'")]
+    );
+    assert_eq!(multi.get("server"), Some(&scalar("'c'")));
+    assert_eq!(multi.get_all("missing").count(), 0);
+    assert_eq!(scalar("1").get_all("server").count(), 0);
+
+    let nested = match multi.get("nested") {
+        Some(MultiValue::Array(v)) => &v[0],
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(nested.get_all("x").count(), 2);
+    assert_eq!(multi.clone().into_value(), parse(json).unwrap());
+
+    // Duplicates are kept even where they would be errors.
+    let options = ParseOptions {
+        duplicate_keys: DuplicateKeys::Error,
+        ..ParseOptions::strict()
+    };
+    let multi = json5::parse_multi(r#"{"a": 1, "a": 2}"#, &options).unwrap();
+    assert_eq!(multi.get_all("a").count(), 2);
+    assert!(json5::parse_multi("{ a: 1 }", &options).is_err());
+    assert_eq!(
+        json5::parse_multi(r#"{"a": [1,]}"#, &options)
+            .unwrap_err()
+            .to_string(),
+        "unexpected character `]`, expected a value, line 1 column 10"
+    );
+}
+
+#[test]
+fn duplicates() {
+    let json = "{\n  a: 1,\n  b: { c: 2, c: 3 },\n  a: 'x', // again\n  a: 4,\n}";
+    let span = |line, column, end| Span {
+        start: Position {
+            offset: json
+                .lines()
+                .take(line - 1)
+                .map(|l| l.len() + 1)
+                .sum::<usize>()
+                + column
+                - 1,
+            line,
+            column,
+        },
+        end: Position {
+            offset: json
+                .lines()
+                .take(line - 1)
+                .map(|l| l.len() + 1)
+                .sum::<usize>()
+                + end
+                - 1,
+            line,
+            column: end,
+        },
+    };
+
+    let (value, duplicates) = json5::parse_with_duplicates(json, &ParseOptions::default()).unwrap();
+    assert_eq!(value, parse(json).unwrap());
+    let report: Vec<_> = duplicates
+        .iter()
+        .map(|d| (d.pointer.to_string(), d.kept, d.dropped))
+        .collect();
+    assert_eq!(
+        report,
+        [
+            ("/b/c".to_string(), span(3, 14, 18), span(3, 8, 12)),
+            ("/a".to_string(), span(4, 3, 9), span(2, 3, 7)),
+            ("/a".to_string(), span(5, 3, 7), span(4, 3, 9)),
+        ]
+    );
+
+    let options = ParseOptions {
+        duplicate_keys: DuplicateKeys::FirstWins,
+        ..ParseOptions::default()
+    };
+    let (_, duplicates) = json5::parse_with_duplicates(json, &options).unwrap();
+    assert!(duplicates
+        .iter()
+        .all(|d| d.kept.start.offset < d.dropped.start.offset));
+    assert_eq!(duplicates[2].kept, span(2, 3, 7));
+
+    assert!(
+        json5::parse_with_duplicates("{ a: 1 }", &ParseOptions::default())
+            .unwrap()
+            .1
+            .is_empty()
+    );
+}
+
+#[test]
+fn escaped_keys() {
+    let value = parse(r"{\u0066oo: 1, a\u0062: 2, \u00e9t\u00e9: 3, \u0024_: 4}").unwrap();
+    assert_eq!(value["foo"], Value::Integer(1));
+    assert_eq!(value["ab"], Value::Integer(2));
+    assert_eq!(value["été"], Value::Integer(3));
+    assert_eq!(value["$_"], Value::Integer(4));
+
+    assert_eq!(error_kind(r"{\u0031a: 1}"), ErrorKind::InvalidEscape);
+    assert_eq!(error_kind(r"{a\u002d: 1}"), ErrorKind::InvalidEscape);
+    assert_eq!(error_kind(r"{a\u0020b: 1}"), ErrorKind::InvalidEscape);
+    assert_eq!(error_kind(r"{a\ud800: 1}"), ErrorKind::InvalidEscape);
+    assert_eq!(error_kind(r"{a\u00g0: 1}"), ErrorKind::InvalidEscape);
+    assert_eq!(
+        error_kind(r"{a\x41: 1}"),
+        ErrorKind::UnexpectedCharacter {
+            found: 'x',
+            expected: vec![TokenKind::Char('u')],
+        }
+    );
+}
+
+#[test]
+fn reserved_word_keys() {
+    let value = parse("{null: 1, true: 2, if: 3, Infinity: 4, NaN: null, nullable: 5}").unwrap();
+    assert_eq!(value["null"], Value::Integer(1));
+    assert_eq!(value["true"], Value::Integer(2));
+    assert_eq!(value["if"], Value::Integer(3));
+    assert_eq!(value["Infinity"], Value::Integer(4));
+    assert_eq!(value["NaN"], Value::Null);
+    assert_eq!(value["nullable"], Value::Integer(5));
+
+    // Other identifiers at the edges of what keys may be.
+    let value = parse("{$: 1, _: 2, ünï: 3, a\u{200C}b: 4, x1: 5}").unwrap();
+    assert_eq!(value["$"], Value::Integer(1));
+    assert_eq!(value["_"], Value::Integer(2));
+    assert_eq!(value["ünï"], Value::Integer(3));
+    assert_eq!(value["a\u{200C}b"], Value::Integer(4));
+    assert_eq!(value["x1"], Value::Integer(5));
+    assert!(parse("{1x: 1}").is_err());
+    assert!(parse("{a-b: 1}").is_err());
+
+    let options = ParseOptions {
+        allow_reserved_word_keys: false,
+        ..ParseOptions::default()
+    };
+    let error = json5::parse_with_options("{a: 1,\n  null: 2}", &options).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "reserved word `null` as a key must be quoted, line 2 column 3"
+    );
+    assert_eq!(error.kind(), &ErrorKind::ReservedWordKey("null".into()));
+    assert_eq!(error.kind().code(), "reserved_word_key");
+    assert!(json5::parse_with_options("{'null': 1, \"true\": 2, nullable: 3}", &options).is_ok());
+}
+
+#[test]
+fn key_spans() {
+    let json = "{a: 1, 'b c': {\"d\": [{e: null}]}}";
+    let (value, spans) = json5::parse_with_key_spans(json).unwrap();
+
+    assert_eq!(value, parse(json).unwrap());
+    assert_eq!(
+        spans
+            .iter()
+            .map(|(pointer, span)| (
+                pointer.to_string(),
+                &json[span.start.offset..span.end.offset]
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("/a".to_string(), "a"),
+            ("/b c".to_string(), "'b c'"),
+            ("/b c/d".to_string(), "\"d\""),
+            ("/b c/d/0/e".to_string(), "e"),
+        ]
+    );
+    assert_eq!(spans[1].1.start.column, 8);
+}
+
+#[test]
+fn const_options() {
+    const DEFAULT: ParseOptions = ParseOptions::new();
+    static STRICT: ParseOptions = ParseOptions::strict();
+    assert_eq!(DEFAULT, ParseOptions::default());
+    assert!(!STRICT.allow_comments);
+    assert_eq!(STRICT, ParseOptions::strict());
+}
@@ -0,0 +1,413 @@
+extern crate json5;
+
+use json5::{parse, stringify, Map, Pointer, Value};
+
+#[test]
+fn it_works() {
+    assert_eq!(stringify(&Value::Null), "null");
+    assert_eq!(stringify(&Value::Boolean(true)), "true");
+    assert_eq!(stringify(&Value::Integer(-42)), "-42");
+    assert_eq!(stringify(&Value::Float(12.0)), "12.0");
+    assert_eq!(stringify(&Value::Float(-0.33)), "-0.33");
+    assert_eq!(stringify(&Value::Float(1e100)), "1e100");
+    assert_eq!(stringify(&Value::Float(f64::NAN)), "NaN");
+    assert_eq!(stringify(&Value::Float(f64::NEG_INFINITY)), "-Infinity");
+    assert_eq!(
+        stringify(&Value::String("say \"hi\"\n\t\u{0001}\u{2028}".to_string())),
+        r#""say \"hi\"\n\t\u0001\u2028""#
+    );
+    assert_eq!(
+        stringify(&Value::Array(vec![
+            Value::Integer(1),
+            Value::Boolean(false)
+        ])),
+        "[1,false]"
+    );
+    assert_eq!(
+        stringify(&Value::Object({
+            let mut m = Map::new();
+            m.insert("a b".to_string(), Value::Array(vec![]));
+            m
+        })),
+        r#"{"a b":[]}"#
+    );
+}
+
+#[test]
+fn round_trip() {
+    let value = parse(
+        r#"{
+            "name": 'json5 "parser"',
+            "numbers": [0, -1, 2.5, 1e-7, Infinity, -0x10],
+            "nested": { "empty": {}, "list": [[null]] },
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(parse(&stringify(&value)), Ok(value));
+}
+
+#[cfg(feature = "testing")]
+mod property {
+    use json5::{parse, stringify, Value};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn round_trip(value in any::<Value>()) {
+            prop_assert_eq!(parse(&stringify(&value)), Ok(value));
+        }
+
+        #[test]
+        fn round_trip_deep(value in json5::testing::value_strategy(16, 256, 4)) {
+            prop_assert_eq!(parse(&stringify(&value)), Ok(value));
+        }
+    }
+
+    #[test]
+    fn round_trip_arbitrary() {
+        use arbitrary::Unstructured;
+
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..256 {
+            let bytes: Vec<u8> = (0..512)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    seed as u8
+                })
+                .collect();
+            let value: Value = Unstructured::new(&bytes).arbitrary().unwrap();
+
+            assert_eq!(parse(&stringify(&value)), Ok(value));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn preserve_order() {
+    let value = parse("{ 'b': 1, 'c': { 'z': 2, 'a': 3 }, 'a': 4 }").unwrap();
+
+    assert_eq!(stringify(&value), r#"{"b":1,"c":{"z":2,"a":3},"a":4}"#);
+}
+
+#[test]
+#[cfg(all(feature = "btree", not(feature = "preserve_order")))]
+fn btree() {
+    let value = parse("{ 'b': 1, 'c': { 'z': 2, 'a': 3 }, 'a': 4 }").unwrap();
+
+    assert_eq!(stringify(&value), r#"{"a":4,"b":1,"c":{"a":3,"z":2}}"#);
+}
+
+#[test]
+fn wrap_strings() {
+    let value = Value::Array(vec![
+        Value::String("the quick brown fox jumps over the lazy dog".to_string()),
+        Value::String("short".to_string()),
+        Value::String("abcdefghij".to_string()),
+    ]);
+    let options = json5::StringifyOptions {
+        wrap_strings_at: Some(8),
+        ..json5::StringifyOptions::default()
+    };
+    let json = json5::stringify_with_options(&value, &options);
+
+    assert_eq!(
+        json,
+        "[\"the \" +\n\"quick \" +\n\"brown \" +\n\"fox \" +\n\"jumps \" +\n\"over \" +\n\"the \" +\n\"lazy dog\",\"short\",\"abcdefgh\" +\n\"ij\"]"
+    );
+
+    let options = json5::ParseOptions {
+        allow_string_concatenation: true,
+        ..json5::ParseOptions::default()
+    };
+    assert_eq!(
+        json5::parse_with_options(&json, &options),
+        Ok(value.clone())
+    );
+    assert!(parse(&json).is_err());
+
+    let crlf = json5::StringifyOptions {
+        wrap_strings_at: Some(8),
+        line_ending: json5::LineEnding::CrLf,
+        ..json5::StringifyOptions::default()
+    };
+    assert_eq!(
+        json5::stringify_with_options(&value, &crlf),
+        json.replace('\n', "\r\n")
+    );
+    assert!(json5::parse_with_options("'a' + 1", &options).is_err());
+    assert_eq!(
+        json5::parse_with_options("{ 'a': 'x' /* c */ + 'y', 'b': 'z' }", &options).unwrap()["a"],
+        Value::String("xy".to_string())
+    );
+}
+
+#[test]
+fn sort_keys() {
+    let value = parse("{ c: 1, a: { z: 2, b: 3 }, b: [{ y: 4, x: 5 }] }").unwrap();
+    let options = json5::StringifyOptions {
+        sort_keys: true,
+        ..json5::StringifyOptions::default()
+    };
+
+    assert_eq!(
+        json5::stringify_with_options(&value, &options),
+        r#"{"a":{"b":3,"z":2},"b":[{"x":5,"y":4}],"c":1}"#
+    );
+}
+
+#[test]
+fn non_finite() {
+    use json5::NonFinite;
+
+    let value = Value::Array(vec![
+        Value::Float(f64::NAN),
+        Value::Float(f64::INFINITY),
+        Value::Float(f64::NEG_INFINITY),
+        Value::Float(1.5),
+    ]);
+    let with = |non_finite| {
+        let options = json5::StringifyOptions {
+            non_finite,
+            ..json5::StringifyOptions::default()
+        };
+        json5::stringify_with_options(&value, &options)
+    };
+
+    assert_eq!(with(NonFinite::Literal), "[NaN,Infinity,-Infinity,1.5]");
+    assert_eq!(with(NonFinite::Null), "[null,null,null,1.5]");
+    assert_eq!(
+        with(NonFinite::String),
+        r#"["NaN","Infinity","-Infinity",1.5]"#
+    );
+    assert_eq!(
+        with(NonFinite::Clamp),
+        "[null,1.7976931348623157e308,-1.7976931348623157e308,1.5]"
+    );
+    assert_eq!(with(NonFinite::Error), with(NonFinite::Literal));
+
+    let options = json5::StringifyOptions {
+        non_finite: NonFinite::Error,
+        ..json5::StringifyOptions::default()
+    };
+    let nested = parse("{ a: [1, 2.5, { b: -Infinity }] }").unwrap();
+    assert_eq!(
+        json5::try_stringify_with_options(&nested, &options)
+            .unwrap_err()
+            .to_string(),
+        "-Infinity can't be written as JSON at /a/2/b"
+    );
+    assert_eq!(
+        json5::try_stringify_with_options(&value[3], &options),
+        Ok("1.5".to_string())
+    );
+}
+
+#[test]
+fn float_precision() {
+    let value = Value::Array(vec![
+        Value::Float(0.1 + 0.2),
+        Value::Float(2.71234567),
+        Value::Float(1234567.0),
+        Value::Float(-0.000123456),
+        Value::Float(2.0),
+        Value::Integer(123456789),
+    ]);
+    let options = json5::StringifyOptions {
+        significant_digits: Some(3),
+        ..json5::StringifyOptions::default()
+    };
+    assert_eq!(
+        json5::stringify_with_options(&value, &options),
+        "[0.3,2.71,1230000.0,-0.000123,2.0,123456789]"
+    );
+    assert_eq!(stringify(&value[0]), "0.30000000000000004");
+
+    #[cfg(feature = "hints")]
+    {
+        use json5::ScalarHint;
+
+        let value = parse("{ price: 2.5, ratio: 0.125, count: 3, nan: NaN }").unwrap();
+        let hints = [
+            (
+                Pointer::root().key("price"),
+                ScalarHint::Fixed { decimals: 2 },
+            ),
+            (
+                Pointer::root().key("ratio"),
+                ScalarHint::Fixed { decimals: 1 },
+            ),
+            (
+                Pointer::root().key("count"),
+                ScalarHint::Fixed { decimals: 2 },
+            ),
+            (
+                Pointer::root().key("nan"),
+                ScalarHint::Fixed { decimals: 2 },
+            ),
+        ];
+        let out = json5::stringify_with_hints(&value, &hints);
+        for part in [
+            r#""price":2.50"#,
+            r#""ratio":0.1"#,
+            r#""count":3"#,
+            r#""nan":NaN"#,
+        ] {
+            assert!(out.contains(part), "{} in {}", part, out);
+        }
+
+        // `Fixed` wins over `significant_digits`, which still rounds the rest.
+        let value = parse("{ price: 2.456, ratio: 0.123456, n: 1234.5678 }").unwrap();
+        let hints = [
+            (
+                Pointer::root().key("price"),
+                ScalarHint::Fixed { decimals: 2 },
+            ),
+            (Pointer::root().key("n"), ScalarHint::Exponent),
+        ];
+        let options = json5::StringifyOptions {
+            significant_digits: Some(2),
+            sort_keys: true,
+            ..json5::StringifyOptions::default()
+        };
+        assert_eq!(
+            json5::stringify_with_hints_and_options(&value, &hints, &options),
+            r#"{"n":1.2e3,"price":2.46,"ratio":0.12}"#
+        );
+    }
+}
+
+#[test]
+fn serialize_pointer() {
+    let value = parse("{ services: [{ name: 'api', env: { b: 2, a: 1 } }], port: 80 }").unwrap();
+    let options = json5::StringifyOptions {
+        sort_keys: true,
+        ..json5::StringifyOptions::default()
+    };
+
+    let pointer = |s| Pointer::parse(s).unwrap();
+    assert_eq!(
+        value
+            .serialize_pointer(&pointer("/services/0"), &options)
+            .as_deref(),
+        Some(r#"{"env":{"a":1,"b":2},"name":"api"}"#)
+    );
+    assert_eq!(
+        value
+            .serialize_pointer(&pointer("/port"), &options)
+            .as_deref(),
+        Some("80")
+    );
+    assert_eq!(
+        value.serialize_pointer(&Pointer::root(), &options),
+        Some(json5::stringify_with_options(&value, &options))
+    );
+    assert_eq!(
+        value.serialize_pointer(&pointer("/services/1"), &options),
+        None
+    );
+}
+
+#[test]
+#[cfg(feature = "hints")]
+fn hints() {
+    use json5::ScalarHint;
+
+    let json =
+        "{ name: 'app', mask: 0xFF, flags: [0x1a, -0x2], rate: 1.5e3, small: 2E-5, plain: 1.5 }";
+    let (mut value, hints) = json5::parse_with_hints(json).unwrap();
+
+    let mut found: Vec<_> = hints.iter().map(|(p, h)| (p.to_string(), *h)).collect();
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        found
+            .iter()
+            .map(|(p, h)| (p.as_str(), *h))
+            .collect::<Vec<_>>(),
+        vec![
+            ("/flags/0", ScalarHint::Hex { uppercase: false }),
+            ("/flags/1", ScalarHint::Hex { uppercase: false }),
+            ("/mask", ScalarHint::Hex { uppercase: true }),
+            ("/name", ScalarHint::SingleQuoted),
+            ("/rate", ScalarHint::Exponent),
+            ("/small", ScalarHint::Exponent),
+        ]
+    );
+
+    if let Value::Object(map) = &mut value {
+        map.insert("name".to_string(), Value::String("it's".to_string()));
+        map.insert("mask".to_string(), Value::Integer(4096));
+        map.insert("rate".to_string(), Value::Float(2500.0));
+        map.insert(
+            "small".to_string(),
+            Value::String("now a string".to_string()),
+        );
+    }
+    let out = json5::stringify_with_hints(&value, &hints);
+    assert_eq!(json5::parse(&out).unwrap(), value);
+
+    for part in [
+        r#""name":'it\'s'"#,
+        r#""mask":0x1000"#,
+        r#""flags":[0x1a,-0x2]"#,
+        r#""rate":2.5e3"#,
+        r#""small":"now a string""#,
+        r#""plain":1.5"#,
+    ] {
+        assert!(out.contains(part), "{} in {}", part, out);
+    }
+}
+
+#[test]
+fn trailing_commas() {
+    let value = parse("{a: [1, [], {}], b: 'the quick brown fox'}").unwrap();
+    let stringify = |trailing_commas, wrap_strings_at| {
+        let options = json5::StringifyOptions {
+            trailing_commas,
+            wrap_strings_at,
+            sort_keys: true,
+            ..json5::StringifyOptions::default()
+        };
+        json5::stringify_with_options(&value, &options)
+    };
+
+    assert_eq!(
+        stringify(json5::TrailingCommas::Always, None),
+        r#"{"a":[1,[],{},],"b":"the quick brown fox",}"#
+    );
+    assert_eq!(
+        stringify(json5::TrailingCommas::Never, None),
+        r#"{"a":[1,[],{}],"b":"the quick brown fox"}"#
+    );
+    assert_eq!(
+        stringify(json5::TrailingCommas::Multiline, None),
+        stringify(json5::TrailingCommas::Never, None)
+    );
+    assert_eq!(
+        stringify(json5::TrailingCommas::Multiline, Some(10)),
+        "{\"a\":[1,[],{}],\"b\":\"the quick \" +\n\"brown fox\",}"
+    );
+}
+
+#[test]
+fn numbers_per_line() {
+    let value = parse("[[1, 2, 3, 4, 5.5], [1, 2], [1, 'a', 3, 4]]").unwrap();
+    let options = json5::StringifyOptions {
+        numbers_per_line: Some(2),
+        trailing_commas: json5::TrailingCommas::Multiline,
+        ..json5::StringifyOptions::default()
+    };
+    let json = json5::stringify_with_options(&value, &options);
+
+    assert_eq!(json, "[[1,2,\n3,4,\n5.5,],[1,2],[1,\"a\",3,4],]");
+    assert_eq!(parse(&json).unwrap(), value);
+}
+
+#[test]
+fn const_options() {
+    const DEFAULT: json5::StringifyOptions = json5::StringifyOptions::new();
+    assert_eq!(DEFAULT, json5::StringifyOptions::default());
+}
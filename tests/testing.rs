@@ -0,0 +1,39 @@
+#![cfg(feature = "testing")]
+
+extern crate json5;
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+
+use json5::{assert_json5_snapshot, parse};
+
+#[test]
+fn snapshots() {
+    let dir = std::env::temp_dir().join(format!("json5-snapshots-{}", std::process::id()));
+    let path = dir.join("nested/config.json5");
+    let value = parse("{ b: [1, { y: 'x', x: null }], a: 2.5 }").unwrap();
+
+    assert_json5_snapshot!(value, &path);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "{\n  \"a\": 2.5,\n  \"b\": [\n    1,\n    {\n      \"x\": null,\n      \"y\": \"x\"\n    }\n  ]\n}\n"
+    );
+    assert_json5_snapshot!(value.clone(), &path);
+
+    let changed = parse("{ b: [1, { y: 'z' }, 3], a: 2.5, c: true }").unwrap();
+    let message = panic::catch_unwind(AssertUnwindSafe(|| {
+        json5::testing::assert_snapshot(&changed, &path)
+    }))
+    .unwrap_err()
+    .downcast::<String>()
+    .unwrap();
+    assert_eq!(
+        *message,
+        format!(
+            "value doesn't match snapshot {}:\n  /b/1/x: missing\n  /b/1/y: expected \"x\", found \"z\"\n  /b/2: unexpected 3\n  /c: unexpected true\n(set JSON5_UPDATE_SNAPSHOTS to update it)",
+            path.display()
+        )
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "toml")]
+
+extern crate json5;
+
+use std::convert::TryInto;
+
+use json5::{parse, Value};
+
+#[test]
+fn from_toml() {
+    let toml = r#"
+title = "app"
+ports = [80, 443]
+mixed = [1, "two", { three = 3 }]
+big = 10000000000
+
+[server]
+host = "localhost"
+started = 1979-05-27T07:32:00Z
+"#;
+    let expected = parse(
+        r#"{
+            title: 'app',
+            ports: [80, 443],
+            mixed: [1, 'two', { three: 3 }],
+            big: 10000000000.0,
+            server: { host: 'localhost', started: '1979-05-27T07:32:00Z' },
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(Value::from_toml_str(toml), Ok(expected));
+
+    let error = Value::from_toml_str("a = 1\nb = ").unwrap_err();
+    assert_eq!(error.span().unwrap().start.line, 2);
+}
+
+#[test]
+fn to_toml() {
+    let value = parse("{ a: 1, b: null, c: { d: [true, 'x'] }, e: NaN }").unwrap();
+    let back = Value::from_toml_str(&value.to_toml_string().unwrap()).unwrap();
+
+    assert_eq!(back["a"], Value::Integer(1));
+    assert!(!back.contains_key("b"));
+    assert_eq!(back["c"], parse("{ d: [true, 'x'] }").unwrap());
+    assert!(back["e"].to_f64().unwrap().is_nan());
+
+    let toml: toml::Value = (&parse("{ a: [1, 2.5] }").unwrap()).try_into().unwrap();
+    assert_eq!(toml["a"][1].as_float(), Some(2.5));
+
+    assert_eq!(
+        parse("{ a: { b: [1, null] } }")
+            .unwrap()
+            .to_toml_string()
+            .unwrap_err()
+            .to_string(),
+        "null can't be represented in TOML at /a/b/1"
+    );
+    assert_eq!(
+        parse("[1]")
+            .unwrap()
+            .to_toml_string()
+            .unwrap_err()
+            .to_string(),
+        "a TOML document must be a table"
+    );
+}
@@ -1,9 +1,9 @@
 extern crate json5;
 
-use json5::Value;
-use std::collections::HashMap;
+use json5::{KeyMatching, Map, Pointer, Scalar, Value};
 
 #[test]
+#[allow(deprecated, clippy::bool_comparison)]
 fn index() {
     let null = Value::Null;
     let integer = Value::Integer(4);
@@ -14,7 +14,7 @@ fn index() {
         Value::Array(vec![Value::Integer(5), Value::Integer(5)]),
     ]);
     let object = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = Map::new();
         map.insert("foo".to_string(), Value::Integer(10));
         map.insert(
             "bar".to_string(),
@@ -34,14 +34,1097 @@ fn index() {
 }
 
 #[test]
+fn pointer() {
+    let value = json5::parse(
+        r#"{ "servers": [{ "host": "a" }, { "host": "b" }], "a/b": { "~": 1 }, "01": 2 }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value["servers".to_string()][1]["host"],
+        value["servers"][1]["host"]
+    );
+    assert_eq!(value[&"01".to_string()], Value::Integer(2));
+    assert_eq!(
+        value.at(["servers", "1", "host"]),
+        &Value::String("b".to_string())
+    );
+    assert!(value.at(vec!["servers", "01", "host"]).is_null());
+    assert!(value.at(["servers", "2", "host"]).is_null());
+    assert_eq!(value.at(Vec::<String>::new()), &value);
+
+    let pointer = Pointer::parse("/a~1b/~0").unwrap();
+    assert_eq!(pointer.tokens(), ["a/b", "~"]);
+    assert_eq!(pointer.to_string(), "/a~1b/~0");
+    assert_eq!(value[&pointer], Value::Integer(1));
+    assert_eq!(
+        value[&Pointer::parse("/servers/0/host").unwrap()],
+        Value::String("a".to_string())
+    );
+    assert_eq!(value[&Pointer::parse("").unwrap()], value);
+    assert!(Pointer::parse("servers").is_none());
+    assert_eq!(
+        ["servers", "0"].iter().copied().collect::<Pointer>(),
+        Pointer::parse("/servers/0").unwrap()
+    );
+}
+
+#[test]
+fn pointer_builder() {
+    let mut value = json5::parse("{ servers: [{ host: 'a' }], 'a/b': { '~': 1 } }").unwrap();
+
+    let host = Pointer::root().key("servers").index(0).key("host");
+    assert_eq!(host.to_string(), "/servers/0/host");
+    assert_eq!("/servers/0/host".parse::<Pointer>().unwrap(), host);
+    assert_eq!(value[&host], Value::String("a".to_string()));
+    assert_eq!(Pointer::root().key("a/b").key("~").to_string(), "/a~1b/~0");
+    assert_eq!(
+        "servers".parse::<Pointer>().unwrap_err().to_string(),
+        "invalid JSON Pointer `servers`, expected `/` first"
+    );
+
+    assert_eq!(
+        host.parent().and_then(|p| p.parent()),
+        Some(Pointer::root().key("servers"))
+    );
+    assert!(Pointer::root().is_root());
+    assert!(Pointer::root().parent().is_none());
+
+    *value.pointer_mut(&host).unwrap() = Value::String("b".to_string());
+    assert_eq!(value.pointer(&host), Some(&Value::String("b".to_string())));
+    assert!(value
+        .pointer_mut(&Pointer::root().key("servers").index(1))
+        .is_none());
+    assert!(value.pointer_mut(&Pointer::root()).is_some());
+}
+
+#[test]
+#[allow(deprecated)]
 fn to_i32() {
     assert!(Value::Integer(-3).to_i32().unwrap() == -3);
     assert!(Value::Float(12.3).to_i32().unwrap() == 12);
     assert!(Value::Boolean(true).to_i32().is_none());
 }
 
+#[test]
+fn to_i64_exact() {
+    assert_eq!(Value::Integer(-3).to_i64_exact(), Some(-3));
+    assert_eq!(Value::Float(12.0).to_i64_exact(), Some(12));
+    assert_eq!(Value::Float(12.0).to_i64(), None);
+    assert_eq!(Value::Float(12.9).to_i64_exact(), None);
+    assert_eq!(Value::Float(i64::MIN as f64).to_i64_exact(), Some(i64::MIN));
+    assert_eq!(Value::Float(-(i64::MIN as f64)).to_i64_exact(), None);
+    assert_eq!(Value::Float(f64::NAN).to_i64_exact(), None);
+    assert_eq!(Value::Boolean(true).to_i64_exact(), None);
+}
+
+#[test]
+fn to_i64_lossy() {
+    assert_eq!(Value::Integer(7).to_i64_lossy(), Some(7));
+    assert_eq!(Value::Float(12.9).to_i64_lossy(), Some(12));
+    assert_eq!(Value::Float(-12.9).to_i64_lossy(), Some(-12));
+    assert_eq!(Value::Float(1e300).to_i64_lossy(), Some(i64::MAX));
+    assert_eq!(Value::Float(f64::NAN).to_i64_lossy(), None);
+    assert_eq!(Value::String("7".to_string()).to_i64_lossy(), None);
+}
+
 #[test]
 fn to_f64() {
     assert!(Value::Integer(55).to_f64().unwrap() == 55.0);
     assert!(Value::Float(1.23).to_f64().unwrap() == 1.23);
 }
+
+#[test]
+fn len() {
+    let value =
+        json5::parse("{ 'a': [1, 2.5, 'x'], 'b': 'héllo', 'c': {}, 'd': null, 'e': 7 }").unwrap();
+
+    assert_eq!(value.len(), 5);
+    assert_eq!(value["a"].len(), 3);
+    assert_eq!(value["b"].len(), 5);
+    assert!(value["c"].is_empty());
+    assert!(value["d"].is_empty());
+    assert_eq!(value["e"].len(), 0);
+    assert!(!value.is_empty());
+
+    assert!(value.contains_key("d"));
+    assert!(!value.contains_key("f"));
+    assert!(!value["a"].contains_key("0"));
+
+    assert!(value["a"].contains(&Value::Float(2.5)));
+    assert!(value["a"].contains(&Value::String("x".to_string())));
+    assert!(!value["a"].contains(&Value::Integer(3)));
+    assert!(!value["b"].contains(&Value::String("h".to_string())));
+}
+
+#[test]
+fn array_helpers() {
+    let mut value = json5::parse("[3, 1, 2]").unwrap();
+
+    assert!(value.push(Value::Integer(1)));
+    assert!(value.insert_at(0, Value::Integer(2)));
+    assert!(value.insert_at(5, Value::Integer(3)));
+    assert!(!value.insert_at(7, Value::Integer(0)));
+    assert_eq!(json5::stringify(&value), "[2,3,1,2,1,3]");
+
+    assert_eq!(value.remove_at(1), Some(Value::Integer(3)));
+    assert_eq!(value.remove_at(5), None);
+    assert!(value.extend(vec![Value::Integer(2), Value::Integer(2)]));
+    assert!(value.sort_by(|a, b| a.to_i64_exact().cmp(&b.to_i64_exact())));
+    assert_eq!(json5::stringify(&value), "[1,1,2,2,2,2,3]");
+    assert!(value.dedup());
+    assert_eq!(json5::stringify(&value), "[1,2,3]");
+
+    let mut object = json5::parse("{}").unwrap();
+    assert!(!object.push(Value::Null));
+    assert!(!object.insert_at(0, Value::Null));
+    assert_eq!(object.remove_at(0), None);
+    assert!(!object.extend(vec![Value::Null]));
+    assert!(!object.sort_by(|_, _| std::cmp::Ordering::Equal));
+    assert!(!object.dedup());
+    assert_eq!(object, Value::Object(Map::new()));
+}
+
+#[test]
+fn redact() {
+    let mut value = json5::parse(
+        r#"{
+            "user": "admin",
+            "password": "hunter2",
+            "services": [{ "name": "db", "token": { "id": 1 } }],
+        }"#,
+    )
+    .unwrap();
+
+    value.redact(|key| key == "password" || key.ends_with("token"));
+
+    assert_eq!(value["user"], Value::String("admin".to_string()));
+    assert_eq!(value["password"], Value::String("[REDACTED]".to_string()));
+    assert_eq!(
+        value["services"][0]["name"],
+        Value::String("db".to_string())
+    );
+    assert_eq!(
+        value["services"][0]["token"],
+        Value::String("[REDACTED]".to_string())
+    );
+}
+
+#[cfg(feature = "duration")]
+#[test]
+fn to_duration() {
+    use std::time::Duration;
+
+    let string = |s: &str| Value::String(s.to_string());
+
+    assert_eq!(string("30s").to_duration(), Some(Duration::from_secs(30)));
+    assert_eq!(string("5m").to_duration(), Some(Duration::from_secs(300)));
+    assert_eq!(
+        string("1h30m").to_duration(),
+        Some(Duration::from_secs(5400))
+    );
+    assert_eq!(
+        string("250ms").to_duration(),
+        Some(Duration::from_millis(250))
+    );
+    assert_eq!(
+        string("1.5s").to_duration(),
+        Some(Duration::from_millis(1500))
+    );
+    assert!(string("30").to_duration().is_none());
+    assert!(string("5 fortnights").to_duration().is_none());
+    assert!(string("18446744073709551615s").to_duration().is_none());
+    assert!(Value::Integer(30).to_duration().is_none());
+}
+
+#[cfg(feature = "byte-size")]
+#[test]
+fn to_byte_size() {
+    let string = |s: &str| Value::String(s.to_string());
+
+    assert_eq!(string("10MiB").to_byte_size(), Some(10 * 1024 * 1024));
+    assert_eq!(string("1.5 KB").to_byte_size(), Some(1500));
+    assert_eq!(string("512").to_byte_size(), Some(512));
+    assert_eq!(Value::Integer(64).to_byte_size(), Some(64));
+    assert!(Value::Integer(-1).to_byte_size().is_none());
+    assert!(string("0.5B").to_byte_size().is_none());
+    assert!(string("10 parsecs").to_byte_size().is_none());
+    assert!(string("16777216TiB").to_byte_size().is_none());
+    assert!(string("18446744073709551616").to_byte_size().is_none());
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn to_url() {
+    let url = Value::String("https://example.com:8443/api".to_string())
+        .to_url()
+        .unwrap();
+
+    assert_eq!(url.host_str(), Some("example.com"));
+    assert_eq!(url.port(), Some(8443));
+    assert!(Value::String("not a url".to_string()).to_url().is_none());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn to_datetime() {
+    use chrono::{TimeZone, Utc};
+
+    let value = Value::String("2024-05-01T12:30:00+09:00".to_string());
+    let datetime = value.to_datetime().unwrap();
+
+    assert_eq!(
+        datetime,
+        Utc.with_ymd_and_hms(2024, 5, 1, 3, 30, 0).unwrap()
+    );
+    assert!(Value::String("2024-05-01".to_string())
+        .to_datetime()
+        .is_none());
+
+    let value = Value::from(Utc.with_ymd_and_hms(2024, 5, 1, 3, 30, 0).unwrap());
+
+    assert_eq!(
+        value,
+        Value::String("2024-05-01T03:30:00+00:00".to_string())
+    );
+    assert_eq!(value.to_datetime().unwrap(), datetime);
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn to_uuid() {
+    use uuid::Uuid;
+
+    let value = Value::String("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string());
+    let uuid = value.to_uuid().unwrap();
+
+    assert_eq!(uuid.as_u128(), 0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+    assert_eq!(Value::from(uuid), value);
+    assert!(Value::String("67e55044".to_string()).to_uuid().is_none());
+    assert!(Value::Integer(1).to_uuid().is_none());
+    assert_eq!(Value::from(Uuid::nil()).to_uuid(), Some(Uuid::nil()));
+}
+
+#[test]
+fn sized_numbers() {
+    assert_eq!(Value::Integer(255).to_u8(), Some(255));
+    assert_eq!(Value::Integer(256).to_u8(), None);
+    assert_eq!(Value::Integer(-1).to_u8(), None);
+    assert_eq!(Value::Integer(8080).to_u16(), Some(8080));
+    assert_eq!(Value::Integer(70000).to_u16(), None);
+    assert_eq!(Value::Integer(70000).to_u32(), Some(70000));
+    assert_eq!(Value::Integer(i32::MAX).to_u64(), Some(i32::MAX as u64));
+    assert_eq!(Value::Integer(-5).to_i64(), Some(-5));
+    assert_eq!(Value::Integer(i32::MIN).to_i128(), Some(i32::MIN as i128));
+    assert_eq!(Value::Float(8080.0).to_u16(), None);
+    assert_eq!(Value::String("1".to_string()).to_i64(), None);
+
+    assert_eq!(Value::Integer(3).to_f32(), Some(3.0));
+    assert_eq!(Value::Float(0.5).to_f32(), Some(0.5));
+    assert_eq!(Value::Float(f64::INFINITY).to_f32(), Some(f32::INFINITY));
+    assert_eq!(Value::Float(1e300).to_f32(), None);
+    assert_eq!(Value::Null.to_f32(), None);
+}
+
+#[test]
+#[cfg(feature = "codegen")]
+fn to_rust_tokens() {
+    let value =
+        json5::parse("{a: {}, b: [1, -2.5, 'x\"\\n'], c: [NaN, -Infinity, null, true]}").unwrap();
+    let tokens = value.to_rust_tokens();
+
+    assert_eq!(
+        tokens,
+        "::json5::Value::Object(::core::iter::IntoIterator::into_iter([\
+         (\"a\".into(), ::json5::Value::Object(::json5::Map::new())), \
+         (\"b\".into(), ::json5::Value::Array([::json5::Value::Integer(1), \
+         ::json5::Value::Float(-2.5f64), ::json5::Value::String(\"x\\\"\\n\".into())].into())), \
+         (\"c\".into(), ::json5::Value::Array([::json5::Value::Float(f64::NAN), \
+         ::json5::Value::Float(f64::NEG_INFINITY), ::json5::Value::Null, \
+         ::json5::Value::Boolean(true)].into()))]).collect())"
+    );
+
+    // The same code, compiled.
+    let built = ::json5::Value::Object(
+        ::core::iter::IntoIterator::into_iter([
+            ("a".into(), ::json5::Value::Object(::json5::Map::new())),
+            (
+                "b".into(),
+                ::json5::Value::Array(
+                    [
+                        ::json5::Value::Integer(1),
+                        ::json5::Value::Float(-2.5f64),
+                        ::json5::Value::String("x\"\n".into()),
+                    ]
+                    .into(),
+                ),
+            ),
+            (
+                "c".into(),
+                ::json5::Value::Array(
+                    [
+                        ::json5::Value::Float(f64::NAN),
+                        ::json5::Value::Float(f64::NEG_INFINITY),
+                        ::json5::Value::Null,
+                        ::json5::Value::Boolean(true),
+                    ]
+                    .into(),
+                ),
+            ),
+        ])
+        .collect(),
+    );
+    assert_eq!(built.to_rust_tokens(), tokens);
+    assert_eq!(
+        json5::parse("0.1").unwrap().to_rust_tokens(),
+        "::json5::Value::Float(0.1f64)"
+    );
+}
+
+#[test]
+fn at_with() {
+    let value =
+        json5::parse("{ Server: { 'max-retries': 3, max_retries: 4, Ports: [{ TLS: true }] } }")
+            .unwrap();
+
+    let loose = KeyMatching::loose();
+    assert_eq!(
+        value.at_with(["server", "MAX_RETRIES"], loose),
+        &Value::Integer(4)
+    );
+    assert_eq!(
+        value.at_with(["server", "MAX-RETRIES"], loose),
+        &Value::Integer(3)
+    );
+    assert_eq!(
+        value.at_with(["server", "max_retries"], loose),
+        &Value::Integer(4)
+    );
+    assert_eq!(
+        value.at_with(["server", "ports", "0", "tls"], loose),
+        &Value::Boolean(true)
+    );
+    assert!(value.at_with(["server", "ports", "00"], loose).is_null());
+
+    let case = KeyMatching {
+        ignore_case: true,
+        ..KeyMatching::default()
+    };
+    assert_eq!(
+        value.at_with(["SERVER", "MAX-RETRIES"], case),
+        &Value::Integer(3)
+    );
+    assert!(value.at_with(["server", "maxretries"], case).is_null());
+    assert!(value.at_with(["server"], KeyMatching::default()).is_null());
+    assert!(loose.matches("ÉTAT-civil", "état_CIVIL"));
+}
+
+#[test]
+fn get_with() {
+    let value = json5::parse(
+        "{ server: { 'max-retries': 3, max_retries: 4, Max_Timeout: 5, MAX_timeout: 6 } }",
+    )
+    .unwrap();
+    let server = &value["server"];
+    let loose = KeyMatching::loose();
+
+    // Differing only in case is closer than differing in separators.
+    assert_eq!(
+        server.get_with("MAX-RETRIES", loose),
+        Some(&Value::Integer(3))
+    );
+    assert_eq!(
+        server.get_with("Max_Retries", loose),
+        Some(&Value::Integer(4))
+    );
+    assert_eq!(
+        server.get_with("max-retries", loose),
+        Some(&Value::Integer(3))
+    );
+    // Equally close matches go to the key that sorts first.
+    assert_eq!(
+        server.get_with("max_timeout", loose),
+        Some(&Value::Integer(6))
+    );
+    assert_eq!(server.get_with("retries", loose), None);
+    assert_eq!(server.get_with("MAX_RETRIES", KeyMatching::default()), None);
+
+    let pointer = Pointer::parse("/SERVER/Max-Retries").unwrap();
+    assert_eq!(
+        value.pointer_with(&pointer, loose),
+        Some(&Value::Integer(3))
+    );
+    assert_eq!(value.pointer_with(&pointer, KeyMatching::default()), None);
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn normalize_keys() {
+    use json5::{Convention, KeyCollision};
+
+    assert_eq!(Convention::SnakeCase.apply("HTTPServer"), "http_server");
+    assert_eq!(Convention::SnakeCase.apply("maxRetries"), "max_retries");
+    assert_eq!(Convention::KebabCase.apply("Max_Retries"), "max-retries");
+    assert_eq!(Convention::CamelCase.apply("max-retries"), "maxRetries");
+    assert_eq!(Convention::PascalCase.apply("ipv4 address"), "Ipv4Address");
+    assert_eq!(
+        Convention::ScreamingSnakeCase.apply("logLevel2Value"),
+        "LOG_LEVEL2_VALUE"
+    );
+
+    let mut value = json5::parse(
+        "{
+  serverName: 'a',
+  'Listen-Ports': [{ portNumber: 80, 'port-number': 8080, port_number: 8000 }],
+  TimeoutMs: 5,
+  timeout_ms: 10,
+}",
+    )
+    .unwrap();
+    let mut collisions = value.normalize_keys(Convention::SnakeCase);
+    collisions.sort_by(|a, b| a.pointer.cmp(&b.pointer));
+
+    assert_eq!(
+        value,
+        json5::parse("{ server_name: 'a', listen_ports: [{ port_number: 8000 }], timeout_ms: 10 }")
+            .unwrap()
+    );
+    assert_eq!(
+        collisions,
+        vec![
+            KeyCollision {
+                pointer: Pointer::root()
+                    .key("listen_ports")
+                    .index(0)
+                    .key("port_number"),
+                keys: vec![
+                    "port_number".to_string(),
+                    "port-number".to_string(),
+                    "portNumber".to_string(),
+                ],
+            },
+            KeyCollision {
+                pointer: Pointer::root().key("timeout_ms"),
+                keys: vec!["timeout_ms".to_string(), "TimeoutMs".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn columns() {
+    let value = json5::parse(
+        "[{ item: 'a', price: 1.5 }, { item: 'b', price: 'free' }, 3, { item: 'c', price: 2 }]",
+    )
+    .unwrap();
+
+    assert_eq!(
+        value.column("item"),
+        [
+            Some(&Value::String("a".into())),
+            Some(&Value::String("b".into())),
+            None,
+            Some(&Value::String("c".into())),
+        ]
+    );
+    assert_eq!(
+        value.column_with("price", Value::to_f64),
+        [Some(1.5), None, None, Some(2.0)]
+    );
+    assert_eq!(
+        value.columns(&["price", "item", "missing"]),
+        [value.column("price"), value.column("item"), vec![None; 4],]
+    );
+    assert!(value[0].column("item").is_empty());
+    assert_eq!(Value::Null.columns(&["item"]), [Vec::new()]);
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn binary_search_by_key() {
+    let value =
+        json5::parse("[{ id: { n: 1 } }, { id: { n: 3 } }, { id: { n: 3 } }, { id: { n: 8 } }]")
+            .unwrap();
+    let by_number = |a: &Value, b: &Value| a.to_f64().partial_cmp(&b.to_f64()).unwrap();
+
+    let path = ["id", "n"];
+    assert_eq!(
+        value.binary_search_by_key(&path, &Value::Integer(8), by_number),
+        Ok(3)
+    );
+    assert!(value
+        .binary_search_by_key(&path, &Value::Integer(3), by_number)
+        .is_ok_and(|i| i == 1 || i == 2));
+    assert_eq!(
+        value.binary_search_by_key(&path, &Value::Float(2.5), by_number),
+        Err(1)
+    );
+    assert_eq!(
+        value.binary_search_by_key(&path, &Value::Integer(9), by_number),
+        Err(4)
+    );
+    assert_eq!(
+        Value::Null.binary_search_by_key(&path, &Value::Integer(1), by_number),
+        Err(0)
+    );
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn index_by() {
+    let value = json5::parse(
+        "[{ sku: 'a', n: 1 }, { sku: 'b', n: 2 }, { n: 3 }, { sku: 'a', n: 4 }, { sku: { x: 1 } }]",
+    )
+    .unwrap();
+
+    let index = value.index_by(&["sku"]);
+    assert_eq!(index.len(), 5);
+    assert_eq!(index.position(&Value::String("a".into())), Some(0));
+    assert_eq!(index.get(&Value::String("b".into())), Some(&value[1]));
+    assert_eq!(index.position(&Value::Null), Some(2));
+    assert_eq!(index.position(&json5::parse("{ x: 1 }").unwrap()), Some(4));
+    assert_eq!(index.get(&Value::String("c".into())), None);
+    assert!(Value::Null.index_by(&["sku"]).is_empty());
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn resolve_conditions() {
+    let json = "{
+        db: { host: 'localhost', port: 5432 },
+        debug: true,
+        '@if(env=prod)': {
+            db: { host: 'db.internal' },
+            debug: false,
+            '@if(region=eu)': { db: { port: 6432 } },
+        },
+        '@if(env=dev)': { seed: true },
+        replicas: [{ '@if(env=prod)': { n: 3 } }, { '@if(env=dev)': { n: 1 } }],
+    }";
+    let mut value = json5::parse(json).unwrap();
+    let mut conditions = Vec::new();
+    value
+        .resolve_conditions(|condition| {
+            conditions.push(condition.to_string());
+            condition == "env=prod" || condition == "region=eu"
+        })
+        .unwrap();
+    assert_eq!(
+        value,
+        json5::parse(
+            "{
+                db: { host: 'db.internal', port: 6432 },
+                debug: false,
+                replicas: [{ n: 3 }, {}],
+            }"
+        )
+        .unwrap()
+    );
+    conditions.sort();
+    assert_eq!(
+        conditions,
+        ["env=dev", "env=dev", "env=prod", "env=prod", "region=eu"]
+    );
+
+    let error = |json: &str| {
+        json5::parse(json)
+            .unwrap()
+            .resolve_conditions(|_| true)
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        error("{ a: { '@if(x': {} } }"),
+        "unterminated condition at /a/@if(x"
+    );
+    assert_eq!(
+        error("[{ '@if(x)': 1 }]"),
+        "a conditional section must be an object at /0/@if(x)"
+    );
+
+    // Sections that hold are merged in the order of their keys, so the last
+    // one sorted wins, whatever the order in the document.
+    for json in [
+        "{ '@if(b)': { n: 2 }, '@if(a)': { n: 1 } }",
+        "{ '@if(a)': { n: 1 }, '@if(b)': { n: 2 } }",
+    ] {
+        let mut value = json5::parse(json).unwrap();
+        value.resolve_conditions(|_| true).unwrap();
+        assert_eq!(value, json5::parse("{ n: 2 }").unwrap());
+    }
+
+    // Errors are found before anything changes, even in dropped sections.
+    let json = "{ a: 1, '@if(x)': { b: 2 }, c: { '@if(y)': { '@if(z': {} } } }";
+    let mut value = json5::parse(json).unwrap();
+    assert!(value.resolve_conditions(|c| c == "x").is_err());
+    assert_eq!(value, json5::parse(json).unwrap());
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn display_redacted() {
+    use json5::RedactionRules;
+
+    let rules = RedactionRules {
+        max_string_len: 5,
+        max_elements: 3,
+        ..RedactionRules::default()
+    };
+    let value = json5::parse("{ db: { DB_Password: 'hunter2' } }").unwrap();
+    assert_eq!(
+        value.display_redacted(&rules).to_string(),
+        r#"{"db": {"DB_Password": "[REDACTED]"}}"#
+    );
+
+    let value =
+        json5::parse("['abcde', 'abcdef', [1, 2, 3, 4, 5], { api_token: [1] }, null]").unwrap();
+    assert_eq!(
+        format!("{:?}", value.display_redacted(&rules)),
+        r#"["abcde", "abcde"... (1 more characters), [1, 2, 3, ... 2 more], ... 2 more]"#
+    );
+
+    let rules = RedactionRules {
+        secret_keys: vec!["token".into()],
+        max_elements: 0,
+        ..RedactionRules::default()
+    };
+    assert_eq!(value.display_redacted(&rules).to_string(), "[... 5 more]");
+    assert_eq!(
+        value[3]
+            .display_redacted(&RedactionRules::default())
+            .to_string(),
+        r#"{"api_token": "[REDACTED]"}"#
+    );
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn debug() {
+    let value = json5::parse("{ a: [1, 2.5, 'x', [null, [true]], {}] }").unwrap();
+    assert_eq!(
+        format!("{:?}", value.debug()),
+        r#"{"a": [1, 2.5, "x", [null, [true]], {}]}"#
+    );
+    assert_eq!(
+        format!("{:?}", value.debug().max_depth(2).max_elements(3)),
+        r#"{"a": [1, 2.5, "x", ... 2 more]}"#
+    );
+    assert_eq!(
+        format!("{:?}", value["a"].debug().max_depth(1).ellipsis("…")),
+        r#"[1, 2.5, "x", […], {}]"#
+    );
+    assert_eq!(
+        format!("{:#?}", value.debug().max_elements(2)),
+        "{\n    \"a\": [\n        1,\n        2.5,\n        ... 3 more,\n    ],\n}"
+    );
+    let value = json5::parse("{ a: 1, b: 2 }").unwrap();
+    assert_eq!(
+        format!("{:?}", value.debug().max_elements(0)),
+        "{... 2 more}"
+    );
+}
+
+#[test]
+fn coerce() {
+    let string = |s: &str| Value::String(s.to_string());
+
+    assert_eq!(Value::Boolean(true).coerce_bool(), Some(true));
+    assert_eq!(Value::Integer(0).coerce_bool(), Some(false));
+    assert_eq!(Value::Float(1.0).coerce_bool(), Some(true));
+    assert_eq!(Value::Integer(2).coerce_bool(), None);
+    assert_eq!(string(" TRUE ").coerce_bool(), Some(true));
+    assert_eq!(string("0").coerce_bool(), Some(false));
+    assert_eq!(string("yes").coerce_bool(), None);
+    assert_eq!(Value::Null.coerce_bool(), None);
+
+    assert_eq!(string("42").coerce_i64(), Some(42));
+    assert_eq!(string(" -9000000000\n").coerce_i64(), Some(-9_000_000_000));
+    assert_eq!(string("1.5").coerce_i64(), None);
+    assert_eq!(Value::Float(3.0).coerce_i64(), Some(3));
+    assert_eq!(Value::Float(3.5).coerce_i64(), None);
+    assert_eq!(Value::Boolean(true).coerce_i64(), None);
+
+    assert_eq!(string("1.5").coerce_f64(), Some(1.5));
+    assert_eq!(string("1e3").coerce_f64(), Some(1000.0));
+    assert_eq!(Value::Integer(2).coerce_f64(), Some(2.0));
+    assert_eq!(string("").coerce_f64(), None);
+
+    // The strict conversions are unchanged.
+    assert_eq!(string("true").to_bool(), None);
+    assert_eq!(string("42").to_i64_exact(), None);
+}
+
+#[test]
+fn custom() {
+    #[derive(Debug, PartialEq)]
+    struct Decimal(i64, u32);
+
+    impl Scalar for Decimal {
+        fn to_value(&self) -> Value {
+            let scale = 10i64.pow(self.1);
+            Value::String(format!(
+                "{}.{:02$}",
+                self.0 / scale,
+                self.0 % scale,
+                self.1 as usize
+            ))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Id(&'static str);
+
+    impl Scalar for Id {
+        fn to_value(&self) -> Value {
+            Value::String(self.0.into())
+        }
+    }
+
+    let price = Value::custom(Decimal(1250, 2));
+    assert_eq!(price.to_custom::<Decimal>(), Some(&Decimal(1250, 2)));
+    assert!(price.to_custom::<Id>().is_none());
+    assert_eq!(Value::String("12.50".into()).to_custom::<Decimal>(), None);
+
+    assert_eq!(price, Value::custom(Decimal(1250, 2)));
+    assert_eq!(price.clone(), price);
+    assert_ne!(price, Value::custom(Decimal(125, 1)));
+    assert_ne!(Value::custom(Id("12.50")), price);
+    assert_ne!(Value::String("12.50".into()), price);
+
+    let value = Value::Array(vec![price.clone(), Value::custom(Id("a-1"))]);
+    assert_eq!(json5::stringify(&value), r#"["12.50","a-1"]"#);
+    #[cfg(feature = "hash")]
+    assert_eq!(
+        value.structural_hash(),
+        json5::parse(r#"["12.50","a-1"]"#)
+            .unwrap()
+            .structural_hash()
+    );
+    #[cfg(feature = "values")]
+    assert_eq!(
+        format!("{:?}", value.debug()),
+        "[Decimal(1250, 2), Id(\"a-1\")]"
+    );
+}
+
+#[test]
+fn path_macro() {
+    use json5::path;
+
+    let config = json5::parse(
+        "{ servers: [{ host: 'a', port: 80 }, { host: 'b' }], 'log-level': 'info', type: 1 }",
+    )
+    .unwrap();
+    let i = 1;
+
+    assert_eq!(
+        path!(config.servers[0].host),
+        Some(&Value::String("a".to_string()))
+    );
+    assert_eq!(
+        path!(config.servers[i].host),
+        Some(&Value::String("b".to_string()))
+    );
+    assert_eq!(
+        path!(config.servers[0].port).and_then(Value::to_u16),
+        Some(80)
+    );
+    assert_eq!(
+        path!(config."log-level"),
+        Some(&Value::String("info".to_string()))
+    );
+    assert_eq!(path!(config.type), Some(&Value::Integer(1)));
+    assert_eq!(path!(config), Some(&config));
+
+    assert_eq!(path!(config.servers[2].host), None);
+    assert_eq!(path!(config.servers.host), None);
+    assert_eq!(path!(config.missing[0]), None);
+
+    let reference = &config;
+    assert_eq!(
+        path!(reference.servers[1].host),
+        path!(config.servers[1].host)
+    );
+    assert_eq!(
+        path!((config["servers"])[0].port),
+        Some(&Value::Integer(80))
+    );
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn get_and_set_many() {
+    let mut value =
+        json5::parse("{ servers: [{ host: 'a', port: 80 }, { host: 'b' }], name: 'x' }").unwrap();
+    let pointer = |s: &str| s.parse::<Pointer>().unwrap();
+
+    let pointers = [
+        pointer("/servers/1/host"),
+        pointer("/name"),
+        pointer("/servers/0/port"),
+        pointer("/servers/2"),
+        pointer(""),
+        pointer("/servers/0/port"),
+        pointer("/name/0"),
+    ];
+    let expected: Vec<_> = pointers
+        .iter()
+        .map(|p| Some(&value[p]).filter(|v| !v.is_null()))
+        .collect();
+    assert_eq!(value.get_many(&pointers), expected);
+    assert_eq!(value.get_many(&pointers)[4], Some(&value));
+    assert!(value.get_many(&[]).is_empty());
+
+    let set = value.set_many(vec![
+        (pointer("/servers/1/port"), Value::Integer(81)),
+        (pointer("/servers/0/host"), Value::String("c".to_string())),
+        (pointer("/servers/5/host"), Value::Null),
+        (pointer("/new/key"), Value::Null),
+        (pointer("/name"), Value::Integer(1)),
+        (pointer("/name"), Value::Integer(2)),
+        (
+            pointer("/servers/0"),
+            json5::parse("{ host: 'd' }").unwrap(),
+        ),
+    ]);
+    assert_eq!(set, [true, true, false, false, true, true, true]);
+    assert_eq!(
+        value,
+        json5::parse("{ servers: [{ host: 'c' }, { host: 'b', port: 81 }], name: 2 }").unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn aggregates() {
+    use json5::NonNumeric::{Reject, Skip};
+
+    let numbers = json5::parse("[3, 1.5, -2, 1.5]").unwrap();
+    assert_eq!(numbers.sum(Reject), Some(Value::Float(4.0)));
+    assert_eq!(numbers.min(Reject), Some(&Value::Integer(-2)));
+    assert_eq!(numbers.max(Reject), Some(&Value::Integer(3)));
+    assert_eq!(numbers.mean(Reject), Some(1.0));
+
+    let integers = json5::parse("[2147483647, 1]").unwrap();
+    assert_eq!(integers.sum(Reject), Some(Value::Float(2147483648.0)));
+    let integers = json5::parse("[2, 1, 'x', null]").unwrap();
+    assert_eq!(integers.sum(Skip), Some(Value::Integer(3)));
+    assert_eq!(integers.sum(Reject), None);
+    assert_eq!(integers.max(Reject), None);
+    assert_eq!(integers.mean(Skip), Some(1.5));
+
+    let ties = json5::parse("[1, 1.0]").unwrap();
+    assert_eq!(ties.min(Skip), Some(&Value::Integer(1)));
+    assert_eq!(ties.max(Skip), Some(&Value::Integer(1)));
+
+    let empty = json5::parse("['x']").unwrap();
+    assert_eq!(empty.sum(Skip), Some(Value::Integer(0)));
+    assert_eq!(empty.min(Skip), None);
+    assert_eq!(empty.mean(Skip), None);
+    assert_eq!(Value::Integer(1).sum(Skip), None);
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn group_by_and_project() {
+    let rows = json5::parse(
+        "[
+            { name: 'a', region: 'eu', size: { cpu: 2, ram: 4 } },
+            { name: 'b', region: 'us', size: { cpu: 4, ram: 8 } },
+            { name: 'c', region: 'eu', size: 1 },
+            { name: 'd' },
+        ]",
+    )
+    .unwrap();
+
+    let groups = rows.group_by(&["region"]);
+    assert_eq!(groups.len(), 3);
+    assert_eq!(
+        groups["eu"],
+        Value::Array(vec![rows[0].clone(), rows[2].clone()])
+    );
+    assert_eq!(groups["us"], Value::Array(vec![rows[1].clone()]));
+    assert_eq!(groups["null"], Value::Array(vec![rows[3].clone()]));
+    assert_eq!(rows.group_by(&["size", "cpu"])["4"].len(), 1);
+    assert_eq!(Value::Null.group_by(&["region"]), Value::Object(Map::new()));
+
+    let projected = rows.project(&[&["name"][..], &["size", "cpu"], &["missing"]]);
+    assert_eq!(
+        projected,
+        json5::parse("[{ name: 'a', size: { cpu: 2 } }, { name: 'b', size: { cpu: 4 } }, { name: 'c' }, { name: 'd' }]")
+            .unwrap()
+    );
+    assert_eq!(
+        rows[0].project(&[&["region"]]),
+        json5::parse("{ region: 'eu' }").unwrap()
+    );
+    assert_eq!(rows[0].project::<&str>(&[&[]]), rows[0]);
+    assert_eq!(
+        Value::Integer(1).project(&[&["a"]]),
+        Value::Object(Map::new())
+    );
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn sample_and_truncate() {
+    let value = json5::parse(
+        "{ rows: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9], nested: { more: [[1, 2, 3], [4, 5, 6]] } }",
+    )
+    .unwrap();
+
+    let sample = value.sample(3, 42);
+    assert_eq!(sample, value.sample(3, 42));
+    let rows: Vec<_> = sample["rows"]
+        .to_vec()
+        .unwrap()
+        .iter()
+        .map(|v| v.to_i64().unwrap())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows.windows(2).all(|w| w[0] < w[1]));
+    assert!((0..5).any(|seed| value.sample(3, seed)["rows"] != sample["rows"]));
+    assert!(sample["nested"]["more"]
+        .to_vec()
+        .unwrap()
+        .iter()
+        .all(|v| v.len() == 3));
+    assert_eq!(value.sample(20, 1), value);
+    assert_eq!(value.sample(0, 1)["rows"], Value::Array(vec![]));
+
+    let value = json5::parse("{ s: 'héllo', a: [1, 2, 3, ['abc', 'd']] }").unwrap();
+    assert_eq!(
+        value.truncate(4, 2),
+        json5::parse("{ s: 'hé', a: [1, 2, 3, ['ab', 'd']] }").unwrap()
+    );
+    assert_eq!(value["a"].truncate(1, 10), json5::parse("[1]").unwrap());
+    assert_eq!(value.truncate(1, 10).len(), 1);
+    assert_eq!(value.truncate(0, 0), Value::Object(Map::new()));
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn approx_eq() {
+    use json5::EqOptions;
+
+    let parse = |json: &str| json5::parse(json).unwrap();
+    let exact = EqOptions::default();
+
+    assert!(parse("{ a: [1, 2.5], b: 'x' }").approx_eq(&parse("{ b: 'x', a: [1.0, 2.5] }"), &exact));
+    assert!(!parse("[0.1]").approx_eq(&Value::Array(vec![Value::Float(0.1 + 1e-12)]), &exact));
+    assert!(!parse("NaN").approx_eq(&parse("NaN"), &exact));
+    assert!(!parse("[1, 2]").approx_eq(&parse("[2, 1]"), &exact));
+    assert!(!parse("{ a: 1 }").approx_eq(&parse("{ a: 1, b: 2 }"), &exact));
+
+    let epsilon = EqOptions {
+        epsilon: 1e-9,
+        ..EqOptions::default()
+    };
+    assert!(parse("[0.1]").approx_eq(&Value::Array(vec![Value::Float(0.1 + 1e-12)]), &epsilon));
+    assert!(!parse("[0.1]").approx_eq(&parse("[0.2]"), &epsilon));
+
+    let ulps = EqOptions {
+        max_ulps: 4,
+        ..EqOptions::default()
+    };
+    let next = f64::from_bits(1e300f64.to_bits() + 3);
+    assert!(Value::Float(1e300).approx_eq(&Value::Float(next), &ulps));
+    assert!(!Value::Float(1e300).approx_eq(&Value::Float(1.0000001e300), &ulps));
+    assert!(!Value::Float(0.0).approx_eq(&Value::Float(-5e-324), &exact));
+
+    let nan = EqOptions {
+        nan_equal: true,
+        ..EqOptions::default()
+    };
+    assert!(parse("[NaN]").approx_eq(&parse("[NaN]"), &nan));
+    assert!(!parse("[NaN]").approx_eq(&parse("[1]"), &nan));
+
+    let unordered = EqOptions {
+        unordered_arrays: true,
+        ..EqOptions::default()
+    };
+    assert!(parse("[1, [2, 3], 1]").approx_eq(&parse("[[3, 2], 1, 1]"), &unordered));
+    assert!(!parse("[1, 1, 2]").approx_eq(&parse("[1, 2, 2]"), &unordered));
+
+    let ignored = EqOptions {
+        ignored: vec!["/meta/time".parse().unwrap(), "/items/1".parse().unwrap()],
+        ..EqOptions::default()
+    };
+    assert!(parse("{ meta: { time: 1, v: 2 }, items: [1, 2] }")
+        .approx_eq(&parse("{ meta: { v: 2 }, items: [1, 3] }"), &ignored));
+    assert!(!parse("{ meta: { time: 1, v: 2 } }").approx_eq(&parse("{ meta: { v: 3 } }"), &ignored));
+}
+
+#[test]
+#[cfg(feature = "values")]
+fn try_index() {
+    use json5::IndexError;
+
+    let value = json5::parse("{ servers: [{ host: 'a' }], port: 80 }").unwrap();
+
+    assert_eq!(
+        value
+            .try_index("servers")
+            .and_then(|s| s.try_index(0))
+            .and_then(|s| s.try_index("host")),
+        Ok(&Value::String("a".to_string()))
+    );
+    assert_eq!(value.try_index("port".to_string()), Ok(&Value::Integer(80)));
+    assert_eq!(
+        value.try_index("host"),
+        Err(IndexError::MissingKey("host".to_string()))
+    );
+    assert_eq!(
+        value["servers"].try_index(1),
+        Err(IndexError::OutOfBounds { index: 1, len: 1 })
+    );
+    let error = value["port"].try_index("x").unwrap_err();
+    assert_eq!(
+        error,
+        IndexError::WrongType {
+            expected: "object",
+            found: "number"
+        }
+    );
+    assert_eq!(error.to_string(), "expected object, found number");
+    assert_eq!(
+        value.try_index(0).unwrap_err().to_string(),
+        "expected array, found object"
+    );
+    assert_eq!(Value::Null.type_name(), "null");
+}
+
+static FALLBACK: Value = Value::integer(30);
+
+#[test]
+fn const_constructors() {
+    const DEFAULTS: [Value; 6] = [
+        Value::null(),
+        Value::bool(true),
+        Value::integer(-3),
+        Value::float(0.5),
+        Value::empty_string(),
+        Value::empty_array(),
+    ];
+    assert_eq!(
+        DEFAULTS,
+        [
+            Value::Null,
+            Value::Boolean(true),
+            Value::Integer(-3),
+            Value::Float(0.5),
+            Value::String(String::new()),
+            Value::Array(vec![]),
+        ]
+    );
+    assert_eq!(FALLBACK, Value::Integer(30));
+
+    const LOOSE: KeyMatching = KeyMatching::loose();
+    assert_eq!(
+        LOOSE,
+        KeyMatching {
+            ignore_case: true,
+            ignore_separators: true,
+        }
+    );
+}
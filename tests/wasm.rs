@@ -0,0 +1,33 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+extern crate json5;
+
+use json5::Value;
+use std::convert::TryFrom;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn value_to_js_and_back() {
+    let value = json5::parse("{ 'a': [1, 2.5, 'three', null, true], 'b': {} }").unwrap();
+    let js = JsValue::from(value.clone());
+
+    assert_eq!(Value::try_from(js), Ok(value));
+}
+
+#[wasm_bindgen_test]
+fn stringify_js_value() {
+    let js = json5::parse_js("{ a: [NaN, -0.0, 1, 'x'] }").unwrap();
+
+    assert_eq!(
+        json5::stringify_js(js),
+        Ok(r#"{"a":[NaN,-0.0,1,"x"]}"#.to_string())
+    );
+    let function = js_sys::Function::new_no_args("");
+    assert!(json5::stringify_js(function.into()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn parse_js_error() {
+    assert!(json5::parse_js("{ a: }").is_err());
+}
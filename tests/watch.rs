@@ -0,0 +1,54 @@
+#![cfg(feature = "notify")]
+
+extern crate json5;
+
+use std::fs;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use json5::{Value, WatchError};
+
+#[test]
+fn it_works() {
+    let dir = std::env::temp_dir().join(format!("json5-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.json5");
+    fs::write(&path, "{ 'port': 80 }").unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    let watcher = json5::watch(&path, move |result| {
+        let _ = tx.send(result.cloned().map_err(|e| e.to_string()));
+    })
+    .unwrap();
+    let next = || rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(next().unwrap()["port"], Value::Integer(80));
+
+    fs::write(&path, "{ 'port': 81 }").unwrap();
+    assert_eq!(next().unwrap()["port"], Value::Integer(81));
+
+    fs::write(&path, "{ 'port': ").unwrap();
+    assert_eq!(
+        next().unwrap_err(),
+        "unexpected end of JSON, expected a value, line 1 column 11"
+    );
+    assert_eq!(watcher.current().unwrap()["port"], Value::Integer(81));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_file() {
+    let (tx, rx) = mpsc::channel();
+    let watcher = json5::watch("missing.json5", move |result| {
+        let _ = tx.send(matches!(result, Err(WatchError::Io(_))));
+    })
+    .unwrap();
+
+    assert!(rx.recv().unwrap());
+    assert!(watcher.current().is_none());
+    assert_eq!(
+        WatchError::Parse(json5::parse("x").unwrap_err()).to_string(),
+        "unexpected character `x`, expected a value, line 1 column 1"
+    );
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "std")]
+
+extern crate json5;
+
+use std::io::ErrorKind;
+
+use json5::{parse, Json5Writer, Value};
+
+#[test]
+fn it_works() {
+    let mut writer = Json5Writer::new(Vec::new());
+    writer.comment("generated").unwrap();
+    writer.begin_object().unwrap();
+    writer.key("name").unwrap();
+    writer.value(&Value::String("app".to_string())).unwrap();
+    writer.comment("one per region\nkeep sorted").unwrap();
+    writer.key("servers").unwrap();
+    writer.begin_array().unwrap();
+    for port in [80, 81] {
+        writer.begin_object().unwrap();
+        writer.key("port").unwrap();
+        writer.value(&Value::Integer(port)).unwrap();
+        writer.end_object().unwrap();
+    }
+    writer.value(&parse("{ a: [1] }").unwrap()).unwrap();
+    writer.end_array().unwrap();
+    writer.key("empty").unwrap();
+    writer.begin_array().unwrap();
+    writer.end_array().unwrap();
+    writer.end_object().unwrap();
+    let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    assert_eq!(
+        out,
+        r#"// generated
+{
+  "name": "app",
+  // one per region
+  // keep sorted
+  "servers": [
+    {
+      "port": 80
+    },
+    {
+      "port": 81
+    },
+    {"a":[1]}
+  ],
+  "empty": []
+}
+"#
+    );
+    assert_eq!(
+        parse(&out).unwrap(),
+        parse("{ name: 'app', servers: [{ port: 80 }, { port: 81 }, { a: [1] }], empty: [] }")
+            .unwrap()
+    );
+
+    let options = json5::StringifyOptions {
+        line_ending: json5::LineEnding::CrLf,
+        ..json5::StringifyOptions::default()
+    };
+    let mut writer = Json5Writer::with_options(Vec::new(), options);
+    writer.comment("a\r\nb").unwrap();
+    writer.begin_array().unwrap();
+    writer.value(&Value::Null).unwrap();
+    writer.end_array().unwrap();
+    let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(out, "// a\r\n// b\r\n[\r\n  null\r\n]\r\n");
+
+    let options = json5::StringifyOptions {
+        trailing_commas: json5::TrailingCommas::Multiline,
+        ..json5::StringifyOptions::default()
+    };
+    let mut writer = Json5Writer::with_options(Vec::new(), options);
+    writer.begin_array().unwrap();
+    writer.value(&Value::Null).unwrap();
+    writer.comment("end").unwrap();
+    writer.begin_object().unwrap();
+    writer.end_object().unwrap();
+    writer.end_array().unwrap();
+    let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(out, "[\n  null,\n  // end\n  {},\n]\n");
+}
+
+#[test]
+fn misuse() {
+    let error = |f: &dyn Fn(&mut Json5Writer<Vec<u8>>) -> std::io::Result<()>| {
+        let mut writer = Json5Writer::new(Vec::new());
+        f(&mut writer).unwrap_err().kind()
+    };
+
+    assert_eq!(error(&|w| w.key("a")), ErrorKind::InvalidInput);
+    assert_eq!(
+        error(&|w| {
+            w.begin_object()?;
+            w.value(&Value::Null)
+        }),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        error(&|w| {
+            w.begin_array()?;
+            w.end_object()
+        }),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        error(&|w| {
+            w.value(&Value::Null)?;
+            w.value(&Value::Null)
+        }),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        error(&|w| {
+            w.begin_object()?;
+            w.key("a")?;
+            w.comment("x")
+        }),
+        ErrorKind::InvalidInput
+    );
+
+    let mut writer = Json5Writer::new(Vec::new());
+    writer.begin_array().unwrap();
+    assert_eq!(writer.finish().unwrap_err().kind(), ErrorKind::InvalidInput);
+}
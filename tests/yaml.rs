@@ -0,0 +1,173 @@
+#![cfg(feature = "yaml")]
+
+extern crate json5;
+
+use json5::{parse, ErrorKind, Value};
+
+#[test]
+fn from_yaml() {
+    let yaml = r#"
+# deployment
+name: app
+replicas: 3
+ratio: 0.5
+debug: false
+owner: ~
+ports:
+- 80
+- 443
+env:
+  - name: HOST
+    value: "0.0.0.0"
+  - {name: PORT, value: '8080'}
+tags: [a, b, 'c d']
+description: >
+  folded
+  text
+
+  kept
+script: |-
+  echo hi
+    indented
+defaults: &defaults
+  retries: 2
+service:
+  defaults: *defaults
+  id: !!str 007
+  long: plain scalar
+    on two lines
+"#;
+    let expected = parse(
+        r#"{
+            name: 'app', replicas: 3, ratio: 0.5, debug: false, owner: null,
+            ports: [80, 443],
+            env: [{ name: 'HOST', value: '0.0.0.0' }, { name: 'PORT', value: '8080' }],
+            tags: ['a', 'b', 'c d'],
+            description: 'folded text\nkept\n',
+            script: 'echo hi\n  indented',
+            defaults: { retries: 2 },
+            service: { defaults: { retries: 2 }, id: '007', long: 'plain scalar on two lines' },
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(Value::from_yaml_str(yaml), Ok(expected));
+    assert_eq!(Value::from_yaml_str(""), Ok(Value::Null));
+    assert_eq!(Value::from_yaml_str("--- 42\n..."), Ok(Value::Integer(42)));
+    assert_eq!(
+        Value::from_yaml_str("- .inf\n- 0x1F\n- -1e3\n- \"\\u00e9\\t\""),
+        Ok(Value::Array(vec![
+            Value::Float(f64::INFINITY),
+            Value::Integer(31),
+            Value::Float(-1000.0),
+            Value::String("\u{e9}\t".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn unsupported() {
+    let error = |yaml| Value::from_yaml_str(yaml).unwrap_err().to_string();
+
+    assert_eq!(
+        error("a: 1\nb:\n  <<: *x\n"),
+        "merge keys are not supported, line 3 column 3"
+    );
+    assert_eq!(
+        error("a: !foo 1"),
+        "unsupported tag `!foo`, line 1 column 4"
+    );
+    assert_eq!(
+        error("a: !!int x"),
+        "`x` is not a valid !!int, line 1 column 10"
+    );
+    assert_eq!(
+        error("? a\n: b"),
+        "complex keys are not supported, line 1 column 1"
+    );
+    assert_eq!(error("a: *b"), "unknown anchor `b`, line 1 column 4");
+    assert_eq!(
+        error("a\n---\nb"),
+        "multiple documents are not supported, line 2 column 1"
+    );
+    assert_eq!(
+        error("a: b: c"),
+        "mapping values are not allowed here, line 1 column 4"
+    );
+    assert_eq!(
+        Value::from_yaml_str("a: 1\na: 2").unwrap_err().kind(),
+        &ErrorKind::DuplicateKey("a".to_string())
+    );
+    assert_eq!(
+        Value::from_yaml_str("a: 99999999999").unwrap_err().kind(),
+        &ErrorKind::NumberOverflow
+    );
+    assert_eq!(
+        Value::from_yaml_str(&"[".repeat(10000)).unwrap_err().kind(),
+        &ErrorKind::ExceededMaxDepth
+    );
+    assert_eq!(
+        Value::from_yaml_str(&"- ".repeat(10000))
+            .unwrap_err()
+            .kind(),
+        &ErrorKind::ExceededMaxDepth
+    );
+}
+
+#[test]
+fn alias_bomb() {
+    let yaml = r#"
+a: &a ["lol", "lol", "lol", "lol", "lol", "lol", "lol", "lol", "lol"]
+b: &b [*a, *a, *a, *a, *a, *a, *a, *a, *a]
+c: &c [*b, *b, *b, *b, *b, *b, *b, *b, *b]
+d: &d [*c, *c, *c, *c, *c, *c, *c, *c, *c]
+e: &e [*d, *d, *d, *d, *d, *d, *d, *d, *d]
+f: &f [*e, *e, *e, *e, *e, *e, *e, *e, *e]
+g: &g [*f, *f, *f, *f, *f, *f, *f, *f, *f]
+h: &h [*g, *g, *g, *g, *g, *g, *g, *g, *g]
+i: &i [*h, *h, *h, *h, *h, *h, *h, *h, *h]
+"#;
+    let error = Value::from_yaml_str(yaml).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::BudgetExceeded);
+    assert_eq!(error.span().unwrap().start.line, 8);
+
+    let value = Value::from_yaml_str(
+        "a: &a [1, 2]
+b: [*a, *a]",
+    )
+    .unwrap();
+    assert_eq!(value["b"][1], value["a"]);
+}
+
+#[test]
+fn round_trip() {
+    let value = parse(
+        r#"{
+            name: 'app',
+            empty: '',
+            numbers: [1, -2.5, 1e100, NaN, -Infinity],
+            strings: ['true', '123', 'a: b', '- x', 'line\nbreak', ' padded ', '...', 'é'],
+            nested: [[1, [2]], { a: { b: [] } }, {}],
+            'key with: colon': null,
+        }"#,
+    )
+    .unwrap();
+    let yaml = value.to_yaml_string();
+
+    assert!(yaml.lines().any(|line| line == "empty: \"\""), "{}", yaml);
+    assert!(
+        yaml.contains("nested:\n  - - 1\n    - - 2\n  - a:\n      b: []\n  - {}\n"),
+        "{}",
+        yaml
+    );
+
+    let back = Value::from_yaml_str(&yaml).unwrap();
+    assert_eq!(back["strings"], value["strings"]);
+    assert_eq!(back["nested"], value["nested"]);
+    assert_eq!(back["key with: colon"], Value::Null);
+    assert!(back["numbers"][3].to_f64().unwrap().is_nan());
+    assert_eq!(back["numbers"][4], Value::Float(f64::NEG_INFINITY));
+
+    assert_eq!(Value::String("x".to_string()).to_yaml_string(), "x\n");
+    assert_eq!(Value::Array(vec![]).to_yaml_string(), "[]\n");
+}